@@ -1,6 +1,7 @@
 //! # MTD Benchmark
 //!
 //! Measures MTD system performance.
+#![allow(deprecated)]
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use zkmtd::batching::{create_proof_batch, BatchVerifier};
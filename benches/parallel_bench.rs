@@ -0,0 +1,85 @@
+//! # Parallel Benchmark
+//!
+//! Measures batch proving, batch verification and Merkle tree construction
+//! on thread pools of different sizes (requires the `parallel` feature).
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use zkmtd::batching::merkle::hash_leaf;
+use zkmtd::batching::{BatchStatement, MerkleTree};
+use zkmtd::stark::integrated::IntegratedProver;
+use zkmtd::{Epoch, IntegratedBatch, IntegratedBatchVerifier};
+
+const THREADS: [usize; 3] = [1, 2, 4];
+
+fn pool(threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap()
+}
+
+fn statements(count: u64) -> Vec<(BatchStatement, [u8; 32])> {
+    (0..count)
+        .map(|i| {
+            let statement = BatchStatement::Sum {
+                a: vec![i, i + 1, i + 2, i + 3],
+                b: vec![1, 2, 3, 4],
+            };
+            (statement, [i as u8; 32])
+        })
+        .collect()
+}
+
+fn bench_parallel_batch_proving(c: &mut Criterion) {
+    let prover = IntegratedProver::new(b"parallel-benchmark-seed", Epoch::new(1000)).unwrap();
+    let statements = statements(16);
+
+    let mut group = c.benchmark_group("parallel_batch_proving");
+    group.sample_size(10);
+    for threads in THREADS {
+        let pool = pool(threads);
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, _| {
+            b.iter(|| {
+                pool.install(|| IntegratedBatch::prove(&prover, black_box(&statements)).unwrap())
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_parallel_batch_verification(c: &mut Criterion) {
+    let prover = IntegratedProver::new(b"parallel-benchmark-seed", Epoch::new(1000)).unwrap();
+    let batch = IntegratedBatch::prove(&prover, &statements(16)).unwrap();
+    let verifier = IntegratedBatchVerifier::new(prover.get_verifier());
+
+    let mut group = c.benchmark_group("parallel_batch_verification");
+    group.sample_size(10);
+    for threads in THREADS {
+        let pool = pool(threads);
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, _| {
+            b.iter(|| pool.install(|| verifier.verify_batch(black_box(&batch)).unwrap()));
+        });
+    }
+    group.finish();
+}
+
+fn bench_parallel_merkle_tree(c: &mut Criterion) {
+    let leaves: Vec<_> = (0..4096u32).map(|i| hash_leaf(&i.to_le_bytes())).collect();
+
+    let mut group = c.benchmark_group("parallel_merkle_tree_4096");
+    for threads in THREADS {
+        let pool = pool(threads);
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, _| {
+            b.iter(|| pool.install(|| MerkleTree::new(black_box(leaves.clone())).unwrap()));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_parallel_batch_proving,
+    bench_parallel_batch_verification,
+    bench_parallel_merkle_tree
+);
+criterion_main!(benches);
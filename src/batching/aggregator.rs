@@ -8,10 +8,13 @@ use crate::core::types::{Proof, ProofBatch, PublicInputs, Witness};
 use crate::mtd::Epoch;
 use crate::stark::MTDProver;
 use crate::utils::constants::MAX_BATCH_SIZE;
+#[cfg(feature = "alloc")]
+use crate::utils::parallel;
 
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+#[deprecated(since = "0.2.0", note = "Use IntegratedBatch for production")]
 #[derive(Debug)]
 pub struct BatchProver {
     prover: MTDProver,
@@ -80,14 +83,11 @@ impl BatchProverTrait for BatchProver {
             });
         }
 
-        // Generate proof for each witness
-        let mut proofs = Vec::with_capacity(witnesses.len());
-        for (witness, inputs) in witnesses.iter().zip(public_inputs.iter()) {
-            let proof = self.prover.prove(witness, inputs)?;
-            proofs.push(proof);
-        }
-
-        Ok(proofs)
+        // Generate proof for each witness (concurrently with `parallel`;
+        // results stay in witness order), stopping at the first error
+        parallel::try_map_indices(witnesses.len(), |i| {
+            self.prover.prove(&witnesses[i], &public_inputs[i])
+        })
     }
 }
 
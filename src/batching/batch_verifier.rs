@@ -1,16 +1,19 @@
 //! BatchVerifier - verifies proof batches with Merkle root and epoch checks
 #![allow(deprecated)]
 
-use crate::batching::merkle::{hash_leaf, MerkleTree};
+use crate::batching::merkle::{hash_leaf, MerklePath, MerkleTree};
 use crate::core::errors::{Result, ZKMTDError};
 use crate::core::traits::Verifier;
 use crate::core::types::{Proof, ProofBatch, PublicInputs};
 use crate::stark::prover::MTDVerifier as MTDVerifierInner;
 use crate::utils::hash::constant_time_eq_fixed;
+#[cfg(feature = "alloc")]
+use crate::utils::parallel;
 
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+#[deprecated(since = "0.2.0", note = "Use IntegratedBatch for production")]
 #[derive(Debug, Clone)]
 pub struct BatchVerifier {
     verifier: MTDVerifierInner,
@@ -60,17 +63,47 @@ impl BatchVerifier {
             return Ok(false);
         }
 
-        // 4. Individual verification of each proof
-        for (proof, inputs) in batch.proofs.iter().zip(public_inputs.iter()) {
-            let is_valid = self.verifier.verify(proof, inputs)?;
-            if !is_valid {
-                return Ok(false);
-            }
+        // 4. Individual verification of each proof (concurrently with
+        // `parallel`), stopping at the first failure in batch order
+        parallel::try_all_indices(batch.proofs.len(), |i| {
+            self.verifier.verify(&batch.proofs[i], &public_inputs[i])
+        })
+    }
+
+    /// Verify one proof of a batch from its inclusion path, in O(log n) and
+    /// without the rest of the batch. `trusted_root`, `batch_epoch` and
+    /// `num_leaves` must come from a trusted batch header, not from the path.
+    #[cfg(feature = "alloc")]
+    pub fn verify_included(
+        &self,
+        proof: &Proof,
+        public_inputs: &PublicInputs,
+        path: &MerklePath,
+        trusted_root: &[u8; 32],
+        batch_epoch: u64,
+        num_leaves: usize,
+    ) -> Result<bool> {
+        if proof.epoch != batch_epoch {
+            return Err(ZKMTDError::InvalidEpoch {
+                current: batch_epoch,
+                reason: alloc::format!(
+                    "Proof Epoch does not match batch Epoch: {} != {}",
+                    proof.epoch,
+                    batch_epoch
+                ),
+            });
         }
 
-        Ok(true)
+        if !path.verify_in_tree(&hash_leaf(&proof.data), trusted_root, num_leaves) {
+            return Ok(false);
+        }
+
+        self.verifier.verify(proof, public_inputs)
     }
 
+    /// Verify one proof of a batch held in full. Rebuilds the tree to find
+    /// the path; callers with a path should use
+    /// [`verify_included`](Self::verify_included) instead.
     #[cfg(feature = "alloc")]
     pub fn verify_single_in_batch(
         &self,
@@ -210,6 +243,61 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_batch_verifier_included_with_path() {
+        let seed = b"test-seed";
+        let config = StarkConfig::for_testing();
+        let epoch = Epoch::new(100);
+
+        let prover = BatchProver::with_epoch(seed, config, epoch).unwrap();
+        let verifier = BatchVerifier::new(prover.inner_prover().get_verifier());
+
+        let witnesses = vec![
+            Witness::new(vec![1, 2, 3, 4, 5, 6, 7, 8]),
+            Witness::new(vec![9, 10, 11, 12, 13, 14, 15, 16]),
+            Witness::new(vec![17, 18, 19, 20, 21, 22, 23, 24]),
+        ];
+        let inputs = vec![
+            PublicInputs::new(vec![42]),
+            PublicInputs::new(vec![43]),
+            PublicInputs::new(vec![44]),
+        ];
+
+        let proofs = prover.prove_batch(&witnesses, &inputs).unwrap();
+        let batch = create_proof_batch(proofs, epoch.value()).unwrap();
+        let leaves = batch.proofs.iter().map(|p| hash_leaf(&p.data)).collect();
+        let tree = MerkleTree::new(leaves).unwrap();
+
+        // Only the proof, its path and the batch header are needed
+        let root = batch.merkle_root;
+        for (i, input) in inputs.iter().enumerate() {
+            let path = tree.get_proof(i).unwrap();
+            let proof = &batch.proofs[i];
+            assert!(verifier
+                .verify_included(proof, input, &path, &root, epoch.value(), 3)
+                .unwrap());
+        }
+
+        let path = tree.get_proof(0).unwrap();
+        let proof = &batch.proofs[0];
+        // Wrong proof for the path, wrong leaf count, wrong root
+        assert!(!verifier
+            .verify_included(&batch.proofs[1], &inputs[1], &path, &root, epoch.value(), 3)
+            .unwrap());
+        assert!(!verifier
+            .verify_included(proof, &inputs[0], &path, &root, epoch.value(), 4)
+            .unwrap());
+        assert!(!verifier
+            .verify_included(proof, &inputs[0], &path, &[0u8; 32], epoch.value(), 3)
+            .unwrap());
+        // Proof from another epoch
+        assert!(matches!(
+            verifier.verify_included(proof, &inputs[0], &path, &root, 101, 3),
+            Err(ZKMTDError::InvalidEpoch { .. })
+        ));
+    }
+
     #[cfg(feature = "alloc")]
     #[test]
     fn test_batch_verifier_mismatched_lengths() {
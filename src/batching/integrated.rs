@@ -0,0 +1,513 @@
+//! IntegratedBatch - real STARK proofs of one epoch under a Merkle root
+//!
+//! Leaves are `hash_leaf` over each proof's canonical encoding
+//! ([`IntegratedProof::to_bytes`]), so the root commits to every byte a
+//! verifier checks. A holder of the root can accept a single proof with its
+//! inclusion path without seeing the rest of the batch.
+
+use crate::batching::merkle::{hash_leaf, MerklePath, MerkleTree};
+use crate::batching::report::{BatchReport, FailurePolicy, ProofStatus};
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+use crate::mtd::Epoch;
+use crate::stark::integrated::{IntegratedProof, IntegratedProver, IntegratedVerifier};
+use crate::utils::constants::MAX_BATCH_SIZE;
+use crate::utils::hash::constant_time_eq_fixed;
+use crate::utils::parallel;
+
+use alloc::vec::Vec;
+
+/// One statement to prove into an [`IntegratedBatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchStatement {
+    Fibonacci { num_rows: usize },
+    Sum { a: Vec<u64>, b: Vec<u64> },
+    Multiplication { a: Vec<u64>, b: Vec<u64> },
+    Range { value: u64, threshold: u64 },
+}
+
+impl BatchStatement {
+    fn prove(&self, prover: &IntegratedProver, pv_salt: [u8; 32]) -> Result<IntegratedProof> {
+        match self {
+            Self::Fibonacci { num_rows } => prover.prove_fibonacci(*num_rows, pv_salt),
+            Self::Sum { a, b } => prover.prove_sum(a, b, pv_salt),
+            Self::Multiplication { a, b } => prover.prove_multiplication(a, b, pv_salt),
+            Self::Range { value, threshold } => prover.prove_range(*value, *threshold, pv_salt),
+        }
+    }
+}
+
+/// Merkle leaf for one proof.
+pub fn proof_leaf(proof: &IntegratedProof) -> Result<HashDigest> {
+    Ok(hash_leaf(&proof.to_bytes()?))
+}
+
+fn proof_tree(proofs: &[IntegratedProof]) -> Result<MerkleTree> {
+    let leaves = parallel::map(proofs, proof_leaf)
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    MerkleTree::new(leaves)
+}
+
+#[derive(Debug)]
+pub struct IntegratedBatch {
+    pub proofs: Vec<IntegratedProof>,
+    pub merkle_root: HashDigest,
+    pub epoch: Epoch,
+    /// Tree over `proofs` as bundled, kept to serve inclusion paths.
+    tree: MerkleTree,
+}
+
+impl IntegratedBatch {
+    /// Bundle proofs that all carry the same epoch.
+    pub fn new(proofs: Vec<IntegratedProof>) -> Result<Self> {
+        let epoch = match proofs.first() {
+            Some(proof) => proof.epoch,
+            None => {
+                return Err(ZKMTDError::BatchError {
+                    reason: "Proofs are empty".into(),
+                })
+            }
+        };
+        if proofs.len() > MAX_BATCH_SIZE {
+            return Err(ZKMTDError::BatchError {
+                reason: alloc::format!(
+                    "Batch size exceeds maximum: {} > {}",
+                    proofs.len(),
+                    MAX_BATCH_SIZE
+                ),
+            });
+        }
+        if let Some(proof) = proofs.iter().find(|p| p.epoch != epoch) {
+            return Err(ZKMTDError::BatchError {
+                reason: alloc::format!(
+                    "Proof Epoch mismatch: {} != {}",
+                    proof.epoch.value(),
+                    epoch.value()
+                ),
+            });
+        }
+
+        let tree = proof_tree(&proofs)?;
+        Ok(Self {
+            proofs,
+            merkle_root: *tree.root(),
+            epoch,
+            tree,
+        })
+    }
+
+    /// Prove each `(statement, pv_salt)` with `prover` and bundle the results.
+    pub fn prove(
+        prover: &IntegratedProver,
+        statements: &[(BatchStatement, [u8; 32])],
+    ) -> Result<Self> {
+        if statements.len() > MAX_BATCH_SIZE {
+            return Err(ZKMTDError::BatchError {
+                reason: alloc::format!(
+                    "Batch size exceeds maximum: {} > {}",
+                    statements.len(),
+                    MAX_BATCH_SIZE
+                ),
+            });
+        }
+        let proofs = parallel::try_map_indices(statements.len(), |i| {
+            let (statement, pv_salt) = &statements[i];
+            statement.prove(prover, *pv_salt)
+        })?;
+        Self::new(proofs)
+    }
+
+    pub fn root(&self) -> &HashDigest {
+        &self.merkle_root
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// Path proving `proofs[index]` (as bundled) is under the batch root.
+    pub fn inclusion_path(&self, index: usize) -> Result<MerklePath> {
+        self.tree.get_proof(index)
+    }
+}
+
+/// Verifies [`IntegratedBatch`]es and single batched proofs for one epoch.
+#[derive(Debug)]
+pub struct IntegratedBatchVerifier {
+    verifier: IntegratedVerifier,
+}
+
+impl IntegratedBatchVerifier {
+    pub fn new(verifier: IntegratedVerifier) -> Self {
+        Self { verifier }
+    }
+
+    pub fn inner_verifier(&self) -> &IntegratedVerifier {
+        &self.verifier
+    }
+
+    /// Checks epoch consistency, the Merkle root, then every proof.
+    pub fn verify_batch(&self, batch: &IntegratedBatch) -> Result<bool> {
+        if batch.is_empty() {
+            return Err(ZKMTDError::BatchError {
+                reason: "Batch is empty".into(),
+            });
+        }
+
+        for proof in &batch.proofs {
+            if proof.epoch != batch.epoch {
+                return Err(ZKMTDError::InvalidEpoch {
+                    current: batch.epoch.value(),
+                    reason: alloc::format!(
+                        "Proof Epoch does not match batch Epoch: {} != {}",
+                        proof.epoch.value(),
+                        batch.epoch.value()
+                    ),
+                });
+            }
+        }
+
+        let computed_root = *proof_tree(&batch.proofs)?.root();
+        if !constant_time_eq_fixed(&computed_root, &batch.merkle_root) {
+            return Ok(false);
+        }
+
+        // Stops at the first failure in batch order, so the outcome
+        // (including which error surfaces) does not depend on the `parallel`
+        // feature
+        parallel::try_all_indices(batch.proofs.len(), |i| {
+            self.verifier.verify(&batch.proofs[i])
+        })
+    }
+
+    /// Per-proof outcome of [`verify_batch`](Self::verify_batch): the root
+    /// check is reported in `root_valid`, and each proof's epoch, binding
+    /// and STARK checks in its status.
+    pub fn verify_batch_report(
+        &self,
+        batch: &IntegratedBatch,
+        policy: FailurePolicy,
+    ) -> Result<BatchReport> {
+        if batch.is_empty() {
+            return Err(ZKMTDError::BatchError {
+                reason: "Batch is empty".into(),
+            });
+        }
+
+        let leaves: Vec<Option<HashDigest>> = parallel::map(&batch.proofs, |p| proof_leaf(p).ok());
+        let root_valid = match leaves.iter().copied().collect::<Option<Vec<_>>>() {
+            Some(leaves) => {
+                let computed_root = *MerkleTree::new(leaves)?.root();
+                constant_time_eq_fixed(&computed_root, &batch.merkle_root)
+            }
+            None => false,
+        };
+
+        Ok(BatchReport::collect(
+            batch.len(),
+            Some(root_valid),
+            policy,
+            |i| {
+                let proof = &batch.proofs[i];
+                if leaves[i].is_none() {
+                    ProofStatus::Malformed
+                } else if proof.epoch != batch.epoch {
+                    ProofStatus::EpochMismatch {
+                        expected: batch.epoch.value(),
+                        got: proof.epoch.value(),
+                    }
+                } else {
+                    self.verifier.diagnose(proof)
+                }
+            },
+        ))
+    }
+
+    /// Per-proof outcome of [`verify_single`](Self::verify_single) over
+    /// proofs collected with their inclusion paths.
+    pub fn verify_included_report(
+        &self,
+        items: &[(&IntegratedProof, &MerklePath)],
+        trusted_root: &HashDigest,
+        policy: FailurePolicy,
+    ) -> BatchReport {
+        BatchReport::collect(items.len(), None, policy, |i| {
+            let (proof, path) = items[i];
+            match proof_leaf(proof) {
+                Err(_) => ProofStatus::Malformed,
+                Ok(leaf) if !path.verify_against(&leaf, trusted_root) => {
+                    ProofStatus::MerkleMismatch
+                }
+                Ok(_) => self.verifier.diagnose(proof),
+            }
+        })
+    }
+
+    /// Verify one proof against a trusted batch root; the rest of the batch
+    /// is not needed.
+    pub fn verify_single(
+        &self,
+        proof: &IntegratedProof,
+        path: &MerklePath,
+        trusted_root: &HashDigest,
+    ) -> Result<bool> {
+        let leaf = proof_leaf(proof)?;
+        if !path.verify_against(&leaf, trusted_root) {
+            return Ok(false);
+        }
+        self.verifier.verify(proof)
+    }
+
+    /// [`verify_single`](Self::verify_single) for light clients holding only
+    /// a batch header: also checks the proof's epoch and that `path` is for a
+    /// tree of `num_leaves` leaves (in range, full depth). O(log n).
+    pub fn verify_included(
+        &self,
+        proof: &IntegratedProof,
+        path: &MerklePath,
+        trusted_root: &HashDigest,
+        batch_epoch: Epoch,
+        num_leaves: usize,
+    ) -> Result<bool> {
+        if proof.epoch != batch_epoch {
+            return Err(ZKMTDError::InvalidEpoch {
+                current: batch_epoch.value(),
+                reason: alloc::format!(
+                    "Proof Epoch does not match batch Epoch: {} != {}",
+                    proof.epoch.value(),
+                    batch_epoch.value()
+                ),
+            });
+        }
+        let leaf = proof_leaf(proof)?;
+        if !path.verify_in_tree(&leaf, trusted_root, num_leaves) {
+            return Ok(false);
+        }
+        self.verifier.verify(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn prover() -> IntegratedProver {
+        IntegratedProver::new(b"integrated-batch-seed", Epoch::new(300)).unwrap()
+    }
+
+    fn statements() -> Vec<(BatchStatement, [u8; 32])> {
+        vec![
+            (BatchStatement::Fibonacci { num_rows: 8 }, [1u8; 32]),
+            (
+                BatchStatement::Sum {
+                    a: vec![1, 2, 3, 4],
+                    b: vec![5, 6, 7, 8],
+                },
+                [2u8; 32],
+            ),
+            (
+                BatchStatement::Multiplication {
+                    a: vec![2, 3, 4, 5],
+                    b: vec![6, 7, 8, 9],
+                },
+                [3u8; 32],
+            ),
+            (
+                BatchStatement::Range {
+                    value: 42,
+                    threshold: 18,
+                },
+                [4u8; 32],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_integrated_batch_verify() {
+        let prover = prover();
+        let batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        assert_eq!(batch.len(), 4);
+        assert_eq!(batch.epoch, Epoch::new(300));
+
+        let verifier = IntegratedBatchVerifier::new(prover.get_verifier());
+        assert!(verifier.verify_batch(&batch).unwrap());
+
+        // Another epoch's verifier rejects the batch
+        let other = IntegratedProver::new(b"integrated-batch-seed", Epoch::new(301)).unwrap();
+        let verifier = IntegratedBatchVerifier::new(other.get_verifier());
+        assert!(!verifier.verify_batch(&batch).unwrap_or(false));
+    }
+
+    #[test]
+    fn test_integrated_batch_single_inclusion() {
+        let prover = prover();
+        let batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        let verifier = IntegratedBatchVerifier::new(prover.get_verifier());
+
+        for (i, proof) in batch.proofs.iter().enumerate() {
+            let path = batch.inclusion_path(i).unwrap();
+            assert!(verifier.verify_single(proof, &path, batch.root()).unwrap());
+        }
+
+        // Wrong path, or a proof that is not in the batch
+        let path = batch.inclusion_path(0).unwrap();
+        assert!(!verifier
+            .verify_single(&batch.proofs[1], &path, batch.root())
+            .unwrap());
+        let outsider = prover.prove_fibonacci(8, [9u8; 32]).unwrap();
+        assert!(!verifier
+            .verify_single(&outsider, &path, batch.root())
+            .unwrap());
+    }
+
+    #[test]
+    fn test_integrated_batch_verify_included() {
+        let prover = prover();
+        let verifier = IntegratedBatchVerifier::new(prover.get_verifier());
+        let batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        let (root, epoch, n) = (*batch.root(), batch.epoch, batch.len());
+
+        for (i, proof) in batch.proofs.iter().enumerate() {
+            let path = batch.inclusion_path(i).unwrap();
+            assert!(verifier
+                .verify_included(proof, &path, &root, epoch, n)
+                .unwrap());
+        }
+
+        let path = batch.inclusion_path(0).unwrap();
+        let proof = &batch.proofs[0];
+        assert!(!verifier
+            .verify_included(proof, &path, &root, epoch, n + 1)
+            .unwrap());
+        let mut shifted = path.clone();
+        shifted.leaf_index += 1 << path.len();
+        assert!(!verifier
+            .verify_included(proof, &shifted, &root, epoch, n)
+            .unwrap());
+        assert!(matches!(
+            verifier.verify_included(proof, &path, &root, Epoch::new(301), n),
+            Err(ZKMTDError::InvalidEpoch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_integrated_batch_tampering() {
+        let prover = prover();
+        let verifier = IntegratedBatchVerifier::new(prover.get_verifier());
+
+        let mut batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        batch.merkle_root[0] ^= 1;
+        assert!(!verifier.verify_batch(&batch).unwrap());
+
+        let mut batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        batch.proofs.swap(0, 1);
+        assert!(!verifier.verify_batch(&batch).unwrap());
+
+        let mut batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        batch.proofs.pop();
+        assert!(!verifier.verify_batch(&batch).unwrap());
+    }
+
+    #[test]
+    fn test_integrated_batch_report() {
+        let prover = prover();
+        let verifier = IntegratedBatchVerifier::new(prover.get_verifier());
+
+        let batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        let report = verifier
+            .verify_batch_report(&batch, FailurePolicy::StopAtFirst)
+            .unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.valid_count(), 4);
+
+        // Corrupt the binding of proof 1, and swap in a STARK for proof 3 that
+        // has the same public values (so the binding still holds) but was
+        // proven outside this epoch's transcript
+        let mut batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        batch.proofs[1].binding_hash[0] ^= 1;
+        let standalone = crate::stark::RealStarkProver::new(crate::stark::SimpleAir::fibonacci())
+            .unwrap()
+            .prove_range(42, 18)
+            .unwrap();
+        assert_eq!(
+            standalone.public_values,
+            batch.proofs[3].stark_proof.public_values
+        );
+        batch.proofs[3].stark_proof = standalone;
+        let report = verifier
+            .verify_batch_report(&batch, FailurePolicy::Continue)
+            .unwrap();
+        assert_eq!(report.root_valid, Some(false));
+        assert_eq!(report.failed_count(), 2);
+        assert_eq!(
+            report.failures().next(),
+            Some((1, ProofStatus::BindingMismatch))
+        );
+        assert_eq!(report.statuses[3], ProofStatus::StarkFailure);
+
+        let report = verifier
+            .verify_batch_report(&batch, FailurePolicy::StopAtFirst)
+            .unwrap();
+        assert_eq!(report.counts().skipped, 4);
+
+        // Another epoch's verifier names the epoch mismatch
+        let later = IntegratedProver::new(b"integrated-batch-seed", Epoch::new(301)).unwrap();
+        let batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        let report = IntegratedBatchVerifier::new(later.get_verifier())
+            .verify_batch_report(&batch, FailurePolicy::Continue)
+            .unwrap();
+        assert_eq!(report.root_valid, Some(true));
+        assert_eq!(
+            report.statuses[0],
+            ProofStatus::EpochMismatch {
+                expected: 301,
+                got: 300
+            }
+        );
+        assert_eq!(report.counts().epoch_mismatch, 4);
+    }
+
+    #[test]
+    fn test_included_report_merkle_mismatch() {
+        let prover = prover();
+        let verifier = IntegratedBatchVerifier::new(prover.get_verifier());
+        let batch = IntegratedBatch::prove(&prover, &statements()).unwrap();
+        let paths: Vec<_> = (0..batch.len())
+            .map(|i| batch.inclusion_path(i).unwrap())
+            .collect();
+
+        let items = [
+            (&batch.proofs[0], &paths[0]),
+            (&batch.proofs[1], &paths[0]),
+            (&batch.proofs[2], &paths[2]),
+        ];
+        let report = verifier.verify_included_report(&items, batch.root(), FailurePolicy::Continue);
+        assert_eq!(report.root_valid, None);
+        assert_eq!(
+            report.statuses,
+            [
+                ProofStatus::Valid,
+                ProofStatus::MerkleMismatch,
+                ProofStatus::Valid
+            ]
+        );
+    }
+
+    #[test]
+    fn test_integrated_batch_construction_errors() {
+        assert!(IntegratedBatch::new(Vec::new()).is_err());
+
+        let a = prover().prove_fibonacci(8, [1u8; 32]).unwrap();
+        let b = IntegratedProver::new(b"integrated-batch-seed", Epoch::new(301))
+            .unwrap()
+            .prove_fibonacci(8, [1u8; 32])
+            .unwrap();
+        assert!(IntegratedBatch::new(vec![a, b]).is_err());
+    }
+}
@@ -1,16 +1,70 @@
-//! MerkleTree - binary hash tree for batch proof integrity
+//! MerkleTree - 2-, 4- or 8-ary hash tree for batch proof integrity
 
 use crate::core::errors::{Result, ZKMTDError};
 use crate::core::types::HashDigest;
 use crate::utils::constants::{DOMAIN_MERKLE, DOMAIN_MERKLE_NODE};
 use crate::utils::hash::{combine_hashes, poseidon_hash};
+#[cfg(feature = "alloc")]
+use crate::utils::parallel;
 
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Children per internal node. Paths carry `arity - 1` siblings per level,
+/// `(arity - 1)·⌈log_arity n⌉` in total, so binary gives the SMALLEST paths:
+/// for 1,024 leaves that is 10 siblings (320 B) binary, 15 (480 B)
+/// quaternary and 28 (896 B) octal. A higher arity only saves levels, and
+/// each 4- or 8-child node spans several Poseidon2 permutations anyway (56
+/// bytes per absorb), so it does not shrink on-chain paths or verification
+/// cost. Keep [`Binary`](Self::Binary) for Solana and light-client paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum MerkleArity {
+    #[default]
+    Binary = 2,
+    Quaternary = 4,
+    Octal = 8,
+}
+
+impl MerkleArity {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            2 => Some(Self::Binary),
+            4 => Some(Self::Quaternary),
+            8 => Some(Self::Octal),
+            _ => None,
+        }
+    }
+
+    pub fn value(self) -> usize {
+        self as usize
+    }
+
+    /// Number of levels above the leaves in a tree of `num_leaves` leaves.
+    pub fn depth(self, num_leaves: usize) -> usize {
+        let mut depth = 0;
+        let mut capacity = 1usize;
+        while capacity < num_leaves {
+            capacity = capacity.saturating_mul(self.value());
+            depth += 1;
+        }
+        depth
+    }
+
+    /// Number of siblings in an inclusion path for a tree of `num_leaves`
+    /// leaves.
+    pub fn path_len(self, num_leaves: usize) -> usize {
+        self.depth(num_leaves) * (self.value() - 1)
+    }
+}
+
 #[cfg(feature = "alloc")]
 #[derive(Debug, Clone)]
 pub struct MerkleTree {
+    arity: MerkleArity,
     leaves: Vec<HashDigest>,
     levels: Vec<Vec<HashDigest>>,
     root: HashDigest,
@@ -18,7 +72,12 @@ pub struct MerkleTree {
 
 #[cfg(feature = "alloc")]
 impl MerkleTree {
+    /// Binary tree over `leaves`.
     pub fn new(leaves: Vec<HashDigest>) -> Result<Self> {
+        Self::with_arity(leaves, MerkleArity::Binary)
+    }
+
+    pub fn with_arity(leaves: Vec<HashDigest>, arity: MerkleArity) -> Result<Self> {
         if leaves.is_empty() {
             return Err(ZKMTDError::MerkleError {
                 reason: "Leaves are empty".into(),
@@ -31,24 +90,14 @@ impl MerkleTree {
         // Build tree levels
         let mut current_level = leaves.clone();
         while current_level.len() > 1 {
-            let mut next_level = Vec::new();
-
-            let mut i = 0;
-            while i < current_level.len() {
-                if i + 1 < current_level.len() {
-                    // Combine two nodes (internal-node domain, distinct from leaves)
-                    let combined =
-                        combine_hashes(&current_level[i], &current_level[i + 1], DOMAIN_MERKLE_NODE);
-                    next_level.push(combined);
-                    i += 2;
-                } else {
-                    // For odd count, combine last node with itself
-                    let combined =
-                        combine_hashes(&current_level[i], &current_level[i], DOMAIN_MERKLE_NODE);
-                    next_level.push(combined);
-                    i += 1;
-                }
-            }
+            // Combine node groups (internal-node domain, distinct from leaves);
+            // a short last group is padded with its last node
+            let next_level = parallel::map_chunks(&current_level, arity.value(), |group| {
+                let last = group[group.len() - 1];
+                let mut children = group.to_vec();
+                children.resize(arity.value(), last);
+                hash_children(&children)
+            });
 
             levels.push(next_level.clone());
             current_level = next_level;
@@ -58,10 +107,12 @@ impl MerkleTree {
             reason: "Root computation failed".into(),
         })?;
         // RT-2: bind the leaf count into the root so trees of different sizes
-        // (e.g. [A,B,C] vs [A,B,C,C] via odd-node duplication) cannot collide.
-        let root = bind_root(&inner_root, leaves.len());
+        // (e.g. [A,B,C] vs [A,B,C,C] via odd-node duplication) cannot collide;
+        // the arity is bound too, so trees of different arity cannot either.
+        let root = bind_root(&inner_root, leaves.len(), arity);
 
         Ok(Self {
+            arity,
             leaves,
             levels,
             root,
@@ -76,7 +127,10 @@ impl MerkleTree {
         self.leaves.len()
     }
 
-    #[allow(clippy::manual_is_multiple_of)]
+    pub fn arity(&self) -> MerkleArity {
+        self.arity
+    }
+
     pub fn get_proof(&self, index: usize) -> Result<MerklePath> {
         if index >= self.leaves.len() {
             return Err(ZKMTDError::MerkleError {
@@ -84,27 +138,18 @@ impl MerkleTree {
             });
         }
 
+        let arity = self.arity.value();
         let mut siblings = Vec::new();
         let mut current_index = index;
 
-        // Collect sibling nodes at each level
-        for level_idx in 0..(self.levels.len() - 1) {
-            let level = &self.levels[level_idx];
-            let sibling_index = if current_index % 2 == 0 {
-                current_index + 1
-            } else {
-                current_index - 1
-            };
-
-            let sibling = if sibling_index < level.len() {
-                level[sibling_index]
-            } else {
-                // For odd count, use self
-                level[current_index]
-            };
-
-            siblings.push(sibling);
-            current_index /= 2;
+        // Collect the other members of the node's group at each level, left
+        // to right; positions past the end repeat the level's last node
+        for level in &self.levels[..self.levels.len() - 1] {
+            let start = current_index - current_index % arity;
+            for i in (start..start + arity).filter(|&i| i != current_index) {
+                siblings.push(level[i.min(level.len() - 1)]);
+            }
+            current_index /= arity;
         }
 
         Ok(MerklePath {
@@ -112,12 +157,65 @@ impl MerkleTree {
             siblings,
             root: self.root,
             num_leaves: self.leaves.len(),
+            arity: self.arity,
         })
     }
 
     pub fn leaves(&self) -> &[HashDigest] {
         &self.leaves
     }
+
+    /// One proof for several leaves, sharing the siblings their paths have
+    /// in common. Indices may be given in any order; the proof lists them
+    /// ascending. Binary trees only.
+    pub fn get_multi_proof(&self, indices: &[usize]) -> Result<MerkleMultiProof> {
+        if self.arity != MerkleArity::Binary {
+            return Err(ZKMTDError::MerkleError {
+                reason: "Multi-proofs require a binary tree".into(),
+            });
+        }
+        let mut sorted = indices.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.is_empty() {
+            return Err(ZKMTDError::MerkleError {
+                reason: "No leaf indices".into(),
+            });
+        }
+        if let Some(&index) = sorted.iter().find(|&&i| i >= self.leaves.len()) {
+            return Err(ZKMTDError::MerkleError {
+                reason: alloc::format!("Invalid index: {} >= {}", index, self.leaves.len()),
+            });
+        }
+        let num_leaves = u32::try_from(self.leaves.len()).map_err(|_| ZKMTDError::MerkleError {
+            reason: "Tree too large for a multi-proof".into(),
+        })?;
+
+        let mut siblings = Vec::new();
+        let mut known = sorted.clone();
+        for level in &self.levels[..self.levels.len() - 1] {
+            let mut next = Vec::with_capacity(known.len());
+            let mut i = 0;
+            while i < known.len() {
+                let index = known[i];
+                let sibling = index ^ 1;
+                if index.is_multiple_of(2) && known.get(i + 1) == Some(&sibling) {
+                    i += 1;
+                } else if sibling < level.len() {
+                    siblings.push(level[sibling]);
+                }
+                next.push(index / 2);
+                i += 1;
+            }
+            known = next;
+        }
+
+        Ok(MerkleMultiProof {
+            num_leaves,
+            leaf_indices: sorted.into_iter().map(|i| i as u32).collect(),
+            siblings,
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -128,30 +226,18 @@ pub struct MerklePath {
     pub root: HashDigest,
     /// Number of leaves in the originating tree; bound into the root (RT-2).
     pub num_leaves: usize,
+    /// Arity of the originating tree; also bound into the root.
+    pub arity: MerkleArity,
 }
 
 #[cfg(feature = "alloc")]
 impl MerklePath {
     /// Compute the root hash from this path and the given leaf.
     /// Returns the computed root without comparing to any expected value.
-    #[allow(clippy::manual_is_multiple_of)]
     pub fn compute_root(&self, leaf: &HashDigest) -> HashDigest {
-        let mut current_hash = *leaf;
-        let mut current_index = self.leaf_index;
-
-        for sibling in &self.siblings {
-            current_hash = if current_index % 2 == 0 {
-                // Left child
-                combine_hashes(&current_hash, sibling, DOMAIN_MERKLE_NODE)
-            } else {
-                // Right child
-                combine_hashes(sibling, &current_hash, DOMAIN_MERKLE_NODE)
-            };
-            current_index /= 2;
-        }
-
-        // RT-2: bind the leaf count, matching MerkleTree::new.
-        bind_root(&current_hash, self.num_leaves)
+        let inner = fold_path(leaf, self.leaf_index, &self.siblings, self.arity);
+        // RT-2: bind the leaf count and arity, matching MerkleTree::new.
+        bind_root(&inner, self.num_leaves, self.arity)
     }
 
     /// Verify this path against an externally-provided trusted root.
@@ -159,19 +245,43 @@ impl MerklePath {
     ///
     /// The `expected_root` should come from a trusted source (e.g., on-chain state,
     /// signed batch header, etc.), NOT from the proof itself.
-    #[allow(clippy::manual_is_multiple_of)]
     pub fn verify_against(&self, leaf: &HashDigest, expected_root: &HashDigest) -> bool {
         use crate::utils::hash::constant_time_eq_fixed;
+        // Whole groups of siblings only
+        if !self.siblings.len().is_multiple_of(self.arity.value() - 1) {
+            return false;
+        }
         let computed_root = self.compute_root(leaf);
         constant_time_eq_fixed(&computed_root, expected_root)
     }
 
+    /// Verify this path against a trusted root for a tree of `num_leaves`
+    /// leaves, where the leaf count also comes from a trusted source.
+    ///
+    /// On top of [`verify_against`](Self::verify_against), the path must be
+    /// for that leaf count, its index must lie within the tree and its depth
+    /// must match the tree size, so every digit of `leaf_index` is consumed
+    /// (H-A).
+    pub fn verify_in_tree(
+        &self,
+        leaf: &HashDigest,
+        expected_root: &HashDigest,
+        num_leaves: usize,
+    ) -> bool {
+        if num_leaves == 0 || self.num_leaves != num_leaves || self.leaf_index >= num_leaves {
+            return false;
+        }
+        if self.siblings.len() != self.arity.path_len(num_leaves) {
+            return false;
+        }
+        self.verify_against(leaf, expected_root)
+    }
+
     /// Verify this path against the internally stored root.
     ///
     /// WARNING: Only use this when the MerklePath was constructed internally
     /// (e.g., from MerkleTree::get_proof()). For externally-provided paths,
     /// use `verify_against()` with a trusted root instead.
-    #[allow(clippy::manual_is_multiple_of)]
     pub fn verify(&self, leaf: &HashDigest) -> bool {
         self.verify_against(leaf, &self.root)
     }
@@ -185,19 +295,160 @@ impl MerklePath {
     }
 }
 
+/// Inclusion proof for several leaves of one tree with deduplicated
+/// siblings: a sibling is only carried when the verifier cannot compute it
+/// from the proven leaves. Borsh-encodable for on-chain use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub struct MerkleMultiProof {
+    /// Number of leaves in the originating tree; bound into the root (RT-2).
+    pub num_leaves: u32,
+    /// Proven leaf indices, strictly ascending.
+    pub leaf_indices: Vec<u32>,
+    /// Missing siblings in bottom-up, left-to-right order.
+    pub siblings: Vec<HashDigest>,
+}
+
+#[cfg(feature = "alloc")]
+impl MerkleMultiProof {
+    /// Compute the root for `leaves` (ordered as `leaf_indices`), or `None`
+    /// if the proof is malformed.
+    pub fn compute_root(&self, leaves: &[HashDigest]) -> Option<HashDigest> {
+        use crate::utils::constants::MAX_MERKLE_DEPTH;
+
+        let num_leaves = self.num_leaves as usize;
+        if num_leaves == 0
+            || leaves.len() != self.leaf_indices.len()
+            || leaves.is_empty()
+            || self.siblings.len() > leaves.len() * MAX_MERKLE_DEPTH
+        {
+            return None;
+        }
+        // Strictly ascending and inside the tree
+        if self.leaf_indices.windows(2).any(|w| w[0] >= w[1])
+            || *self.leaf_indices.last()? as usize >= num_leaves
+        {
+            return None;
+        }
+
+        let mut nodes: Vec<(usize, HashDigest)> = self
+            .leaf_indices
+            .iter()
+            .map(|&i| i as usize)
+            .zip(leaves.iter().copied())
+            .collect();
+        let mut siblings = self.siblings.iter();
+        let mut level_len = num_leaves;
+        while level_len > 1 {
+            let mut next = Vec::with_capacity(nodes.len());
+            let mut i = 0;
+            while i < nodes.len() {
+                let (index, hash) = nodes[i];
+                let parent = if index.is_multiple_of(2) {
+                    match nodes.get(i + 1) {
+                        Some(&(right_index, right)) if right_index == index + 1 => {
+                            i += 1;
+                            combine_hashes(&hash, &right, DOMAIN_MERKLE_NODE)
+                        }
+                        // Odd count: the last node is combined with itself
+                        _ if index + 1 >= level_len => {
+                            combine_hashes(&hash, &hash, DOMAIN_MERKLE_NODE)
+                        }
+                        _ => combine_hashes(&hash, siblings.next()?, DOMAIN_MERKLE_NODE),
+                    }
+                } else {
+                    combine_hashes(siblings.next()?, &hash, DOMAIN_MERKLE_NODE)
+                };
+                next.push((index / 2, parent));
+                i += 1;
+            }
+            nodes = next;
+            level_len = level_len.div_ceil(2);
+        }
+
+        // Every carried sibling must be consumed
+        if siblings.next().is_some() {
+            return None;
+        }
+        Some(bind_root(
+            &nodes.first()?.1,
+            num_leaves,
+            MerkleArity::Binary,
+        ))
+    }
+
+    /// Verify against an externally-provided trusted root (see
+    /// [`MerklePath::verify_against`]).
+    pub fn verify_against(&self, leaves: &[HashDigest], expected_root: &HashDigest) -> bool {
+        use crate::utils::hash::constant_time_eq_fixed;
+        match self.compute_root(leaves) {
+            Some(root) => constant_time_eq_fixed(&root, expected_root),
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaf_indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaf_indices.is_empty()
+    }
+}
+
 pub fn hash_leaf(data: &[u8]) -> HashDigest {
     poseidon_hash(data, DOMAIN_MERKLE)
 }
 
-/// Bind the leaf count into a Merkle root so that trees of different sizes
-/// cannot share a root via the odd-node duplication rule (RT-2). The 8-byte
-/// count makes the input length differ from a plain 64-byte node hash, so a
-/// bound root cannot be confused with an internal node.
-pub(crate) fn bind_root(inner_root: &HashDigest, num_leaves: usize) -> HashDigest {
-    let mut data = [0u8; 40];
+/// Hash one group of child nodes into their parent.
+#[cfg(feature = "alloc")]
+fn hash_children(children: &[HashDigest]) -> HashDigest {
+    poseidon_hash(&children.concat(), DOMAIN_MERKLE_NODE)
+}
+
+/// Fold a leaf up its path to the unbound root: each `arity - 1` siblings
+/// are the other members of the node's group, left to right.
+#[cfg(feature = "alloc")]
+pub(crate) fn fold_path(
+    leaf: &HashDigest,
+    leaf_index: usize,
+    siblings: &[HashDigest],
+    arity: MerkleArity,
+) -> HashDigest {
+    let arity = arity.value();
+    let mut current = *leaf;
+    let mut index = leaf_index;
+    for group in siblings.chunks(arity - 1) {
+        let position = (index % arity).min(group.len());
+        let mut children = Vec::with_capacity(arity);
+        children.extend_from_slice(&group[..position]);
+        children.push(current);
+        children.extend_from_slice(&group[position..]);
+        current = hash_children(&children);
+        index /= arity;
+    }
+    current
+}
+
+/// Bind the leaf count and arity into a Merkle root so that trees of
+/// different sizes (via the padding rule, RT-2) or arities cannot share a
+/// root. Binary roots keep the original 40-byte `root ∥ count` input, so
+/// roots already published (batch headers, announcement commitments, XMSS
+/// public keys) are unchanged; 4- and 8-ary roots append the arity byte
+/// (41 bytes). Neither length matches an internal node hash, so a bound root
+/// cannot be confused with an internal node.
+pub(crate) fn bind_root(
+    inner_root: &HashDigest,
+    num_leaves: usize,
+    arity: MerkleArity,
+) -> HashDigest {
+    let mut data = [0u8; 41];
     data[..32].copy_from_slice(inner_root);
-    data[32..].copy_from_slice(&(num_leaves as u64).to_le_bytes());
-    poseidon_hash(&data, DOMAIN_MERKLE_NODE)
+    data[32..40].copy_from_slice(&(num_leaves as u64).to_le_bytes());
+    data[40] = arity as u8;
+    let len = if arity == MerkleArity::Binary { 40 } else { 41 };
+    poseidon_hash(&data[..len], DOMAIN_MERKLE_NODE)
 }
 
 #[cfg(test)]
@@ -315,4 +566,173 @@ mod tests {
         let t4 = MerkleTree::new(vec![a, b, c, c]).unwrap();
         assert_ne!(t3.root(), t4.root(), "tree-size ambiguity must be eliminated");
     }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_merkle_tree_arity() {
+        let arities = [
+            MerkleArity::Binary,
+            MerkleArity::Quaternary,
+            MerkleArity::Octal,
+        ];
+        for n in 1..=20usize {
+            let leaves: Vec<HashDigest> = (0..n).map(|i| hash_leaf(&[i as u8])).collect();
+            let mut roots = Vec::new();
+            for arity in arities {
+                let tree = MerkleTree::with_arity(leaves.clone(), arity).unwrap();
+                for (i, leaf) in leaves.iter().enumerate() {
+                    let path = tree.get_proof(i).unwrap();
+                    assert_eq!(path.len(), arity.path_len(n));
+                    assert!(path.verify_in_tree(leaf, tree.root(), n), "n={} i={}", n, i);
+                }
+                roots.push(*tree.root());
+            }
+            // The arity is bound into the root
+            assert_ne!(roots[0], roots[1]);
+            assert_ne!(roots[1], roots[2]);
+            assert_ne!(roots[0], roots[2]);
+        }
+
+        // 1,024 leaves: 10 binary levels, 5 quaternary, 4 octal, but binary
+        // still has the shortest path
+        assert_eq!(MerkleArity::Binary.depth(1024), 10);
+        assert_eq!(MerkleArity::Quaternary.depth(1024), 5);
+        assert_eq!(MerkleArity::Octal.depth(1024), 4);
+        assert_eq!(MerkleArity::Binary.path_len(1024), 10);
+        assert_eq!(MerkleArity::Quaternary.path_len(1024), 15);
+        assert_eq!(MerkleArity::Octal.path_len(1024), 28);
+        assert_eq!(MerkleArity::from_u8(3), None);
+
+        // Binary roots keep the pre-arity `root ∥ count` format
+        let leaves = vec![hash_leaf(b"a"), hash_leaf(b"b"), hash_leaf(b"c")];
+        let tree = MerkleTree::new(leaves.clone()).unwrap();
+        let inner = hash_children(&[
+            hash_children(&leaves[..2]),
+            hash_children(&[leaves[2], leaves[2]]),
+        ]);
+        let mut data = [0u8; 40];
+        data[..32].copy_from_slice(&inner);
+        data[32..].copy_from_slice(&3u64.to_le_bytes());
+        assert_eq!(*tree.root(), poseidon_hash(&data, DOMAIN_MERKLE_NODE));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_merkle_path_rejects_wrong_arity() {
+        let leaves: Vec<HashDigest> = (0..16u8).map(|i| hash_leaf(&[i])).collect();
+        let tree = MerkleTree::with_arity(leaves.clone(), MerkleArity::Quaternary).unwrap();
+        let path = tree.get_proof(5).unwrap();
+        assert!(path.verify_against(&leaves[5], tree.root()));
+
+        let mut relabeled = path.clone();
+        relabeled.arity = MerkleArity::Binary;
+        assert!(!relabeled.verify_against(&leaves[5], tree.root()));
+        let mut truncated = path.clone();
+        truncated.siblings.pop();
+        assert!(!truncated.verify_against(&leaves[5], tree.root()));
+
+        assert!(tree.get_multi_proof(&[0, 1]).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_merkle_path_verify_in_tree() {
+        for n in 1..=9usize {
+            let leaves: Vec<HashDigest> = (0..n).map(|i| hash_leaf(&[i as u8])).collect();
+            let tree = MerkleTree::new(leaves.clone()).unwrap();
+            for (i, leaf) in leaves.iter().enumerate() {
+                let path = tree.get_proof(i).unwrap();
+                assert!(path.verify_in_tree(leaf, tree.root(), n), "n={} i={}", n, i);
+                assert!(!path.verify_in_tree(leaf, tree.root(), n + 1));
+            }
+        }
+
+        // H-A: an out-of-range index or a padded path is rejected even where
+        // the root itself would still match
+        let leaves: Vec<HashDigest> = (0..4u8).map(|i| hash_leaf(&[i])).collect();
+        let tree = MerkleTree::new(leaves.clone()).unwrap();
+        let mut path = tree.get_proof(1).unwrap();
+        path.leaf_index += 4;
+        assert!(path.verify_against(&leaves[1], tree.root()));
+        assert!(!path.verify_in_tree(&leaves[1], tree.root(), 4));
+        let mut path = tree.get_proof(1).unwrap();
+        path.siblings.push([0u8; 32]);
+        assert!(!path.verify_in_tree(&leaves[1], tree.root(), 4));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_multi_proof_verification() {
+        for n in 1..=13usize {
+            let leaves: Vec<HashDigest> = (0..n).map(|i| hash_leaf(&[i as u8])).collect();
+            let tree = MerkleTree::new(leaves.clone()).unwrap();
+
+            for subset in [
+                vec![0],
+                vec![n - 1],
+                (0..n).collect(),
+                (0..n).step_by(3).collect(),
+            ] {
+                let proof = tree.get_multi_proof(&subset).unwrap();
+                let proven: Vec<_> = proof
+                    .leaf_indices
+                    .iter()
+                    .map(|&i| leaves[i as usize])
+                    .collect();
+                assert!(
+                    proof.verify_against(&proven, tree.root()),
+                    "n={} {:?}",
+                    n,
+                    subset
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_multi_proof_deduplicates_siblings() {
+        let leaves: Vec<HashDigest> = (0..16u8).map(|i| hash_leaf(&[i])).collect();
+        let tree = MerkleTree::new(leaves.clone()).unwrap();
+
+        let proof = tree.get_multi_proof(&[3, 0, 1, 2]).unwrap();
+        assert_eq!(proof.leaf_indices, vec![0, 1, 2, 3]);
+        // Four separate paths carry 16 siblings; the subtree needs only 2
+        assert_eq!(proof.siblings.len(), 2);
+        assert!(proof.verify_against(&leaves[..4], tree.root()));
+        assert!(tree
+            .get_multi_proof(&(0..16).collect::<Vec<_>>())
+            .unwrap()
+            .siblings
+            .is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_multi_proof_rejects_malformed() {
+        let leaves: Vec<HashDigest> = (0..8u8).map(|i| hash_leaf(&[i])).collect();
+        let tree = MerkleTree::new(leaves.clone()).unwrap();
+        let proof = tree.get_multi_proof(&[1, 5]).unwrap();
+        let proven = [leaves[1], leaves[5]];
+        assert!(proof.verify_against(&proven, tree.root()));
+
+        // Swapped leaves, wrong count, relabelled indices, extra sibling
+        assert!(!proof.verify_against(&[leaves[5], leaves[1]], tree.root()));
+        assert!(!proof.verify_against(&proven[..1], tree.root()));
+        let mut bad = proof.clone();
+        bad.leaf_indices = vec![5, 1];
+        assert!(!bad.verify_against(&proven, tree.root()));
+        let mut bad = proof.clone();
+        bad.leaf_indices = vec![1, 4];
+        assert!(!bad.verify_against(&proven, tree.root()));
+        let mut bad = proof.clone();
+        bad.num_leaves = 9;
+        assert!(!bad.verify_against(&proven, tree.root()));
+        let mut bad = proof.clone();
+        bad.siblings.push([0u8; 32]);
+        assert!(!bad.verify_against(&proven, tree.root()));
+
+        assert!(tree.get_multi_proof(&[]).is_err());
+        assert!(tree.get_multi_proof(&[8]).is_err());
+    }
 }
@@ -0,0 +1,392 @@
+//! Merkle Mountain Range - append-only proof log with a stable history
+//!
+//! Leaves are appended one at a time and never move, so every earlier root
+//! stays reproducible: the MMR of size `n` is a forest of perfect binary
+//! trees ("peaks"), one per set bit of `n`, and its root bags those peaks
+//! together with `n`. Inclusion proofs can target any historical root, and a
+//! consistency proof shows that a newer root extends an older one.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+use crate::utils::constants::{DOMAIN_MMR_NODE, DOMAIN_MMR_ROOT};
+use crate::utils::hash::{combine_hashes, constant_time_eq_fixed, poseidon_hash};
+
+use alloc::vec::Vec;
+
+/// `(height, first leaf)` of each peak of an MMR with `size` leaves, tallest
+/// first.
+fn peak_positions(size: u64) -> Vec<(u32, u64)> {
+    let mut positions = Vec::new();
+    let mut start = 0u64;
+    for height in (0..u64::BITS).rev() {
+        if (size >> height) & 1 == 1 {
+            positions.push((height, start));
+            start += 1 << height;
+        }
+    }
+    positions
+}
+
+/// Index of the peak covering `leaf` among `positions`.
+fn peak_index(positions: &[(u32, u64)], leaf: u64) -> Option<usize> {
+    positions
+        .iter()
+        .position(|&(height, start)| leaf >= start && leaf - start < (1u64 << height))
+}
+
+/// Bag the peaks of an MMR with `size` leaves into its root. The leaf count
+/// is bound in, so MMRs of different sizes never share a root.
+pub fn bag_peaks(size: u64, peaks: &[HashDigest]) -> HashDigest {
+    let mut data = Vec::with_capacity(8 + peaks.len() * 32);
+    data.extend_from_slice(&size.to_le_bytes());
+    for peak in peaks {
+        data.extend_from_slice(peak);
+    }
+    poseidon_hash(&data, DOMAIN_MMR_ROOT)
+}
+
+/// Climb from a node at `index` on its level through `siblings`.
+fn climb(node: &HashDigest, mut index: u64, siblings: &[HashDigest]) -> HashDigest {
+    let mut current = *node;
+    for sibling in siblings {
+        current = if index & 1 == 0 {
+            combine_hashes(&current, sibling, DOMAIN_MMR_NODE)
+        } else {
+            combine_hashes(sibling, &current, DOMAIN_MMR_NODE)
+        };
+        index >>= 1;
+    }
+    current
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MerkleMountainRange {
+    /// `levels[h][i]` is the root of the perfect subtree over leaves
+    /// `i * 2^h .. (i + 1) * 2^h`; only complete subtrees are stored.
+    levels: Vec<Vec<HashDigest>>,
+}
+
+impl MerkleMountainRange {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuild from leaves in append order.
+    pub fn from_leaves(leaves: &[HashDigest]) -> Self {
+        let mut mmr = Self::new();
+        for leaf in leaves {
+            mmr.append(*leaf);
+        }
+        mmr
+    }
+
+    /// Append a leaf (e.g. `hash_leaf` or `proof_leaf` output) and return
+    /// its index.
+    pub fn append(&mut self, leaf: HashDigest) -> u64 {
+        let index = self.len();
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        self.levels[0].push(leaf);
+
+        let mut height = 0;
+        while self.levels[height].len().is_multiple_of(2) {
+            let level = &self.levels[height];
+            let node = combine_hashes(
+                &level[level.len() - 2],
+                &level[level.len() - 1],
+                DOMAIN_MMR_NODE,
+            );
+            if self.levels.len() == height + 1 {
+                self.levels.push(Vec::new());
+            }
+            self.levels[height + 1].push(node);
+            height += 1;
+        }
+        index
+    }
+
+    /// Append an integrated proof by its canonical-encoding leaf.
+    #[cfg(feature = "full-p3")]
+    pub fn append_proof(&mut self, proof: &crate::stark::IntegratedProof) -> Result<u64> {
+        Ok(self.append(crate::batching::integrated::proof_leaf(proof)?))
+    }
+
+    pub fn len(&self) -> u64 {
+        self.levels.first().map_or(0, |leaves| leaves.len() as u64)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn leaf(&self, index: u64) -> Option<&HashDigest> {
+        self.levels.first()?.get(usize::try_from(index).ok()?)
+    }
+
+    fn node(&self, height: u32, index: u64) -> HashDigest {
+        self.levels[height as usize][index as usize]
+    }
+
+    fn check_size(&self, size: u64) -> Result<()> {
+        if size > self.len() {
+            return Err(ZKMTDError::MerkleError {
+                reason: alloc::format!("MMR size {} exceeds current size {}", size, self.len()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Peaks of the MMR as it was with `size` leaves.
+    pub fn peaks_at(&self, size: u64) -> Result<Vec<HashDigest>> {
+        self.check_size(size)?;
+        Ok(peak_positions(size)
+            .into_iter()
+            .map(|(height, start)| self.node(height, start >> height))
+            .collect())
+    }
+
+    pub fn peaks(&self) -> Vec<HashDigest> {
+        self.peaks_at(self.len()).unwrap_or_default()
+    }
+
+    /// Root of the MMR as it was with `size` leaves.
+    pub fn root_at(&self, size: u64) -> Result<HashDigest> {
+        Ok(bag_peaks(size, &self.peaks_at(size)?))
+    }
+
+    pub fn root(&self) -> HashDigest {
+        bag_peaks(self.len(), &self.peaks())
+    }
+
+    /// Inclusion proof for `leaf_index` against the current root.
+    pub fn prove(&self, leaf_index: u64) -> Result<MmrProof> {
+        self.prove_at(leaf_index, self.len())
+    }
+
+    /// Inclusion proof for `leaf_index` against the root at `size` leaves.
+    pub fn prove_at(&self, leaf_index: u64, size: u64) -> Result<MmrProof> {
+        self.check_size(size)?;
+        let positions = peak_positions(size);
+        let (height, _) = peak_index(&positions, leaf_index)
+            .map(|k| positions[k])
+            .ok_or_else(|| ZKMTDError::MerkleError {
+                reason: alloc::format!("Invalid index: {} >= {}", leaf_index, size),
+            })?;
+
+        let siblings = (0..height)
+            .map(|level| self.node(level, (leaf_index >> level) ^ 1))
+            .collect();
+        Ok(MmrProof {
+            leaf_index,
+            mmr_size: size,
+            siblings,
+            peaks: self.peaks_at(size)?,
+        })
+    }
+
+    /// Proof that the root at `new_size` leaves extends the root at
+    /// `old_size` leaves.
+    pub fn prove_consistency(&self, old_size: u64, new_size: u64) -> Result<ConsistencyProof> {
+        self.check_size(new_size)?;
+        if old_size > new_size {
+            return Err(ZKMTDError::MerkleError {
+                reason: alloc::format!("Old MMR size {} exceeds new size {}", old_size, new_size),
+            });
+        }
+
+        let new_positions = peak_positions(new_size);
+        let mut paths = Vec::new();
+        for (height, start) in peak_positions(old_size) {
+            let (top, _) = peak_index(&new_positions, start)
+                .map(|k| new_positions[k])
+                .ok_or_else(|| ZKMTDError::MerkleError {
+                    reason: "Old peak outside new MMR".into(),
+                })?;
+            paths.push(
+                (height..top)
+                    .map(|level| self.node(level, (start >> level) ^ 1))
+                    .collect(),
+            );
+        }
+
+        Ok(ConsistencyProof {
+            old_size,
+            new_size,
+            old_peaks: self.peaks_at(old_size)?,
+            new_peaks: self.peaks_at(new_size)?,
+            paths,
+        })
+    }
+}
+
+/// Inclusion of one leaf under an MMR root of a given size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MmrProof {
+    pub leaf_index: u64,
+    /// Leaf count of the MMR the proof was made against; bound into its root.
+    pub mmr_size: u64,
+    /// Siblings from the leaf up to its peak.
+    pub siblings: Vec<HashDigest>,
+    /// All peaks of the MMR, tallest first.
+    pub peaks: Vec<HashDigest>,
+}
+
+impl MmrProof {
+    /// Verify against a trusted root (e.g. one recorded in an earlier audit).
+    pub fn verify_against(&self, leaf: &HashDigest, expected_root: &HashDigest) -> bool {
+        let positions = peak_positions(self.mmr_size);
+        let Some(k) = peak_index(&positions, self.leaf_index) else {
+            return false;
+        };
+        let (height, _) = positions[k];
+        if self.siblings.len() != height as usize || self.peaks.len() != positions.len() {
+            return false;
+        }
+
+        let peak = climb(leaf, self.leaf_index, &self.siblings);
+        constant_time_eq_fixed(&peak, &self.peaks[k])
+            && constant_time_eq_fixed(&bag_peaks(self.mmr_size, &self.peaks), expected_root)
+    }
+}
+
+/// Shows that the MMR at `new_size` leaves contains the MMR at `old_size`
+/// leaves as a prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyProof {
+    pub old_size: u64,
+    pub new_size: u64,
+    pub old_peaks: Vec<HashDigest>,
+    pub new_peaks: Vec<HashDigest>,
+    /// For each old peak, siblings up to the new peak that contains it.
+    pub paths: Vec<Vec<HashDigest>>,
+}
+
+impl ConsistencyProof {
+    /// Verify against two trusted roots; true iff `new_root` only appended
+    /// leaves to `old_root`.
+    pub fn verify(&self, old_root: &HashDigest, new_root: &HashDigest) -> bool {
+        if self.old_size > self.new_size {
+            return false;
+        }
+        let old_positions = peak_positions(self.old_size);
+        let new_positions = peak_positions(self.new_size);
+        if self.old_peaks.len() != old_positions.len()
+            || self.paths.len() != old_positions.len()
+            || self.new_peaks.len() != new_positions.len()
+        {
+            return false;
+        }
+        if !constant_time_eq_fixed(&bag_peaks(self.old_size, &self.old_peaks), old_root)
+            || !constant_time_eq_fixed(&bag_peaks(self.new_size, &self.new_peaks), new_root)
+        {
+            return false;
+        }
+
+        for ((&(height, start), peak), path) in
+            old_positions.iter().zip(&self.old_peaks).zip(&self.paths)
+        {
+            let Some(k) = peak_index(&new_positions, start) else {
+                return false;
+            };
+            let (top, _) = new_positions[k];
+            if path.len() != (top - height) as usize {
+                return false;
+            }
+            let computed = climb(peak, start >> height, path);
+            if !constant_time_eq_fixed(&computed, &self.new_peaks[k]) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batching::merkle::hash_leaf;
+
+    fn leaf(i: u64) -> HashDigest {
+        hash_leaf(&i.to_le_bytes())
+    }
+
+    fn mmr(size: u64) -> MerkleMountainRange {
+        let mut mmr = MerkleMountainRange::new();
+        for i in 0..size {
+            assert_eq!(mmr.append(leaf(i)), i);
+        }
+        mmr
+    }
+
+    #[test]
+    fn test_mmr_peaks_and_history() {
+        let mmr = mmr(11);
+        assert_eq!(mmr.len(), 11);
+        assert_eq!(mmr.peaks().len(), 3);
+
+        // Historical roots match a fresh MMR of that size
+        for size in 0..=11 {
+            assert_eq!(mmr.root_at(size).unwrap(), self::mmr(size).root());
+        }
+        assert_ne!(mmr.root_at(4).unwrap(), mmr.root_at(5).unwrap());
+        assert!(mmr.root_at(12).is_err());
+    }
+
+    #[test]
+    fn test_mmr_inclusion_against_historical_roots() {
+        let mmr = mmr(13);
+        for size in 1..=13 {
+            let root = mmr.root_at(size).unwrap();
+            for i in 0..size {
+                let proof = mmr.prove_at(i, size).unwrap();
+                assert!(proof.verify_against(&leaf(i), &root));
+                assert!(!proof.verify_against(&leaf(i + 1), &root));
+            }
+        }
+        assert!(mmr.prove_at(5, 5).is_err());
+
+        // A proof for one size does not verify under another size's root
+        let proof = mmr.prove_at(2, 7).unwrap();
+        assert!(!proof.verify_against(&leaf(2), &mmr.root_at(8).unwrap()));
+        let mut relabelled = proof.clone();
+        relabelled.mmr_size = 8;
+        assert!(!relabelled.verify_against(&leaf(2), &mmr.root_at(8).unwrap()));
+    }
+
+    #[test]
+    fn test_mmr_consistency() {
+        let mmr = mmr(21);
+        for old in 0..=21 {
+            for new in old..=21 {
+                let proof = mmr.prove_consistency(old, new).unwrap();
+                assert!(
+                    proof.verify(&mmr.root_at(old).unwrap(), &mmr.root_at(new).unwrap()),
+                    "{} -> {}",
+                    old,
+                    new
+                );
+            }
+        }
+        assert!(mmr.prove_consistency(5, 4).is_err());
+    }
+
+    #[test]
+    fn test_mmr_consistency_rejects_rewritten_history() {
+        let honest = mmr(9);
+        let mut forked = mmr(4);
+        forked.append(hash_leaf(b"rewritten"));
+        for i in 5..9 {
+            forked.append(leaf(i));
+        }
+
+        let old_root = honest.root_at(6).unwrap();
+        let proof = forked.prove_consistency(6, 9).unwrap();
+        assert!(!proof.verify(&old_root, &forked.root()));
+
+        let mut tampered = honest.prove_consistency(6, 9).unwrap();
+        assert!(tampered.verify(&old_root, &honest.root()));
+        tampered.paths[0][0][0] ^= 1;
+        assert!(!tampered.verify(&old_root, &honest.root()));
+    }
+}
@@ -2,11 +2,39 @@
 
 pub mod aggregator;
 pub mod batch_verifier;
+#[cfg(all(feature = "full-p3", feature = "alloc"))]
+pub mod integrated;
 pub mod merkle;
+#[cfg(feature = "alloc")]
+pub mod mmr;
+#[cfg(all(feature = "full-p3", feature = "alloc"))]
+pub mod multi_epoch;
+#[cfg(feature = "alloc")]
+pub mod nullifier;
+#[cfg(feature = "alloc")]
+pub mod report;
+#[cfg(feature = "alloc")]
+pub mod sum_tree;
 
+#[allow(deprecated)]
 pub use aggregator::{create_proof_batch, BatchProver};
+#[allow(deprecated)]
 pub use batch_verifier::BatchVerifier;
-pub use merkle::{MerklePath, MerkleTree};
+#[cfg(all(feature = "full-p3", feature = "alloc"))]
+pub use integrated::{proof_leaf, BatchStatement, IntegratedBatch, IntegratedBatchVerifier};
+pub use merkle::{MerkleArity, MerkleMultiProof, MerklePath, MerkleTree};
+#[cfg(feature = "alloc")]
+pub use mmr::{ConsistencyProof, MerkleMountainRange, MmrProof};
+#[cfg(all(feature = "full-p3", feature = "alloc"))]
+pub use multi_epoch::{MultiEpochBatch, MultiEpochBatchVerifier, MultiEpochPath};
+#[cfg(feature = "alloc")]
+pub use nullifier::{
+    nullifier_from_commitments, nullifier_from_committed_values, NullifierSet, SmtProof,
+};
+#[cfg(feature = "alloc")]
+pub use report::{BatchCounts, BatchReport, FailurePolicy, ProofStatus};
+#[cfg(feature = "alloc")]
+pub use sum_tree::{MerkleSumTree, SumLeaf, SumNode, SumPath};
 
 // Re-export
 pub use crate::core::types::ProofBatch;
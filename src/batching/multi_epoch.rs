@@ -0,0 +1,380 @@
+//! MultiEpochBatch - proofs from several epochs under one root
+//!
+//! Proofs are grouped by epoch into [`IntegratedBatch`] subtrees, and the
+//! batch root is a Merkle tree over `(epoch, subtree root)` leaves in
+//! ascending epoch order. Each subtree is verified against its own epoch's
+//! params, so a daily settlement can commit to every hourly epoch at once
+//! while a proof still only verifies under the epoch it was made in.
+
+use crate::batching::integrated::{proof_leaf, IntegratedBatch, IntegratedBatchVerifier};
+use crate::batching::merkle::{MerklePath, MerkleTree};
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+use crate::mtd::Epoch;
+use crate::stark::integrated::{IntegratedProof, IntegratedVerifier};
+use crate::utils::constants::{DOMAIN_EPOCH_SUBTREE, MAX_BATCH_EPOCHS};
+use crate::utils::hash::{constant_time_eq_fixed, poseidon_hash};
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// Top-level leaf committing to one epoch's subtree.
+pub fn epoch_leaf(epoch: Epoch, subtree_root: &HashDigest) -> HashDigest {
+    let mut data = [0u8; 40];
+    data[..8].copy_from_slice(&epoch.value().to_le_bytes());
+    data[8..].copy_from_slice(subtree_root);
+    poseidon_hash(&data, DOMAIN_EPOCH_SUBTREE)
+}
+
+fn epoch_tree(subtrees: &[IntegratedBatch]) -> Result<MerkleTree> {
+    let leaves = subtrees
+        .iter()
+        .map(|batch| epoch_leaf(batch.epoch, &batch.merkle_root))
+        .collect();
+    MerkleTree::new(leaves)
+}
+
+#[derive(Debug)]
+pub struct MultiEpochBatch {
+    /// One subtree per epoch, in ascending epoch order.
+    pub subtrees: Vec<IntegratedBatch>,
+    pub merkle_root: HashDigest,
+    /// Tree over the subtree roots as combined, kept to serve inclusion
+    /// paths; each subtree keeps its own.
+    epoch_tree: MerkleTree,
+}
+
+impl MultiEpochBatch {
+    /// Group proofs by epoch (keeping their order within each epoch).
+    pub fn new(proofs: Vec<IntegratedProof>) -> Result<Self> {
+        let mut groups: BTreeMap<Epoch, Vec<IntegratedProof>> = BTreeMap::new();
+        for proof in proofs {
+            groups.entry(proof.epoch).or_default().push(proof);
+        }
+        let subtrees = groups
+            .into_values()
+            .map(IntegratedBatch::new)
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_batches(subtrees)
+    }
+
+    /// Combine single-epoch batches; their epochs must be distinct.
+    pub fn from_batches(mut subtrees: Vec<IntegratedBatch>) -> Result<Self> {
+        if subtrees.is_empty() {
+            return Err(ZKMTDError::BatchError {
+                reason: "Proofs are empty".into(),
+            });
+        }
+        if subtrees.len() > MAX_BATCH_EPOCHS {
+            return Err(ZKMTDError::BatchError {
+                reason: alloc::format!(
+                    "Batch epochs exceed maximum: {} > {}",
+                    subtrees.len(),
+                    MAX_BATCH_EPOCHS
+                ),
+            });
+        }
+        subtrees.sort_by_key(|batch| batch.epoch);
+        if let Some(pair) = subtrees.windows(2).find(|w| w[0].epoch == w[1].epoch) {
+            return Err(ZKMTDError::BatchError {
+                reason: alloc::format!("Duplicate epoch subtree: {}", pair[0].epoch.value()),
+            });
+        }
+
+        let epoch_tree = epoch_tree(&subtrees)?;
+        Ok(Self {
+            subtrees,
+            merkle_root: *epoch_tree.root(),
+            epoch_tree,
+        })
+    }
+
+    pub fn root(&self) -> &HashDigest {
+        &self.merkle_root
+    }
+
+    pub fn epochs(&self) -> impl Iterator<Item = Epoch> + '_ {
+        self.subtrees.iter().map(|batch| batch.epoch)
+    }
+
+    pub fn subtree(&self, epoch: Epoch) -> Option<&IntegratedBatch> {
+        self.subtrees.iter().find(|batch| batch.epoch == epoch)
+    }
+
+    /// Total number of proofs.
+    pub fn len(&self) -> usize {
+        self.subtrees.iter().map(IntegratedBatch::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subtrees.is_empty()
+    }
+
+    /// Path proving proof `index` of `epoch`'s subtree is under the root.
+    pub fn inclusion_path(&self, epoch: Epoch, index: usize) -> Result<MultiEpochPath> {
+        let position = self
+            .subtrees
+            .iter()
+            .position(|batch| batch.epoch == epoch)
+            .ok_or_else(|| ZKMTDError::BatchError {
+                reason: alloc::format!("No subtree for epoch {}", epoch.value()),
+            })?;
+        let subtree = &self.subtrees[position];
+        Ok(MultiEpochPath {
+            epoch,
+            subtree_root: subtree.merkle_root,
+            leaf_path: subtree.inclusion_path(index)?,
+            epoch_path: self.epoch_tree.get_proof(position)?,
+        })
+    }
+}
+
+/// Inclusion of one proof: leaf -> epoch subtree root -> batch root.
+#[derive(Debug, Clone)]
+pub struct MultiEpochPath {
+    pub epoch: Epoch,
+    pub subtree_root: HashDigest,
+    /// Path from the proof's leaf to `subtree_root`.
+    pub leaf_path: MerklePath,
+    /// Path from `epoch_leaf(epoch, subtree_root)` to the batch root.
+    pub epoch_path: MerklePath,
+}
+
+impl MultiEpochPath {
+    /// Both levels verify against an externally-provided trusted root, with
+    /// the leaf counts (`epoch_count` subtrees, `subtree_len` proofs in this
+    /// epoch's subtree) taken from the same trusted source. Each path must be
+    /// in range and full depth for its tree (H-A).
+    pub fn verify_in_tree(
+        &self,
+        leaf: &HashDigest,
+        trusted_root: &HashDigest,
+        epoch_count: usize,
+        subtree_len: usize,
+    ) -> bool {
+        self.leaf_path
+            .verify_in_tree(leaf, &self.subtree_root, subtree_len)
+            && self.epoch_path.verify_in_tree(
+                &epoch_leaf(self.epoch, &self.subtree_root),
+                trusted_root,
+                epoch_count,
+            )
+    }
+}
+
+/// Verifies [`MultiEpochBatch`]es against a configured set of epochs, each
+/// with its own params.
+#[derive(Debug, Default)]
+pub struct MultiEpochBatchVerifier {
+    verifiers: BTreeMap<Epoch, IntegratedBatchVerifier>,
+}
+
+impl MultiEpochBatchVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept `verifier`'s epoch (replacing any verifier already set for it).
+    pub fn with_verifier(mut self, verifier: IntegratedVerifier) -> Self {
+        self.verifiers.insert(
+            verifier.current_epoch(),
+            IntegratedBatchVerifier::new(verifier),
+        );
+        self
+    }
+
+    /// Accept each of `epochs`, deriving its params from `seed`.
+    pub fn for_epochs(seed: &[u8], epochs: &[Epoch]) -> Result<Self> {
+        epochs.iter().try_fold(Self::new(), |acc, &epoch| {
+            Ok(acc.with_verifier(IntegratedVerifier::new(seed, epoch)?))
+        })
+    }
+
+    pub fn accepts(&self, epoch: Epoch) -> bool {
+        self.verifiers.contains_key(&epoch)
+    }
+
+    pub fn epochs(&self) -> impl Iterator<Item = Epoch> + '_ {
+        self.verifiers.keys().copied()
+    }
+
+    fn verifier_for(&self, epoch: Epoch) -> Result<&IntegratedBatchVerifier> {
+        self.verifiers
+            .get(&epoch)
+            .ok_or_else(|| ZKMTDError::InvalidEpoch {
+                current: epoch.value(),
+                reason: "Epoch is not accepted by this verifier".into(),
+            })
+    }
+
+    /// Checks that every epoch is accepted, the root over the subtrees,
+    /// then each subtree under its epoch's verifier.
+    pub fn verify_batch(&self, batch: &MultiEpochBatch) -> Result<bool> {
+        if batch.is_empty() {
+            return Err(ZKMTDError::BatchError {
+                reason: "Batch is empty".into(),
+            });
+        }
+        let verifiers = batch
+            .subtrees
+            .iter()
+            .map(|subtree| self.verifier_for(subtree.epoch))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Epochs must be strictly ascending, as `from_batches` builds them
+        if batch.subtrees.windows(2).any(|w| w[0].epoch >= w[1].epoch) {
+            return Ok(false);
+        }
+        let computed_root = *epoch_tree(&batch.subtrees)?.root();
+        if !constant_time_eq_fixed(&computed_root, &batch.merkle_root) {
+            return Ok(false);
+        }
+
+        for (verifier, subtree) in verifiers.into_iter().zip(&batch.subtrees) {
+            if !verifier.verify_batch(subtree)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Verify one proof against a trusted batch root; the rest of the batch
+    /// is not needed. `epoch_count` and `subtree_len` come from the trusted
+    /// header alongside the root (see [`MultiEpochPath::verify_in_tree`]).
+    pub fn verify_single(
+        &self,
+        proof: &IntegratedProof,
+        path: &MultiEpochPath,
+        trusted_root: &HashDigest,
+        epoch_count: usize,
+        subtree_len: usize,
+    ) -> Result<bool> {
+        let verifier = self.verifier_for(proof.epoch)?;
+        if path.epoch != proof.epoch {
+            return Ok(false);
+        }
+        if !path.verify_in_tree(&proof_leaf(proof)?, trusted_root, epoch_count, subtree_len) {
+            return Ok(false);
+        }
+        verifier.inner_verifier().verify(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stark::integrated::IntegratedProver;
+    use alloc::vec;
+
+    const SEED: &[u8] = b"multi-epoch-batch-seed";
+
+    fn proofs(epoch: u64, count: u8) -> Vec<IntegratedProof> {
+        let prover = IntegratedProver::new(SEED, Epoch::new(epoch)).unwrap();
+        (0..count)
+            .map(|i| prover.prove_fibonacci(8, [i; 32]).unwrap())
+            .collect()
+    }
+
+    fn sample_batch() -> MultiEpochBatch {
+        let mut all = proofs(501, 2);
+        all.extend(proofs(500, 3));
+        all.extend(proofs(502, 1));
+        MultiEpochBatch::new(all).unwrap()
+    }
+
+    fn epochs(values: &[u64]) -> Vec<Epoch> {
+        values.iter().map(|&e| Epoch::new(e)).collect()
+    }
+
+    #[test]
+    fn test_multi_epoch_batch_verify() {
+        let batch = sample_batch();
+        assert_eq!(batch.epochs().collect::<Vec<_>>(), epochs(&[500, 501, 502]));
+        assert_eq!(batch.len(), 6);
+
+        let verifier =
+            MultiEpochBatchVerifier::for_epochs(SEED, &epochs(&[500, 501, 502])).unwrap();
+        assert!(verifier.verify_batch(&batch).unwrap());
+
+        // An epoch outside the configured set is rejected
+        let narrow = MultiEpochBatchVerifier::for_epochs(SEED, &epochs(&[500, 501])).unwrap();
+        assert!(matches!(
+            narrow.verify_batch(&batch),
+            Err(ZKMTDError::InvalidEpoch { .. })
+        ));
+
+        // A verifier with the wrong params for one epoch rejects its subtree
+        let wrong = MultiEpochBatchVerifier::for_epochs(SEED, &epochs(&[500, 501]))
+            .unwrap()
+            .with_verifier(IntegratedVerifier::new(b"other-seed", Epoch::new(502)).unwrap());
+        assert!(!wrong.verify_batch(&batch).unwrap());
+    }
+
+    #[test]
+    fn test_multi_epoch_batch_tampering() {
+        let verifier =
+            MultiEpochBatchVerifier::for_epochs(SEED, &epochs(&[500, 501, 502])).unwrap();
+
+        let mut batch = sample_batch();
+        batch.merkle_root[0] ^= 1;
+        assert!(!verifier.verify_batch(&batch).unwrap());
+
+        // Swapping subtrees changes the root
+        let mut batch = sample_batch();
+        batch.subtrees.swap(0, 1);
+        assert!(!verifier.verify_batch(&batch).unwrap());
+
+        // Moving a proof into another epoch's subtree
+        let mut batch = sample_batch();
+        let moved = batch.subtrees[0].proofs.pop().unwrap();
+        batch.subtrees[1].proofs.push(moved);
+        assert!(!verifier.verify_batch(&batch).unwrap());
+
+        let mut dup = proofs(500, 1);
+        dup.extend(proofs(500, 1));
+        let twice = MultiEpochBatch::from_batches(vec![
+            IntegratedBatch::new(proofs(500, 1)).unwrap(),
+            IntegratedBatch::new(dup).unwrap(),
+        ]);
+        assert!(twice.is_err());
+    }
+
+    #[test]
+    fn test_multi_epoch_single_inclusion() {
+        let batch = sample_batch();
+        let root = *batch.root();
+        let epoch_count = batch.subtrees.len();
+        let verifier =
+            MultiEpochBatchVerifier::for_epochs(SEED, &epochs(&[500, 501, 502])).unwrap();
+
+        for subtree in &batch.subtrees {
+            for (i, proof) in subtree.proofs.iter().enumerate() {
+                let path = batch.inclusion_path(subtree.epoch, i).unwrap();
+                assert!(verifier
+                    .verify_single(proof, &path, &root, epoch_count, subtree.len())
+                    .unwrap());
+            }
+        }
+
+        // A path for another epoch, or a relabeled epoch, does not verify
+        let proof = &batch.subtrees[0].proofs[0];
+        let leaf = proof_leaf(proof).unwrap();
+        let other = batch.inclusion_path(Epoch::new(501), 0).unwrap();
+        assert!(!verifier
+            .verify_single(proof, &other, &root, epoch_count, 3)
+            .unwrap());
+        let mut relabeled = batch.inclusion_path(Epoch::new(500), 0).unwrap();
+        relabeled.epoch = Epoch::new(501);
+        assert!(!relabeled.verify_in_tree(&leaf, &root, epoch_count, 3));
+        assert!(batch.inclusion_path(Epoch::new(503), 0).is_err());
+
+        // H-A: leaf counts that disagree with the trusted header
+        let path = batch.inclusion_path(Epoch::new(500), 0).unwrap();
+        assert!(path.verify_in_tree(&leaf, &root, epoch_count, 3));
+        assert!(!path.verify_in_tree(&leaf, &root, epoch_count + 1, 3));
+        assert!(!path.verify_in_tree(&leaf, &root, epoch_count, 4));
+        let mut padded = path.clone();
+        padded.leaf_path.siblings.push([0u8; 32]);
+        assert!(!padded.verify_in_tree(&leaf, &root, epoch_count, 3));
+    }
+}
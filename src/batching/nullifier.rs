@@ -0,0 +1,308 @@
+//! Nullifier set - Poseidon2 sparse Merkle tree for replay prevention
+//!
+//! Every proof has a nullifier derived from data its verifier actually
+//! checks: the STARK commitments and epoch off chain, the committed public
+//! values on chain. Anything else (e.g. the unkeyed binding hash) can be
+//! recomputed by whoever replays the proof, giving it a fresh nullifier. A
+//! verifier that
+//! only holds the set's root can check a submitted non-membership proof,
+//! accept the proof, and compute the next root from the same path, so the
+//! set never has to live on the verifying side.
+//!
+//! The tree has depth 256 and is keyed by the nullifier bits, most
+//! significant first. Empty leaves are all-zero; only non-empty nodes are
+//! stored.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+use crate::utils::constants::{
+    DOMAIN_NULLIFIER, DOMAIN_ONCHAIN_NULLIFIER, DOMAIN_SMT_LEAF, DOMAIN_SMT_NODE,
+};
+use crate::utils::hash::{combine_hashes, constant_time_eq_fixed, poseidon_hash};
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Tree depth: one level per nullifier bit.
+pub const SMT_DEPTH: usize = 256;
+
+const EMPTY_LEAF: HashDigest = [0u8; 32];
+
+/// Nullifier of a STARK proof with the given canonical commitments
+/// (`RealProof::commitments_bytes`), made in `epoch`.
+pub fn nullifier_from_commitments(epoch: u64, stark_commitments: &[u8]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(8 + stark_commitments.len());
+    data.extend_from_slice(&epoch.to_le_bytes());
+    data.extend_from_slice(stark_commitments);
+    poseidon_hash(&data, DOMAIN_NULLIFIER)
+}
+
+/// Nullifier of an on-chain proof with the given committed public values,
+/// the only per-proof data the on-chain verifier checks.
+pub fn nullifier_from_committed_values(committed_values: &[u8; 32]) -> [u8; 32] {
+    poseidon_hash(committed_values, DOMAIN_ONCHAIN_NULLIFIER)
+}
+
+fn leaf_value(nullifier: &[u8; 32]) -> HashDigest {
+    poseidon_hash(nullifier, DOMAIN_SMT_LEAF)
+}
+
+/// Bit of `key` deciding the side at `height` (0 = leaf level).
+fn bit_at(key: &[u8; 32], height: usize) -> bool {
+    let i = SMT_DEPTH - 1 - height;
+    (key[i / 8] >> (7 - i % 8)) & 1 == 1
+}
+
+/// `key` with its lowest `height` bits cleared: the node id at `height`.
+fn prefix(key: &[u8; 32], height: usize) -> [u8; 32] {
+    let mut out = *key;
+    for i in (SMT_DEPTH - height)..SMT_DEPTH {
+        out[i / 8] &= !(1 << (7 - i % 8));
+    }
+    out
+}
+
+fn flip(key: &[u8; 32], height: usize) -> [u8; 32] {
+    let mut out = *key;
+    let i = SMT_DEPTH - 1 - height;
+    out[i / 8] ^= 1 << (7 - i % 8);
+    out
+}
+
+/// Roots of empty subtrees, by height.
+fn empty_hashes() -> Vec<HashDigest> {
+    let mut hashes = Vec::with_capacity(SMT_DEPTH + 1);
+    hashes.push(EMPTY_LEAF);
+    for h in 0..SMT_DEPTH {
+        hashes.push(combine_hashes(&hashes[h], &hashes[h], DOMAIN_SMT_NODE));
+    }
+    hashes
+}
+
+#[derive(Debug, Clone)]
+pub struct NullifierSet {
+    /// Non-empty nodes keyed by `(height, prefix)`.
+    nodes: BTreeMap<(u16, [u8; 32]), HashDigest>,
+    empty: Vec<HashDigest>,
+    len: u64,
+}
+
+impl Default for NullifierSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NullifierSet {
+    pub fn new() -> Self {
+        Self {
+            nodes: BTreeMap::new(),
+            empty: empty_hashes(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn node(&self, height: usize, key: &[u8; 32]) -> HashDigest {
+        self.nodes
+            .get(&(height as u16, prefix(key, height)))
+            .copied()
+            .unwrap_or(self.empty[height])
+    }
+
+    pub fn root(&self) -> HashDigest {
+        self.node(SMT_DEPTH, &[0u8; 32])
+    }
+
+    pub fn contains(&self, nullifier: &[u8; 32]) -> bool {
+        self.nodes.contains_key(&(0, *nullifier))
+    }
+
+    /// Path for `nullifier`: a membership proof if it is in the set, a
+    /// non-membership proof otherwise.
+    pub fn prove(&self, nullifier: &[u8; 32]) -> SmtProof {
+        let mut bitmap = [0u8; 32];
+        let mut siblings = Vec::new();
+        for height in 0..SMT_DEPTH {
+            let sibling = self.node(height, &flip(nullifier, height));
+            if sibling != self.empty[height] {
+                bitmap[height / 8] |= 1 << (height % 8);
+                siblings.push(sibling);
+            }
+        }
+        SmtProof { bitmap, siblings }
+    }
+
+    /// Insert a nullifier; returns its non-membership proof against the
+    /// previous root (what a stateless verifier needs to follow the update).
+    pub fn insert(&mut self, nullifier: [u8; 32]) -> Result<SmtProof> {
+        if self.contains(&nullifier) {
+            return Err(ZKMTDError::Replayed {
+                reason: "Nullifier already in set".into(),
+            });
+        }
+        let proof = self.prove(&nullifier);
+
+        let mut current = leaf_value(&nullifier);
+        self.nodes.insert((0, nullifier), current);
+        for height in 0..SMT_DEPTH {
+            let sibling = self.node(height, &flip(&nullifier, height));
+            current = if bit_at(&nullifier, height) {
+                combine_hashes(&sibling, &current, DOMAIN_SMT_NODE)
+            } else {
+                combine_hashes(&current, &sibling, DOMAIN_SMT_NODE)
+            };
+            self.nodes.insert(
+                ((height + 1) as u16, prefix(&nullifier, height + 1)),
+                current,
+            );
+        }
+        self.len += 1;
+        Ok(proof)
+    }
+}
+
+/// Path from a nullifier's leaf to the root. Empty siblings are omitted and
+/// flagged in `bitmap` (bit `h` set: the sibling at height `h` is carried).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub struct SmtProof {
+    pub bitmap: [u8; 32],
+    pub siblings: Vec<HashDigest>,
+}
+
+impl SmtProof {
+    fn compute_root(&self, nullifier: &[u8; 32], leaf: &HashDigest) -> Option<HashDigest> {
+        let carried = self.bitmap.iter().map(|b| b.count_ones() as usize).sum();
+        if self.siblings.len() != carried {
+            return None;
+        }
+
+        let mut siblings = self.siblings.iter();
+        let mut empty = EMPTY_LEAF;
+        let mut current = *leaf;
+        for height in 0..SMT_DEPTH {
+            let sibling = if (self.bitmap[height / 8] >> (height % 8)) & 1 == 1 {
+                *siblings.next()?
+            } else {
+                empty
+            };
+            current = if bit_at(nullifier, height) {
+                combine_hashes(&sibling, &current, DOMAIN_SMT_NODE)
+            } else {
+                combine_hashes(&current, &sibling, DOMAIN_SMT_NODE)
+            };
+            empty = combine_hashes(&empty, &empty, DOMAIN_SMT_NODE);
+        }
+        Some(current)
+    }
+
+    fn verify_leaf(&self, nullifier: &[u8; 32], leaf: &HashDigest, root: &HashDigest) -> bool {
+        self.compute_root(nullifier, leaf)
+            .is_some_and(|computed| constant_time_eq_fixed(&computed, root))
+    }
+
+    /// `nullifier` is in the set with root `root`.
+    pub fn verify_membership(&self, nullifier: &[u8; 32], root: &HashDigest) -> bool {
+        self.verify_leaf(nullifier, &leaf_value(nullifier), root)
+    }
+
+    /// `nullifier` is not in the set with root `root`.
+    pub fn verify_non_membership(&self, nullifier: &[u8; 32], root: &HashDigest) -> bool {
+        self.verify_leaf(nullifier, &EMPTY_LEAF, root)
+    }
+
+    /// Root after inserting `nullifier` into the set with root `root`, or
+    /// `None` unless this is a valid non-membership proof for it.
+    pub fn root_after_insert(&self, nullifier: &[u8; 32], root: &HashDigest) -> Option<HashDigest> {
+        if !self.verify_non_membership(nullifier, root) {
+            return None;
+        }
+        self.compute_root(nullifier, &leaf_value(nullifier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nullifier(i: u8) -> [u8; 32] {
+        nullifier_from_commitments(100, &[i; 32])
+    }
+
+    #[test]
+    fn test_nullifier_set_insert_and_prove() {
+        let mut set = NullifierSet::new();
+        let empty_root = set.root();
+        assert!(set.prove(&nullifier(1)).siblings.is_empty());
+
+        for i in 0..8 {
+            let root = set.root();
+            let proof = set.insert(nullifier(i)).unwrap();
+            assert!(proof.verify_non_membership(&nullifier(i), &root));
+            assert_eq!(
+                proof.root_after_insert(&nullifier(i), &root),
+                Some(set.root())
+            );
+        }
+        assert_eq!(set.len(), 8);
+        assert_ne!(set.root(), empty_root);
+
+        let root = set.root();
+        for i in 0..8 {
+            let proof = set.prove(&nullifier(i));
+            assert!(proof.verify_membership(&nullifier(i), &root));
+            assert!(!proof.verify_non_membership(&nullifier(i), &root));
+        }
+        let absent = set.prove(&nullifier(9));
+        assert!(absent.verify_non_membership(&nullifier(9), &root));
+        assert!(!absent.verify_membership(&nullifier(9), &root));
+    }
+
+    #[test]
+    fn test_nullifier_set_rejects_replay() {
+        let mut set = NullifierSet::new();
+        set.insert(nullifier(1)).unwrap();
+        assert!(matches!(
+            set.insert(nullifier(1)),
+            Err(ZKMTDError::Replayed { .. })
+        ));
+
+        // A stale non-membership proof does not verify against the new root
+        let mut fresh = NullifierSet::new();
+        let stale = fresh.insert(nullifier(1)).unwrap();
+        assert!(!stale.verify_non_membership(&nullifier(1), &fresh.root()));
+        assert!(stale
+            .root_after_insert(&nullifier(1), &fresh.root())
+            .is_none());
+    }
+
+    #[test]
+    fn test_smt_proof_rejects_malformed() {
+        let mut set = NullifierSet::new();
+        for i in 0..4 {
+            set.insert(nullifier(i)).unwrap();
+        }
+        let root = set.root();
+        let proof = set.prove(&nullifier(2));
+        assert!(proof.verify_membership(&nullifier(2), &root));
+
+        let mut bad = proof.clone();
+        bad.siblings.push([0u8; 32]);
+        assert!(!bad.verify_membership(&nullifier(2), &root));
+        let mut bad = proof.clone();
+        bad.bitmap[31] ^= 0x80;
+        assert!(!bad.verify_membership(&nullifier(2), &root));
+        assert!(!proof.verify_membership(&nullifier(3), &root));
+    }
+}
@@ -0,0 +1,215 @@
+//! BatchReport - per-proof outcomes of batch verification
+//!
+//! `verify_batch` answers one bit for the whole batch. A report records why
+//! each proof passed or failed, so a bad proof in a large batch can be found
+//! and dropped without re-verifying the rest one by one.
+
+use crate::utils::parallel;
+
+use alloc::vec::Vec;
+
+/// Outcome of verifying one proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofStatus {
+    Valid,
+    /// Proof epoch differs from the batch's or the verifier's epoch.
+    EpochMismatch {
+        expected: u64,
+        got: u64,
+    },
+    /// Leaf does not reach the trusted root through its inclusion path.
+    MerkleMismatch,
+    /// Proof carries another epoch's MTD params.
+    ParamsMismatch,
+    /// Binding hash does not match the proof contents.
+    BindingMismatch,
+    /// Timestamp outside its epoch or the verifier's freshness window.
+    TimestampRejected,
+    /// Epoch or seed is on the verifier's revocation list.
+    Revoked,
+    /// The STARK itself does not verify.
+    StarkFailure,
+    /// Proof could not be encoded for hashing.
+    Malformed,
+    /// Not checked: verification stopped at an earlier failure.
+    Skipped,
+}
+
+impl ProofStatus {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, ProofStatus::Valid)
+    }
+}
+
+/// Whether batch verification stops at the first failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailurePolicy {
+    /// Stop at the first failure; later proofs are [`ProofStatus::Skipped`].
+    #[default]
+    StopAtFirst,
+    /// Check every proof.
+    Continue,
+}
+
+/// Number of proofs per status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchCounts {
+    pub valid: usize,
+    pub epoch_mismatch: usize,
+    pub merkle_mismatch: usize,
+    pub params_mismatch: usize,
+    pub binding_mismatch: usize,
+    pub timestamp_rejected: usize,
+    pub revoked: usize,
+    pub stark_failure: usize,
+    pub malformed: usize,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchReport {
+    /// One status per proof, in batch order.
+    pub statuses: Vec<ProofStatus>,
+    /// Whether the batch root matches its proofs; `None` when proofs were
+    /// checked against a trusted root one path at a time.
+    pub root_valid: Option<bool>,
+}
+
+impl BatchReport {
+    /// Runs `check` over `count` proofs under `policy`. With
+    /// [`FailurePolicy::StopAtFirst`], nothing is checked if `root_valid`
+    /// is `Some(false)`, and proofs after the first failure are not checked
+    /// but reported as [`ProofStatus::Skipped`]. With the `parallel` feature
+    /// proofs are checked concurrently and the report is the same.
+    #[cfg_attr(not(feature = "full-p3"), allow(dead_code))]
+    pub(crate) fn collect<F>(
+        count: usize,
+        root_valid: Option<bool>,
+        policy: FailurePolicy,
+        check: F,
+    ) -> Self
+    where
+        F: Fn(usize) -> ProofStatus + Sync + Send,
+    {
+        let statuses = match policy {
+            FailurePolicy::StopAtFirst if root_valid == Some(false) => {
+                alloc::vec![ProofStatus::Skipped; count]
+            }
+            FailurePolicy::StopAtFirst => {
+                parallel::map_indices_until(count, check, |status| !status.is_valid())
+                    .into_iter()
+                    .map(|status| status.unwrap_or(ProofStatus::Skipped))
+                    .collect()
+            }
+            _ => parallel::map_indices(count, check),
+        };
+        Self {
+            statuses,
+            root_valid,
+        }
+    }
+
+    /// True iff the root (if any) matches and every proof is valid.
+    pub fn is_valid(&self) -> bool {
+        self.root_valid != Some(false) && self.statuses.iter().all(ProofStatus::is_valid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.statuses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statuses.is_empty()
+    }
+
+    pub fn valid_count(&self) -> usize {
+        self.statuses.iter().filter(|s| s.is_valid()).count()
+    }
+
+    /// Proofs checked and rejected (skipped proofs are not counted).
+    pub fn failed_count(&self) -> usize {
+        self.statuses
+            .iter()
+            .filter(|s| !s.is_valid() && **s != ProofStatus::Skipped)
+            .count()
+    }
+
+    /// `(index, status)` of every rejected proof.
+    pub fn failures(&self) -> impl Iterator<Item = (usize, ProofStatus)> + '_ {
+        self.statuses
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, s)| !s.is_valid() && *s != ProofStatus::Skipped)
+    }
+
+    pub fn counts(&self) -> BatchCounts {
+        let mut counts = BatchCounts::default();
+        for status in &self.statuses {
+            let slot = match status {
+                ProofStatus::Valid => &mut counts.valid,
+                ProofStatus::EpochMismatch { .. } => &mut counts.epoch_mismatch,
+                ProofStatus::MerkleMismatch => &mut counts.merkle_mismatch,
+                ProofStatus::ParamsMismatch => &mut counts.params_mismatch,
+                ProofStatus::BindingMismatch => &mut counts.binding_mismatch,
+                ProofStatus::TimestampRejected => &mut counts.timestamp_rejected,
+                ProofStatus::Revoked => &mut counts.revoked,
+                ProofStatus::StarkFailure => &mut counts.stark_failure,
+                ProofStatus::Malformed => &mut counts.malformed,
+                ProofStatus::Skipped => &mut counts.skipped,
+            };
+            *slot += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcomes(i: usize) -> ProofStatus {
+        match i {
+            1 => ProofStatus::BindingMismatch,
+            3 => ProofStatus::StarkFailure,
+            _ => ProofStatus::Valid,
+        }
+    }
+
+    #[test]
+    fn test_report_policies() {
+        let report = BatchReport::collect(5, Some(true), FailurePolicy::Continue, outcomes);
+        assert!(!report.is_valid());
+        assert_eq!(report.valid_count(), 3);
+        assert_eq!(report.failed_count(), 2);
+        assert_eq!(
+            report.failures().collect::<Vec<_>>(),
+            [
+                (1, ProofStatus::BindingMismatch),
+                (3, ProofStatus::StarkFailure)
+            ]
+        );
+
+        let report = BatchReport::collect(5, Some(true), FailurePolicy::StopAtFirst, outcomes);
+        assert_eq!(report.failed_count(), 1);
+        let counts = report.counts();
+        assert_eq!(
+            (counts.valid, counts.binding_mismatch, counts.skipped),
+            (1, 1, 3)
+        );
+    }
+
+    #[test]
+    fn test_report_root_mismatch() {
+        let all_valid = |_| ProofStatus::Valid;
+        let report = BatchReport::collect(3, Some(false), FailurePolicy::StopAtFirst, all_valid);
+        assert!(!report.is_valid());
+        assert_eq!(report.counts().skipped, 3);
+
+        let report = BatchReport::collect(3, Some(false), FailurePolicy::Continue, all_valid);
+        assert!(!report.is_valid());
+        assert_eq!(report.valid_count(), 3);
+
+        assert!(BatchReport::collect(3, None, FailurePolicy::Continue, all_valid).is_valid());
+    }
+}
@@ -0,0 +1,398 @@
+//! Merkle sum tree - per-user inclusion proofs for proof of liabilities
+//!
+//! Every node carries `(hash, sum)`: a leaf's sum is its balance, an inner
+//! node's sum is the sum of its children, and the node hash covers both
+//! children's hashes AND sums, so a custodian cannot shift value between
+//! subtrees without changing the root. Each user checks their own balance
+//! against the published root with a [`SumPath`]; the root sum is the total
+//! liability.
+//!
+//! Balances are `u64` and every sum is overflow-checked, so no subtree can
+//! wrap around to hide value. Each leaf also commits to its balance as
+//! `Poseidon2([balance, salt])[0]`, the public commitment of
+//! `stark::range_commit_air`. With `full-p3`, [`prove_leaf_balance`] proves
+//! in-circuit that the committed balance lies in `[0, 2^32)`, and
+//! [`SumPath::verify_with_range_proof`] checks that proof together with the
+//! leaf's inclusion. The account owner, who is given `salt`, checks that the
+//! commitment opens to their balance by rebuilding the leaf with
+//! [`SumLeaf::committed`].
+//!
+//! An odd node at the end of a level is paired with an empty node (zero hash,
+//! zero sum) rather than with itself, so padding never double-counts a
+//! balance. The leaf count is bound into the root (RT-2).
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+#[cfg(feature = "full-p3")]
+use crate::stark::real_stark::{ProofAirType, RealProof, RealStarkProver, RealStarkVerifier};
+use crate::utils::constants::{DOMAIN_SUM_TREE_LEAF, DOMAIN_SUM_TREE_NODE};
+use crate::utils::hash::{constant_time_eq_fixed, poseidon_hash};
+use crate::utils::parallel;
+
+use alloc::vec::Vec;
+
+/// One account: an identifier (e.g. a salted hash of the user id), its
+/// balance and the range-proof commitment to that balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SumLeaf {
+    pub id: [u8; 32],
+    pub balance: u64,
+    /// `Poseidon2([balance, salt])[0]` as a canonical Goldilocks element.
+    pub commitment: u64,
+}
+
+impl SumLeaf {
+    pub fn new(id: [u8; 32], balance: u64, commitment: u64) -> Self {
+        Self {
+            id,
+            balance,
+            commitment,
+        }
+    }
+
+    /// Leaf whose commitment is computed from `salt`; fails for balances the
+    /// range AIR cannot prove (`>= 2^32`).
+    #[cfg(feature = "full-p3")]
+    pub fn committed(id: [u8; 32], balance: u64, salt: u64) -> Result<Self> {
+        use p3_field::PrimeField64;
+
+        let commitment = crate::stark::range_commit_air::value_commitment(balance, salt)?;
+        Ok(Self::new(id, balance, commitment.as_canonical_u64()))
+    }
+
+    pub fn node(&self) -> SumNode {
+        let mut data = [0u8; 48];
+        data[..32].copy_from_slice(&self.id);
+        data[32..40].copy_from_slice(&self.balance.to_le_bytes());
+        data[40..].copy_from_slice(&self.commitment.to_le_bytes());
+        SumNode {
+            hash: poseidon_hash(&data, DOMAIN_SUM_TREE_LEAF),
+            sum: self.balance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SumNode {
+    pub hash: HashDigest,
+    pub sum: u64,
+}
+
+impl SumNode {
+    /// Padding for an odd node at the end of a level.
+    pub const EMPTY: SumNode = SumNode {
+        hash: [0u8; 32],
+        sum: 0,
+    };
+
+    /// Parent of `left` and `right`, or `None` if the sum overflows.
+    pub fn parent(left: &SumNode, right: &SumNode) -> Option<SumNode> {
+        let sum = left.sum.checked_add(right.sum)?;
+        let mut data = [0u8; 80];
+        data[..32].copy_from_slice(&left.hash);
+        data[32..40].copy_from_slice(&left.sum.to_le_bytes());
+        data[40..72].copy_from_slice(&right.hash);
+        data[72..].copy_from_slice(&right.sum.to_le_bytes());
+        Some(SumNode {
+            hash: poseidon_hash(&data, DOMAIN_SUM_TREE_NODE),
+            sum,
+        })
+    }
+}
+
+/// Bind the leaf count into the root hash (RT-2). The 48-byte input differs
+/// in length from an inner node's 80 bytes (and leaves use another domain).
+fn bind_root(inner: &SumNode, num_leaves: usize) -> SumNode {
+    let mut data = [0u8; 48];
+    data[..32].copy_from_slice(&inner.hash);
+    data[32..40].copy_from_slice(&inner.sum.to_le_bytes());
+    data[40..].copy_from_slice(&(num_leaves as u64).to_le_bytes());
+    SumNode {
+        hash: poseidon_hash(&data, DOMAIN_SUM_TREE_NODE),
+        sum: inner.sum,
+    }
+}
+
+fn overflow() -> ZKMTDError {
+    ZKMTDError::MerkleError {
+        reason: "Balance sum overflows u64".into(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MerkleSumTree {
+    levels: Vec<Vec<SumNode>>,
+    root: SumNode,
+}
+
+impl MerkleSumTree {
+    /// Build the tree; fails if `leaves` is empty or the total overflows.
+    pub fn new(leaves: &[SumLeaf]) -> Result<Self> {
+        if leaves.is_empty() {
+            return Err(ZKMTDError::MerkleError {
+                reason: "Leaves are empty".into(),
+            });
+        }
+
+        let mut levels = alloc::vec![parallel::map(leaves, SumLeaf::node)];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = parallel::map_chunks(&levels[levels.len() - 1], 2, |pair| {
+                SumNode::parent(&pair[0], pair.get(1).unwrap_or(&SumNode::EMPTY))
+            });
+            let next = next
+                .into_iter()
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(overflow)?;
+            levels.push(next);
+        }
+
+        let root = bind_root(&levels[levels.len() - 1][0], leaves.len());
+        Ok(Self { levels, root })
+    }
+
+    /// Root hash and total of all balances.
+    pub fn root(&self) -> &SumNode {
+        &self.root
+    }
+
+    pub fn total(&self) -> u64 {
+        self.root.sum
+    }
+
+    pub fn num_leaves(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn get_proof(&self, index: usize) -> Result<SumPath> {
+        if index >= self.num_leaves() {
+            return Err(ZKMTDError::MerkleError {
+                reason: alloc::format!("Invalid index: {} >= {}", index, self.num_leaves()),
+            });
+        }
+
+        let mut siblings = Vec::with_capacity(self.levels.len() - 1);
+        let mut current = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            siblings.push(level.get(current ^ 1).copied().unwrap_or(SumNode::EMPTY));
+            current /= 2;
+        }
+
+        Ok(SumPath {
+            leaf_index: index,
+            num_leaves: self.num_leaves(),
+            siblings,
+        })
+    }
+}
+
+/// Inclusion path for one balance. Sibling sums are revealed, so a user
+/// learns the totals of the subtrees next to their path, not other balances
+/// (except an adjacent leaf's).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumPath {
+    pub leaf_index: usize,
+    pub num_leaves: usize,
+    pub siblings: Vec<SumNode>,
+}
+
+impl SumPath {
+    /// Root for `leaf`, or `None` if the path is malformed or a sum
+    /// overflows.
+    pub fn compute_root(&self, leaf: &SumLeaf) -> Option<SumNode> {
+        // H-A: index inside the tree and a full-depth path
+        let n = self.num_leaves;
+        if n == 0 || self.leaf_index >= n {
+            return None;
+        }
+        let depth = (usize::BITS - (n - 1).leading_zeros()) as usize;
+        if self.siblings.len() != depth {
+            return None;
+        }
+
+        let mut current = leaf.node();
+        let mut index = self.leaf_index;
+        for sibling in &self.siblings {
+            current = if index.is_multiple_of(2) {
+                SumNode::parent(&current, sibling)?
+            } else {
+                SumNode::parent(sibling, &current)?
+            };
+            index /= 2;
+        }
+        Some(bind_root(&current, n))
+    }
+
+    /// Verify `leaf` against a root published by the custodian. Checks both
+    /// the hash and the total.
+    pub fn verify_against(&self, leaf: &SumLeaf, trusted_root: &SumNode) -> bool {
+        match self.compute_root(leaf) {
+            Some(root) => {
+                constant_time_eq_fixed(&root.hash, &trusted_root.hash)
+                    && root.sum == trusted_root.sum
+            }
+            None => false,
+        }
+    }
+
+    /// [`verify_against`](Self::verify_against) plus `range_proof`, a
+    /// [`prove_leaf_balance`] proof that the balance committed in `leaf` is
+    /// non-negative.
+    #[cfg(feature = "full-p3")]
+    pub fn verify_with_range_proof(
+        &self,
+        leaf: &SumLeaf,
+        trusted_root: &SumNode,
+        verifier: &RealStarkVerifier,
+        range_proof: &RealProof,
+    ) -> Result<bool> {
+        if !self.verify_against(leaf, trusted_root) {
+            return Ok(false);
+        }
+        verify_leaf_balance(verifier, leaf, range_proof)
+    }
+}
+
+/// Prove that `leaf`'s committed balance lies in `[0, 2^32)`: a committed
+/// range proof with threshold 0. `salt` must open `leaf.commitment` to
+/// `leaf.balance`.
+#[cfg(feature = "full-p3")]
+pub fn prove_leaf_balance(
+    prover: &RealStarkProver,
+    leaf: &SumLeaf,
+    salt: u64,
+) -> Result<RealProof> {
+    if SumLeaf::committed(leaf.id, leaf.balance, salt)? != *leaf {
+        return Err(ZKMTDError::InvalidWitness {
+            reason: "Salt does not open the leaf commitment to its balance".into(),
+        });
+    }
+    prover.prove_range_commit(leaf.balance, 0, salt)
+}
+
+/// Check a [`prove_leaf_balance`] proof against `leaf.commitment`.
+#[cfg(feature = "full-p3")]
+pub fn verify_leaf_balance(
+    verifier: &RealStarkVerifier,
+    leaf: &SumLeaf,
+    proof: &RealProof,
+) -> Result<bool> {
+    if proof.air_type != ProofAirType::RangeCommit || proof.public_values != [0, leaf.commitment] {
+        return Ok(false);
+    }
+    verifier.verify_range_commit(proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: usize) -> Vec<SumLeaf> {
+        (0..n)
+            .map(|i| SumLeaf::new([i as u8; 32], 100 * i as u64 + 7, i as u64))
+            .collect()
+    }
+
+    #[test]
+    fn test_sum_tree_inclusion() {
+        for n in 1..=9 {
+            let leaves = leaves(n);
+            let tree = MerkleSumTree::new(&leaves).unwrap();
+            let total: u64 = leaves.iter().map(|l| l.balance).sum();
+            assert_eq!(tree.total(), total);
+
+            for (i, leaf) in leaves.iter().enumerate() {
+                let path = tree.get_proof(i).unwrap();
+                assert!(path.verify_against(leaf, tree.root()), "n={} i={}", n, i);
+
+                let mut richer = *leaf;
+                richer.balance += 1;
+                assert!(!path.verify_against(&richer, tree.root()));
+            }
+        }
+        assert!(MerkleSumTree::new(&[]).is_err());
+    }
+
+    #[test]
+    fn test_sum_tree_rejects_tampering() {
+        let leaves = leaves(5);
+        let tree = MerkleSumTree::new(&leaves).unwrap();
+        let path = tree.get_proof(2).unwrap();
+
+        // Understated total
+        let mut root = *tree.root();
+        root.sum -= 1;
+        assert!(!path.verify_against(&leaves[2], &root));
+
+        // Moving value between a sibling subtree and the claimed total
+        let mut moved = path.clone();
+        moved.siblings[1].sum -= 1;
+        assert!(!moved.verify_against(&leaves[2], tree.root()));
+
+        // H-A: out-of-range index, wrong leaf count, padded path
+        let mut bad = path.clone();
+        bad.leaf_index += 8;
+        assert!(!bad.verify_against(&leaves[2], tree.root()));
+        let mut bad = path.clone();
+        bad.num_leaves = 6;
+        assert!(!bad.verify_against(&leaves[2], tree.root()));
+        let mut bad = path.clone();
+        bad.siblings.push(SumNode::EMPTY);
+        assert!(!bad.verify_against(&leaves[2], tree.root()));
+    }
+
+    #[test]
+    fn test_sum_tree_overflow() {
+        let leaves = [
+            SumLeaf::new([1u8; 32], u64::MAX, 0),
+            SumLeaf::new([2u8; 32], 1, 0),
+        ];
+        assert!(matches!(
+            MerkleSumTree::new(&leaves),
+            Err(ZKMTDError::MerkleError { .. })
+        ));
+
+        // A path whose sibling sums overflow does not verify
+        let leaves = [SumLeaf::new([1u8; 32], 5, 0), SumLeaf::new([2u8; 32], 6, 0)];
+        let tree = MerkleSumTree::new(&leaves).unwrap();
+        let mut path = tree.get_proof(0).unwrap();
+        path.siblings[0].sum = u64::MAX;
+        assert!(path.compute_root(&leaves[0]).is_none());
+    }
+
+    #[cfg(feature = "full-p3")]
+    #[test]
+    fn test_sum_tree_leaf_range_proof() {
+        use crate::stark::air::SimpleAir;
+
+        let salts = [11u64, 22, 33];
+        let leaves: Vec<SumLeaf> = [5u64, 0, 1 << 31]
+            .iter()
+            .zip(salts)
+            .enumerate()
+            .map(|(i, (&balance, salt))| SumLeaf::committed([i as u8; 32], balance, salt).unwrap())
+            .collect();
+        let tree = MerkleSumTree::new(&leaves).unwrap();
+        let prover = RealStarkProver::new(SimpleAir::fibonacci()).unwrap();
+        let verifier = prover.get_verifier();
+
+        let path = tree.get_proof(2).unwrap();
+        let proof = prove_leaf_balance(&prover, &leaves[2], salts[2]).unwrap();
+        assert!(path
+            .verify_with_range_proof(&leaves[2], tree.root(), &verifier, &proof)
+            .unwrap());
+
+        // The proof is bound to this leaf's commitment
+        let other = tree.get_proof(0).unwrap();
+        assert!(!other
+            .verify_with_range_proof(&leaves[0], tree.root(), &verifier, &proof)
+            .unwrap());
+        // The owner's salt must open the commitment, and the AIR only covers
+        // balances below 2^32
+        assert!(prove_leaf_balance(&prover, &leaves[2], salts[1]).is_err());
+        assert!(SumLeaf::committed([9u8; 32], 1 << 32, 1).is_err());
+        // A commitment that does not match the leaf breaks inclusion
+        let mut swapped = leaves[2];
+        swapped.commitment = leaves[1].commitment;
+        assert!(!path.verify_against(&swapped, tree.root()));
+    }
+}
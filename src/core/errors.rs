@@ -95,6 +95,20 @@ pub enum ZKMTDError {
         #[cfg(not(feature = "alloc"))]
         reason: &'static str,
     },
+    /// Proof rejected by a revocation list (revoked epoch or seed).
+    Revoked {
+        #[cfg(feature = "alloc")]
+        reason: String,
+        #[cfg(not(feature = "alloc"))]
+        reason: &'static str,
+    },
+    /// Proof nullifier already spent (replay).
+    Replayed {
+        #[cfg(feature = "alloc")]
+        reason: String,
+        #[cfg(not(feature = "alloc"))]
+        reason: &'static str,
+    },
 }
 
 impl fmt::Display for ZKMTDError {
@@ -145,6 +159,12 @@ impl fmt::Display for ZKMTDError {
             ZKMTDError::InternalError { reason } => {
                 write!(f, "Internal error: {}", reason)
             }
+            ZKMTDError::Revoked { reason } => {
+                write!(f, "Revoked: {}", reason)
+            }
+            ZKMTDError::Replayed { reason } => {
+                write!(f, "Replayed: {}", reason)
+            }
         }
     }
 }
@@ -306,6 +326,26 @@ mod tests {
         assert!(msg.contains("unexpected state"));
     }
 
+    #[test]
+    fn test_error_display_revoked() {
+        let err = ZKMTDError::Revoked {
+            reason: "epoch 5003 is revoked".into(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("Revoked"));
+        assert!(msg.contains("epoch 5003"));
+    }
+
+    #[test]
+    fn test_error_display_replayed() {
+        let err = ZKMTDError::Replayed {
+            reason: "nullifier already spent".into(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("Replayed"));
+        assert!(msg.contains("nullifier"));
+    }
+
     #[test]
     fn test_error_debug() {
         let err = ZKMTDError::InvalidProof;
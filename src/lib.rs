@@ -13,6 +13,8 @@ pub mod adapters;
 pub mod batching;
 pub mod core;
 pub mod mtd;
+#[cfg(feature = "alloc")]
+pub mod signature;
 pub mod stark;
 pub mod utils;
 
@@ -27,7 +29,7 @@ pub use crate::core::{
     types::{CommittedPublicInputs, Proof, PublicInputs, Witness},
 };
 
-pub use crate::mtd::{Epoch, MTDManager};
+pub use crate::mtd::{DrbgEntropy, Epoch, MTDManager};
 
 #[cfg(feature = "std")]
 pub use crate::mtd::entropy::SystemEntropy;
@@ -35,8 +37,14 @@ pub use crate::mtd::entropy::SystemEntropy;
 #[allow(deprecated)]
 pub use crate::stark::{MTDProver, MTDVerifier, StarkConfig};
 
+#[allow(deprecated)]
 pub use crate::batching::{BatchProver, BatchVerifier, ProofBatch};
 
+#[cfg(all(feature = "full-p3", feature = "alloc"))]
+pub use crate::batching::{
+    IntegratedBatch, IntegratedBatchVerifier, MultiEpochBatch, MultiEpochBatchVerifier,
+};
+
 #[cfg(any(feature = "solana-program", feature = "std"))]
 pub use crate::solana::{LightweightProof, OnchainVerifier, ProofCommitment};
 
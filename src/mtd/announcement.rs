@@ -0,0 +1,294 @@
+//! Epoch parameter announcements - seedless public verification
+//!
+//! The seed holder commits to a schedule of future epochs' [`WarpingParams`]
+//! with a single Merkle root, published ahead of time. At the start of each
+//! epoch it reveals that epoch's params together with an inclusion path.
+//! Verifiers check the path against the published root and then verify
+//! proofs with the announced params, without ever holding the seed.
+//!
+//! Params must only be revealed once their epoch starts: they are all a
+//! prover needs, so early disclosure hands out proving ability early.
+
+use crate::batching::merkle::{MerkleArity, MerklePath, MerkleTree};
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+use crate::mtd::{Epoch, WarpingParams};
+use crate::utils::constants::{DOMAIN_EPOCH_ANNOUNCEMENT, MAX_MERKLE_DEPTH, MAX_SCHEDULE_EPOCHS};
+use crate::utils::hash::poseidon_hash;
+
+use alloc::vec::Vec;
+
+/// Size of a serialized [`WarpingParams`].
+const PARAMS_BYTES: usize = 104;
+
+/// Published commitment to the params of epochs
+/// `[start_epoch, start_epoch + num_epochs)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleCommitment {
+    pub start_epoch: Epoch,
+    pub num_epochs: u64,
+    pub root: HashDigest,
+}
+
+impl ScheduleCommitment {
+    pub fn contains(&self, epoch: Epoch) -> bool {
+        epoch >= self.start_epoch && epoch.value() - self.start_epoch.value() < self.num_epochs
+    }
+
+    pub fn to_bytes(&self) -> [u8; 48] {
+        let mut bytes = [0u8; 48];
+        bytes[..8].copy_from_slice(&self.start_epoch.to_bytes());
+        bytes[8..16].copy_from_slice(&self.num_epochs.to_le_bytes());
+        bytes[16..].copy_from_slice(&self.root);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 48 {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Invalid byte length: {} (expected: 48)", bytes.len()),
+            });
+        }
+        let mut epoch = [0u8; 8];
+        epoch.copy_from_slice(&bytes[..8]);
+        let mut count = [0u8; 8];
+        count.copy_from_slice(&bytes[8..16]);
+        let mut root = [0u8; 32];
+        root.copy_from_slice(&bytes[16..]);
+        Ok(Self {
+            start_epoch: Epoch::from_bytes(epoch)?,
+            num_epochs: u64::from_le_bytes(count),
+            root,
+        })
+    }
+}
+
+/// One epoch's revealed params plus their inclusion path in the schedule.
+#[derive(Debug, Clone)]
+pub struct EpochAnnouncement {
+    pub params: WarpingParams,
+    pub path: MerklePath,
+}
+
+impl EpochAnnouncement {
+    pub fn epoch(&self) -> Epoch {
+        self.params.epoch
+    }
+
+    /// Check the announcement against a TRUSTED schedule commitment (obtained
+    /// out of band, never from the announcement itself).
+    pub fn verify(&self, commitment: &ScheduleCommitment) -> bool {
+        if !commitment.contains(self.params.epoch) {
+            return false;
+        }
+        let expected_index = self.params.epoch.value() - commitment.start_epoch.value();
+        if self.path.leaf_index as u64 != expected_index
+            || self.path.num_leaves as u64 != commitment.num_epochs
+            || self.path.len() > MAX_MERKLE_DEPTH
+        {
+            return false;
+        }
+        self.path
+            .verify_against(&announcement_leaf(&self.params), &commitment.root)
+    }
+
+    /// `params ∥ leaf_index ∥ num_leaves ∥ depth ∥ siblings`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(PARAMS_BYTES + 17 + self.path.len() * 32);
+        bytes.extend_from_slice(&self.params.to_bytes());
+        bytes.extend_from_slice(&(self.path.leaf_index as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.path.num_leaves as u64).to_le_bytes());
+        bytes.push(self.path.len() as u8);
+        for sibling in &self.path.siblings {
+            bytes.extend_from_slice(sibling);
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let header = PARAMS_BYTES + 17;
+        if bytes.len() < header {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Announcement too short: {} bytes", bytes.len()),
+            });
+        }
+        let params = WarpingParams::from_bytes(&bytes[..PARAMS_BYTES])?;
+        let read_u64 = |at: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&bytes[at..at + 8]);
+            u64::from_le_bytes(buf)
+        };
+        let leaf_index = read_u64(PARAMS_BYTES);
+        let num_leaves = read_u64(PARAMS_BYTES + 8);
+        let depth = bytes[PARAMS_BYTES + 16] as usize;
+        if depth > MAX_MERKLE_DEPTH || bytes.len() != header + depth * 32 {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Invalid announcement path (depth {})", depth),
+            });
+        }
+        let siblings = bytes[header..]
+            .chunks_exact(32)
+            .map(|c| {
+                let mut s = [0u8; 32];
+                s.copy_from_slice(c);
+                s
+            })
+            .collect();
+
+        let mut path = MerklePath {
+            leaf_index: leaf_index as usize,
+            siblings,
+            root: [0u8; 32],
+            num_leaves: num_leaves as usize,
+            arity: MerkleArity::Binary,
+        };
+        // The embedded root is never trusted; callers verify against a commitment.
+        path.root = path.compute_root(&announcement_leaf(&params));
+        Ok(Self { params, path })
+    }
+}
+
+/// Seed-holder side: derives and commits to a schedule of epoch params.
+/// Holds only the derived params, never the seed itself.
+#[derive(Debug, Clone)]
+pub struct AnnouncementPublisher {
+    start_epoch: Epoch,
+    params: Vec<WarpingParams>,
+    tree: MerkleTree,
+}
+
+impl AnnouncementPublisher {
+    pub fn new(seed: &[u8], start_epoch: Epoch, num_epochs: u64) -> Result<Self> {
+        if num_epochs == 0 || num_epochs > MAX_SCHEDULE_EPOCHS {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: alloc::format!(
+                    "Schedule length {} out of range (1..={})",
+                    num_epochs,
+                    MAX_SCHEDULE_EPOCHS
+                ),
+            });
+        }
+        // Reject schedules running past MAX_EPOCH up front.
+        start_epoch.advance(num_epochs - 1)?;
+
+        let params = (0..num_epochs)
+            .map(|i| WarpingParams::generate(seed, Epoch::new(start_epoch.value() + i)))
+            .collect::<Result<Vec<_>>>()?;
+        let tree = MerkleTree::new(params.iter().map(announcement_leaf).collect())?;
+
+        Ok(Self {
+            start_epoch,
+            params,
+            tree,
+        })
+    }
+
+    pub fn commitment(&self) -> ScheduleCommitment {
+        ScheduleCommitment {
+            start_epoch: self.start_epoch,
+            num_epochs: self.params.len() as u64,
+            root: *self.tree.root(),
+        }
+    }
+
+    /// Reveal the announcement for `epoch`. Call only once `epoch` has started.
+    pub fn announce(&self, epoch: Epoch) -> Result<EpochAnnouncement> {
+        if !self.commitment().contains(epoch) {
+            return Err(ZKMTDError::InvalidEpoch {
+                current: epoch.value(),
+                reason: "Epoch is outside the committed schedule".into(),
+            });
+        }
+        let index = (epoch.value() - self.start_epoch.value()) as usize;
+        Ok(EpochAnnouncement {
+            params: self.params[index].clone(),
+            path: self.tree.get_proof(index)?,
+        })
+    }
+}
+
+/// Leaf hash for one epoch's params in the schedule tree.
+pub fn announcement_leaf(params: &WarpingParams) -> HashDigest {
+    poseidon_hash(&params.to_bytes(), DOMAIN_EPOCH_ANNOUNCEMENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_announce_and_verify_schedule() {
+        let publisher = AnnouncementPublisher::new(b"publisher-seed", Epoch::new(100), 24).unwrap();
+        let commitment = publisher.commitment();
+
+        for e in 100..124 {
+            let announcement = publisher.announce(Epoch::new(e)).unwrap();
+            assert!(announcement.verify(&commitment), "epoch {} rejected", e);
+            assert_eq!(
+                announcement.params,
+                WarpingParams::generate(b"publisher-seed", Epoch::new(e)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_announce_outside_schedule() {
+        let publisher = AnnouncementPublisher::new(b"publisher-seed", Epoch::new(100), 4).unwrap();
+        assert!(publisher.announce(Epoch::new(99)).is_err());
+        assert!(publisher.announce(Epoch::new(104)).is_err());
+    }
+
+    #[test]
+    fn test_tampered_params_rejected() {
+        let publisher = AnnouncementPublisher::new(b"publisher-seed", Epoch::new(100), 8).unwrap();
+        let commitment = publisher.commitment();
+
+        let mut announcement = publisher.announce(Epoch::new(103)).unwrap();
+        announcement.params.fri_seed[0] ^= 0xFF;
+        assert!(!announcement.verify(&commitment));
+    }
+
+    #[test]
+    fn test_params_from_other_seed_rejected() {
+        let honest = AnnouncementPublisher::new(b"publisher-seed", Epoch::new(100), 8).unwrap();
+        let forged = AnnouncementPublisher::new(b"attacker-seed", Epoch::new(100), 8).unwrap();
+
+        let announcement = forged.announce(Epoch::new(101)).unwrap();
+        assert!(!announcement.verify(&honest.commitment()));
+    }
+
+    #[test]
+    fn test_announcement_replayed_at_other_index() {
+        let publisher = AnnouncementPublisher::new(b"publisher-seed", Epoch::new(100), 8).unwrap();
+        let commitment = publisher.commitment();
+
+        let mut announcement = publisher.announce(Epoch::new(101)).unwrap();
+        announcement.path.leaf_index = 2;
+        assert!(!announcement.verify(&commitment));
+    }
+
+    #[test]
+    fn test_announcement_serialization() {
+        let publisher = AnnouncementPublisher::new(b"publisher-seed", Epoch::new(100), 5).unwrap();
+        let commitment = publisher.commitment();
+        let announcement = publisher.announce(Epoch::new(104)).unwrap();
+
+        let decoded = EpochAnnouncement::from_bytes(&announcement.to_bytes()).unwrap();
+        assert_eq!(decoded.params, announcement.params);
+        assert!(decoded.verify(&commitment));
+
+        let decoded_commitment = ScheduleCommitment::from_bytes(&commitment.to_bytes()).unwrap();
+        assert_eq!(decoded_commitment, commitment);
+
+        assert!(EpochAnnouncement::from_bytes(&announcement.to_bytes()[..50]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_schedule_length() {
+        assert!(AnnouncementPublisher::new(b"seed", Epoch::new(0), 0).is_err());
+        assert!(
+            AnnouncementPublisher::new(b"seed", Epoch::new(0), MAX_SCHEDULE_EPOCHS + 1).is_err()
+        );
+        assert!(AnnouncementPublisher::new(b"", Epoch::new(0), 4).is_err());
+    }
+}
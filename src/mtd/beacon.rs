@@ -0,0 +1,606 @@
+//! BeaconEntropy - epoch entropy from a public, hash-chained randomness beacon
+//!
+//! A beacon publishes one round every `period_secs` from `genesis_time`. Each
+//! round carries its number, 32 bytes of randomness and the digest of the
+//! previous round, the first round linking to a genesis digest that commits
+//! to the chain id and schedule. Anyone holding the [`BeaconInfo`] can re-walk
+//! the chain, so epoch randomness taken from it is auditable: an operator
+//! cannot substitute a round without breaking every later link.
+//!
+//! The round aligned to an epoch is the last one published at or before the
+//! epoch's start. [`MemoryBeacon`] and the std [`FileBeacon`] are local
+//! beacons for tests and self-hosted deployments.
+//!
+//! Beacon output is public, so it never stands in for secure entropy. It
+//! enters MTD through [`BeaconEntropy::bind_seed`] instead, which mixes the
+//! round into the secret seed (`MTDManager::from_beacon`,
+//! `IntegratedProver::from_beacon`): the epoch's params then depend on
+//! randomness the operator could not choose, and anyone holding the seed
+//! re-derives them from the public chain.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::traits::EntropySource;
+use crate::core::types::HashDigest;
+use crate::mtd::Epoch;
+use crate::utils::constants::{
+    DOMAIN_BEACON_ENTROPY, DOMAIN_BEACON_GENESIS, DOMAIN_BEACON_ROUND, DOMAIN_BEACON_SEED,
+    MAX_BEACON_CHAIN_ROUNDS,
+};
+use crate::utils::hash::poseidon_hash;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use zeroize::Zeroizing;
+
+/// Encoded size of a [`BeaconRound`]: round || randomness || previous.
+pub const BEACON_ROUND_BYTES: usize = 8 + 32 + 32;
+
+/// Public description of a beacon chain, distributed out of band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeaconInfo {
+    genesis_time: u64,
+    period_secs: u64,
+    genesis: HashDigest,
+}
+
+impl BeaconInfo {
+    pub fn new(chain_id: &[u8], genesis_time: u64, period_secs: u64) -> Result<Self> {
+        if period_secs == 0 {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: "Beacon period must be non-zero".into(),
+            });
+        }
+        let mut data = Vec::with_capacity(16 + chain_id.len());
+        data.extend_from_slice(&genesis_time.to_le_bytes());
+        data.extend_from_slice(&period_secs.to_le_bytes());
+        data.extend_from_slice(chain_id);
+        Ok(Self {
+            genesis_time,
+            period_secs,
+            genesis: poseidon_hash(&data, DOMAIN_BEACON_GENESIS),
+        })
+    }
+
+    pub fn genesis_time(&self) -> u64 {
+        self.genesis_time
+    }
+
+    pub fn period_secs(&self) -> u64 {
+        self.period_secs
+    }
+
+    /// Digest round 1 links to.
+    pub fn genesis(&self) -> HashDigest {
+        self.genesis
+    }
+
+    /// Last round published at or before the start of `epoch`.
+    pub fn round_for_epoch(&self, epoch: Epoch) -> Result<u64> {
+        let start = epoch.start_timestamp();
+        if start < self.genesis_time {
+            return Err(ZKMTDError::EntropyError {
+                reason: alloc::format!("Epoch {} starts before beacon genesis", epoch.value()),
+            });
+        }
+        Ok((start - self.genesis_time) / self.period_secs + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeaconRound {
+    pub round: u64,
+    pub randomness: [u8; 32],
+    /// Digest of round `round - 1`, or the genesis digest for round 1.
+    pub previous: HashDigest,
+}
+
+impl BeaconRound {
+    pub fn to_bytes(&self) -> [u8; BEACON_ROUND_BYTES] {
+        let mut out = [0u8; BEACON_ROUND_BYTES];
+        out[..8].copy_from_slice(&self.round.to_le_bytes());
+        out[8..40].copy_from_slice(&self.randomness);
+        out[40..].copy_from_slice(&self.previous);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8; BEACON_ROUND_BYTES]) -> Self {
+        let mut round = [0u8; 8];
+        round.copy_from_slice(&bytes[..8]);
+        let mut randomness = [0u8; 32];
+        randomness.copy_from_slice(&bytes[8..40]);
+        let mut previous = [0u8; 32];
+        previous.copy_from_slice(&bytes[40..]);
+        Self {
+            round: u64::from_le_bytes(round),
+            randomness,
+            previous,
+        }
+    }
+
+    /// Digest the next round links to.
+    pub fn digest(&self) -> HashDigest {
+        poseidon_hash(&self.to_bytes(), DOMAIN_BEACON_ROUND)
+    }
+}
+
+/// Check that `rounds` continue the chain from `anchor` (the round number
+/// and digest of the last trusted round; `(0, genesis)` for the start).
+pub fn verify_chain(anchor: (u64, HashDigest), rounds: &[BeaconRound]) -> Result<()> {
+    let (mut number, mut digest) = anchor;
+    for round in rounds {
+        if round.round != number.wrapping_add(1) || round.previous != digest {
+            return Err(ZKMTDError::EntropyError {
+                reason: alloc::format!("Beacon chain broken at round {}", round.round),
+            });
+        }
+        number = round.round;
+        digest = round.digest();
+    }
+    Ok(())
+}
+
+/// Where beacon rounds come from (an HTTP relay, a file, memory).
+pub trait BeaconSource {
+    /// Highest round available, 0 if none.
+    fn latest_round(&mut self) -> Result<u64>;
+    fn round(&mut self, number: u64) -> Result<BeaconRound>;
+}
+
+/// In-memory local beacon.
+#[derive(Debug, Clone)]
+pub struct MemoryBeacon {
+    info: BeaconInfo,
+    rounds: Vec<BeaconRound>,
+}
+
+impl MemoryBeacon {
+    pub fn new(info: BeaconInfo) -> Self {
+        Self {
+            info,
+            rounds: Vec::new(),
+        }
+    }
+
+    pub fn info(&self) -> &BeaconInfo {
+        &self.info
+    }
+
+    /// Publish the next round with `randomness`.
+    pub fn append(&mut self, randomness: [u8; 32]) -> BeaconRound {
+        let round = next_round(&self.info, self.rounds.last(), randomness);
+        self.rounds.push(round);
+        round
+    }
+}
+
+impl BeaconSource for MemoryBeacon {
+    fn latest_round(&mut self) -> Result<u64> {
+        Ok(self.rounds.len() as u64)
+    }
+
+    fn round(&mut self, number: u64) -> Result<BeaconRound> {
+        number
+            .checked_sub(1)
+            .and_then(|i| self.rounds.get(i as usize))
+            .copied()
+            .ok_or_else(|| missing_round(number))
+    }
+}
+
+fn next_round(info: &BeaconInfo, last: Option<&BeaconRound>, randomness: [u8; 32]) -> BeaconRound {
+    match last {
+        Some(last) => BeaconRound {
+            round: last.round + 1,
+            randomness,
+            previous: last.digest(),
+        },
+        None => BeaconRound {
+            round: 1,
+            randomness,
+            previous: info.genesis(),
+        },
+    }
+}
+
+fn missing_round(number: u64) -> ZKMTDError {
+    ZKMTDError::EntropyError {
+        reason: alloc::format!("Beacon round {} is not available", number),
+    }
+}
+
+/// Entropy for one epoch, taken from its aligned beacon round after the
+/// chain leading to it has been verified.
+#[derive(Debug, Clone)]
+pub struct BeaconEntropy {
+    epoch: Epoch,
+    round: BeaconRound,
+    counter: u64,
+}
+
+impl BeaconEntropy {
+    /// Verify the chain from genesis up to the round aligned with `epoch`.
+    pub fn fetch<S: BeaconSource + ?Sized>(
+        source: &mut S,
+        info: &BeaconInfo,
+        epoch: Epoch,
+    ) -> Result<Self> {
+        Self::fetch_from(source, info, (0, info.genesis()), epoch)
+    }
+
+    /// Like [`fetch`](Self::fetch), but trusting `checkpoint` (round number
+    /// and digest of an already verified round) so only later rounds are
+    /// walked.
+    pub fn fetch_from<S: BeaconSource + ?Sized>(
+        source: &mut S,
+        info: &BeaconInfo,
+        checkpoint: (u64, HashDigest),
+        epoch: Epoch,
+    ) -> Result<Self> {
+        let target = info.round_for_epoch(epoch)?;
+        if target <= checkpoint.0 {
+            return Err(ZKMTDError::EntropyError {
+                reason: alloc::format!(
+                    "Beacon round {} for epoch {} precedes checkpoint round {}",
+                    target,
+                    epoch.value(),
+                    checkpoint.0
+                ),
+            });
+        }
+        if target - checkpoint.0 > MAX_BEACON_CHAIN_ROUNDS {
+            return Err(ZKMTDError::ResourceLimitExceeded {
+                reason: alloc::format!(
+                    "Beacon chain walk of {} rounds exceeds {}; use a later checkpoint",
+                    target - checkpoint.0,
+                    MAX_BEACON_CHAIN_ROUNDS
+                ),
+            });
+        }
+        if source.latest_round()? < target {
+            return Err(missing_round(target));
+        }
+
+        let rounds = (checkpoint.0 + 1..=target)
+            .map(|n| source.round(n))
+            .collect::<Result<Vec<_>>>()?;
+        verify_chain(checkpoint, &rounds)?;
+
+        Ok(Self {
+            epoch,
+            round: rounds[rounds.len() - 1],
+            counter: 0,
+        })
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// The verified round the entropy is derived from.
+    pub fn round(&self) -> &BeaconRound {
+        &self.round
+    }
+
+    /// Epoch seed mixing `seed` with this round: the seed MTD derives the
+    /// epoch's params from on the beacon path.
+    pub fn bind_seed(&self, seed: &[u8]) -> Zeroizing<HashDigest> {
+        let mut input = Zeroizing::new(Vec::with_capacity(seed.len() + 48));
+        input.extend_from_slice(seed);
+        input.extend_from_slice(&self.round.randomness);
+        input.extend_from_slice(&self.round.round.to_le_bytes());
+        input.extend_from_slice(&self.epoch.value().to_le_bytes());
+        Zeroizing::new(poseidon_hash(&input, DOMAIN_BEACON_SEED))
+    }
+
+    fn block(&self, counter: u64) -> HashDigest {
+        let mut input = [0u8; 48];
+        input[..32].copy_from_slice(&self.round.randomness);
+        input[32..40].copy_from_slice(&self.epoch.value().to_le_bytes());
+        input[40..].copy_from_slice(&counter.to_le_bytes());
+        poseidon_hash(&input, DOMAIN_BEACON_ENTROPY)
+    }
+}
+
+impl EntropySource for BeaconEntropy {
+    fn generate(&mut self, num_bytes: usize) -> Result<Vec<u8>> {
+        let mut buffer = vec![0u8; num_bytes];
+        self.fill_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn fill_bytes(&mut self, output: &mut [u8]) -> Result<()> {
+        for chunk in output.chunks_mut(32) {
+            let block = self.block(self.counter);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            self.counter = self.counter.wrapping_add(1);
+        }
+        Ok(())
+    }
+
+    fn entropy_bits(&self) -> usize {
+        256
+    }
+
+    fn is_cryptographically_secure(&self) -> bool {
+        // Public randomness: unpredictable before its round, but not secret
+        // afterwards. Suitable for auditable epoch parameters, not for keys,
+        // so it must not pass the checks that gate key material.
+        false
+    }
+}
+
+/// File-backed local beacon: a header holding the [`BeaconInfo`] followed by
+/// fixed-size round records, appended as rounds are published.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct FileBeacon {
+    path: std::path::PathBuf,
+    info: BeaconInfo,
+}
+
+#[cfg(feature = "std")]
+const FILE_MAGIC: &[u8; 8] = b"ZKMTDBCN";
+#[cfg(feature = "std")]
+const FILE_VERSION: u8 = 1;
+#[cfg(feature = "std")]
+const FILE_HEADER_BYTES: usize = 8 + 1 + 8 + 8 + 32;
+
+#[cfg(feature = "std")]
+fn io_err(e: std::io::Error) -> ZKMTDError {
+    ZKMTDError::InternalError {
+        reason: alloc::format!("Beacon file I/O failed: {}", e),
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileBeacon {
+    /// Start a new beacon file at `path`, replacing any existing one.
+    pub fn create<P: Into<std::path::PathBuf>>(path: P, info: BeaconInfo) -> Result<Self> {
+        let path = path.into();
+        let mut header = Vec::with_capacity(FILE_HEADER_BYTES);
+        header.extend_from_slice(FILE_MAGIC);
+        header.push(FILE_VERSION);
+        header.extend_from_slice(&info.genesis_time.to_le_bytes());
+        header.extend_from_slice(&info.period_secs.to_le_bytes());
+        header.extend_from_slice(&info.genesis);
+        std::fs::write(&path, header).map_err(io_err)?;
+        Ok(Self { path, info })
+    }
+
+    /// Open an existing beacon file.
+    pub fn open<P: Into<std::path::PathBuf>>(path: P) -> Result<Self> {
+        use std::io::Read;
+
+        let path = path.into();
+        let mut header = [0u8; FILE_HEADER_BYTES];
+        std::fs::File::open(&path)
+            .and_then(|mut f| f.read_exact(&mut header))
+            .map_err(io_err)?;
+        if &header[..8] != FILE_MAGIC || header[8] != FILE_VERSION {
+            return Err(ZKMTDError::SerializationError {
+                reason: "Not a beacon file (bad magic or version)".into(),
+            });
+        }
+        let read_u64 = |at: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&header[at..at + 8]);
+            u64::from_le_bytes(buf)
+        };
+        let period_secs = read_u64(17);
+        if period_secs == 0 {
+            return Err(ZKMTDError::SerializationError {
+                reason: "Beacon file has a zero period".into(),
+            });
+        }
+        let mut genesis = [0u8; 32];
+        genesis.copy_from_slice(&header[25..]);
+        let info = BeaconInfo {
+            genesis_time: read_u64(9),
+            period_secs,
+            genesis,
+        };
+        Ok(Self { path, info })
+    }
+
+    /// The info recorded in the file. Verifiers should compare it against
+    /// (or simply use) their own out-of-band copy.
+    pub fn info(&self) -> &BeaconInfo {
+        &self.info
+    }
+
+    /// Publish the next round with `randomness`.
+    pub fn append(&mut self, randomness: [u8; 32]) -> Result<BeaconRound> {
+        use std::io::Write;
+
+        let latest = self.latest_round()?;
+        let last = match latest {
+            0 => None,
+            n => Some(self.round(n)?),
+        };
+        let round = next_round(&self.info, last.as_ref(), randomness);
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .map_err(io_err)?;
+        file.write_all(&round.to_bytes()).map_err(io_err)?;
+        file.sync_all().map_err(io_err)?;
+        Ok(round)
+    }
+}
+
+#[cfg(feature = "std")]
+impl BeaconSource for FileBeacon {
+    fn latest_round(&mut self) -> Result<u64> {
+        let len = std::fs::metadata(&self.path).map_err(io_err)?.len() as usize;
+        let body = len.saturating_sub(FILE_HEADER_BYTES);
+        if !body.is_multiple_of(BEACON_ROUND_BYTES) {
+            return Err(ZKMTDError::SerializationError {
+                reason: "Beacon file has a truncated round record".into(),
+            });
+        }
+        Ok((body / BEACON_ROUND_BYTES) as u64)
+    }
+
+    fn round(&mut self, number: u64) -> Result<BeaconRound> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        if number == 0 || number > self.latest_round()? {
+            return Err(missing_round(number));
+        }
+        let offset = FILE_HEADER_BYTES as u64 + (number - 1) * BEACON_ROUND_BYTES as u64;
+        let mut record = [0u8; BEACON_ROUND_BYTES];
+        let mut file = std::fs::File::open(&self.path).map_err(io_err)?;
+        file.seek(SeekFrom::Start(offset)).map_err(io_err)?;
+        file.read_exact(&mut record).map_err(io_err)?;
+        Ok(BeaconRound::from_bytes(&record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::EPOCH_DURATION_SECS;
+
+    const PERIOD: u64 = 30;
+
+    fn info() -> BeaconInfo {
+        BeaconInfo::new(b"test-beacon", 0, PERIOD).unwrap()
+    }
+
+    fn randomness(i: u64) -> [u8; 32] {
+        poseidon_hash(&i.to_le_bytes(), b"test-randomness")
+    }
+
+    /// Enough rounds to cover epoch `epochs - 1`.
+    fn beacon(epochs: u64) -> MemoryBeacon {
+        let mut beacon = MemoryBeacon::new(info());
+        for i in 0..=epochs * EPOCH_DURATION_SECS / PERIOD {
+            beacon.append(randomness(i));
+        }
+        beacon
+    }
+
+    #[test]
+    fn test_beacon_entropy_per_epoch() {
+        let mut beacon = beacon(4);
+        let info = *beacon.info();
+
+        let mut e2 = BeaconEntropy::fetch(&mut beacon, &info, Epoch::new(2)).unwrap();
+        assert_eq!(
+            e2.round().round,
+            info.round_for_epoch(Epoch::new(2)).unwrap()
+        );
+        assert!(!e2.is_cryptographically_secure());
+
+        let mut again = BeaconEntropy::fetch(&mut beacon, &info, Epoch::new(2)).unwrap();
+        assert_eq!(e2.generate(80).unwrap(), again.generate(80).unwrap());
+        let mut e3 = BeaconEntropy::fetch(&mut beacon, &info, Epoch::new(3)).unwrap();
+        assert_ne!(e2.generate(32).unwrap(), e3.generate(32).unwrap());
+
+        // Checkpointed walk yields the same round
+        let r1 = beacon.round(1).unwrap();
+        let from =
+            BeaconEntropy::fetch_from(&mut beacon, &info, (1, r1.digest()), Epoch::new(2)).unwrap();
+        assert_eq!(from.round(), e2.round());
+
+        // Rounds not yet published, and other chains, are rejected
+        assert!(BeaconEntropy::fetch(&mut beacon, &info, Epoch::new(10)).is_err());
+        let other = BeaconInfo::new(b"other-beacon", 0, PERIOD).unwrap();
+        assert!(BeaconEntropy::fetch(&mut beacon, &other, Epoch::new(2)).is_err());
+    }
+
+    #[test]
+    fn test_beacon_bound_manager() {
+        use crate::mtd::MTDManager;
+
+        let mut beacon = beacon(4);
+        let info = *beacon.info();
+        let e2 = BeaconEntropy::fetch(&mut beacon, &info, Epoch::new(2)).unwrap();
+        let e3 = BeaconEntropy::fetch(&mut beacon, &info, Epoch::new(3)).unwrap();
+
+        let manager = MTDManager::from_beacon(b"beacon-seed", &e2).unwrap();
+        assert_eq!(manager.current_epoch(), Epoch::new(2));
+        let again = BeaconEntropy::fetch(&mut beacon, &info, Epoch::new(2)).unwrap();
+        assert_eq!(
+            manager.current_params(),
+            MTDManager::from_beacon(b"beacon-seed", &again)
+                .unwrap()
+                .current_params()
+        );
+
+        // The round, not just the seed and epoch, shapes the params
+        let plain = MTDManager::with_epoch(b"beacon-seed", Epoch::new(2)).unwrap();
+        assert_ne!(manager.current_params(), plain.current_params());
+        let mut forged = e3.clone();
+        forged.epoch = Epoch::new(2);
+        assert_ne!(
+            manager.current_params(),
+            MTDManager::from_beacon(b"beacon-seed", &forged)
+                .unwrap()
+                .current_params()
+        );
+        assert!(MTDManager::from_beacon(b"", &e2).is_err());
+    }
+
+    #[cfg(feature = "full-p3")]
+    #[test]
+    fn test_beacon_bound_proof_end_to_end() {
+        use crate::stark::integrated::{IntegratedProver, IntegratedVerifier};
+
+        let seed = b"beacon-seed";
+        let mut beacon = beacon(4);
+        let info = *beacon.info();
+
+        let prover_round = BeaconEntropy::fetch(&mut beacon, &info, Epoch::new(3)).unwrap();
+        let prover = IntegratedProver::from_beacon(seed, &prover_round).unwrap();
+        let proof = prover.prove_fibonacci(8, [2u8; 32]).unwrap();
+
+        // The verifier walks the public chain itself
+        let verifier_round = BeaconEntropy::fetch(&mut beacon, &info, Epoch::new(3)).unwrap();
+        let verifier = IntegratedVerifier::from_beacon(seed, &verifier_round).unwrap();
+        assert!(verifier.verify(&proof).unwrap());
+
+        let plain = IntegratedVerifier::new(seed, Epoch::new(3)).unwrap();
+        assert!(!matches!(plain.verify(&proof), Ok(true)));
+    }
+
+    #[test]
+    fn test_verify_chain_detects_tampering() {
+        let mut beacon = beacon(1);
+        let info = *beacon.info();
+        let rounds: Vec<_> = (1..=5).map(|n| beacon.round(n).unwrap()).collect();
+        verify_chain((0, info.genesis()), &rounds).unwrap();
+
+        let mut swapped = rounds.clone();
+        swapped[2].randomness = [0xAA; 32];
+        assert!(verify_chain((0, info.genesis()), &swapped).is_err());
+
+        let mut skipped = rounds.clone();
+        skipped.remove(1);
+        assert!(verify_chain((0, info.genesis()), &skipped).is_err());
+        assert!(verify_chain((0, [0u8; 32]), &rounds).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_beacon_roundtrip() {
+        let path =
+            std::env::temp_dir().join(alloc::format!("zkmtd-beacon-{}.bin", std::process::id()));
+        let mut memory = beacon(2);
+        let mut file = FileBeacon::create(&path, info()).unwrap();
+        for n in 1..=memory.latest_round().unwrap() {
+            file.append(memory.round(n).unwrap().randomness).unwrap();
+        }
+
+        let mut reopened = FileBeacon::open(&path).unwrap();
+        assert_eq!(reopened.info(), &info());
+        assert_eq!(
+            reopened.latest_round().unwrap(),
+            memory.latest_round().unwrap()
+        );
+        let from_file = BeaconEntropy::fetch(&mut reopened, &info(), Epoch::new(1)).unwrap();
+        let from_memory = BeaconEntropy::fetch(&mut memory, &info(), Epoch::new(1)).unwrap();
+        assert_eq!(from_file.round(), from_memory.round());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,487 @@
+//! ParamsCache - LRU cache of per-epoch `WarpingParams` with hit/miss statistics
+//!
+//! Entries are keyed by epoch and evicted least-recently-used first, so a
+//! verifier cycling through a working set of epochs keeps them all warm as
+//! long as the set fits the capacity. Lookups and evictions are O(log n).
+//! `SharedParamsCache` (std) is a thread-safe handle bound to one seed.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::mtd::revocation::EpochRange;
+use crate::mtd::{Epoch, WarpingParams};
+use crate::utils::constants::MAX_PARAM_CACHE_CAPACITY;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::mtd::TenantContext;
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex, MutexGuard};
+#[cfg(feature = "std")]
+use zeroize::Zeroizing;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub size: usize,
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// Entries derived ahead of use by `prefetch` / `precompute`.
+    pub prefetched: u64,
+}
+
+impl CacheStats {
+    /// Fraction of lookups served from the cache (0.0 before any lookup).
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / lookups as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamsCache {
+    capacity: usize,
+    /// epoch -> (params, last-use stamp)
+    entries: BTreeMap<Epoch, (WarpingParams, u64)>,
+    /// last-use stamp -> epoch, oldest first
+    recency: BTreeMap<u64, Epoch>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    prefetched: u64,
+}
+
+impl ParamsCache {
+    pub fn new(capacity: usize) -> Result<Self> {
+        check_capacity(capacity)?;
+        Ok(Self {
+            capacity,
+            entries: BTreeMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+            prefetched: 0,
+        })
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, epoch: Epoch) -> bool {
+        self.entries.contains_key(&epoch)
+    }
+
+    /// Look up `epoch`, counting a hit or miss and marking it most recent.
+    pub fn get(&mut self, epoch: Epoch) -> Option<WarpingParams> {
+        let stamp = self.next_stamp();
+        match self.entries.get_mut(&epoch) {
+            Some((params, last)) => {
+                self.recency.remove(last);
+                self.recency.insert(stamp, epoch);
+                *last = stamp;
+                self.hits += 1;
+                Some(params.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Look up `epoch` without touching recency or statistics.
+    pub fn peek(&self, epoch: Epoch) -> Option<&WarpingParams> {
+        self.entries.get(&epoch).map(|(params, _)| params)
+    }
+
+    /// Insert (or refresh) `params` as most recent, evicting the least
+    /// recently used entry when full.
+    pub fn insert(&mut self, params: WarpingParams) {
+        let epoch = params.epoch;
+        self.remove(epoch);
+        if self.entries.len() >= self.capacity {
+            self.evict_lru();
+        }
+        let stamp = self.next_stamp();
+        self.recency.insert(stamp, epoch);
+        self.entries.insert(epoch, (params, stamp));
+    }
+
+    pub fn remove(&mut self, epoch: Epoch) -> Option<WarpingParams> {
+        let (params, stamp) = self.entries.remove(&epoch)?;
+        self.recency.remove(&stamp);
+        Some(params)
+    }
+
+    /// Change the capacity, evicting least recently used entries to fit.
+    pub fn set_capacity(&mut self, capacity: usize) -> Result<()> {
+        check_capacity(capacity)?;
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.evict_lru();
+        }
+        Ok(())
+    }
+
+    /// Derive and insert every epoch in `epochs` not already cached,
+    /// returning how many were derived. Cached epochs in `epochs` are marked
+    /// most recent first, so the inserts never evict them. Rejects more
+    /// epochs than fit.
+    pub fn fill<I, F>(&mut self, epochs: I, mut derive: F) -> Result<usize>
+    where
+        I: IntoIterator<Item = Epoch>,
+        F: FnMut(Epoch) -> Result<WarpingParams>,
+    {
+        let epochs: Vec<Epoch> = epochs.into_iter().take(self.capacity + 1).collect();
+        if epochs.len() > self.capacity {
+            return Err(exceeds_capacity(self.capacity));
+        }
+        let missing: Vec<Epoch> = epochs.into_iter().filter(|e| !self.touch(*e)).collect();
+        for &epoch in &missing {
+            self.insert_prefetched(derive(epoch)?);
+        }
+        Ok(missing.len())
+    }
+
+    /// Mark `epoch` most recent if cached, without counting a hit.
+    fn touch(&mut self, epoch: Epoch) -> bool {
+        let stamp = self.next_stamp();
+        match self.entries.get_mut(&epoch) {
+            Some((_, last)) => {
+                self.recency.remove(last);
+                self.recency.insert(stamp, epoch);
+                *last = stamp;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn insert_prefetched(&mut self, params: WarpingParams) {
+        self.insert(params);
+        self.prefetched += 1;
+    }
+
+    /// Cached epochs, least recently used first.
+    pub fn epochs(&self) -> Vec<Epoch> {
+        self.recency.values().copied().collect()
+    }
+
+    /// Drop all entries. Statistics are kept; see [`reset_stats`](Self::reset_stats).
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.hits = 0;
+        self.misses = 0;
+        self.evictions = 0;
+        self.prefetched = 0;
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            size: self.entries.len(),
+            capacity: self.capacity,
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+            prefetched: self.prefetched,
+        }
+    }
+
+    fn next_stamp(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn evict_lru(&mut self) {
+        if let Some((_, epoch)) = self.recency.pop_first() {
+            self.entries.remove(&epoch);
+            self.evictions += 1;
+        }
+    }
+}
+
+/// `count` epochs following `after`, stopping at the last representable epoch.
+pub(crate) fn following_epochs(after: Epoch, count: u64) -> impl Iterator<Item = Epoch> {
+    (1..=count).map_while(move |i| {
+        after
+            .value()
+            .checked_add(i)
+            .and_then(|v| Epoch::try_new(v).ok())
+    })
+}
+
+/// Every epoch in `range` (inclusive).
+pub(crate) fn range_epochs(range: EpochRange) -> impl Iterator<Item = Epoch> {
+    (range.start.value()..=range.end.value()).map(Epoch::new)
+}
+
+fn check_capacity(capacity: usize) -> Result<()> {
+    if capacity == 0 || capacity > MAX_PARAM_CACHE_CAPACITY {
+        return Err(ZKMTDError::ConfigurationError {
+            reason: alloc::format!(
+                "Params cache capacity must be 1..={}, got {}",
+                MAX_PARAM_CACHE_CAPACITY,
+                capacity
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn exceeds_capacity(capacity: usize) -> ZKMTDError {
+    ZKMTDError::ResourceLimitExceeded {
+        reason: alloc::format!("Requested epochs exceed cache capacity {}", capacity),
+    }
+}
+
+/// Thread-safe params cache bound to one seed (and optional tenant).
+/// Clones share the same cache; derivation runs outside the lock.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct SharedParamsCache {
+    inner: Arc<SharedInner>,
+}
+
+#[cfg(feature = "std")]
+struct SharedInner {
+    seed: Zeroizing<Vec<u8>>,
+    tenant: Option<TenantContext>,
+    cache: Mutex<ParamsCache>,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Debug for SharedParamsCache {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SharedParamsCache")
+            .field("seed", &"<redacted>")
+            .field("tenant", &self.inner.tenant)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl SharedParamsCache {
+    pub fn new(seed: &[u8], capacity: usize) -> Result<Self> {
+        Self::build(seed, None, capacity)
+    }
+
+    pub fn for_tenant(seed: &[u8], tenant: TenantContext, capacity: usize) -> Result<Self> {
+        Self::build(seed, Some(tenant), capacity)
+    }
+
+    fn build(seed: &[u8], tenant: Option<TenantContext>, capacity: usize) -> Result<Self> {
+        if seed.is_empty() {
+            return Err(ZKMTDError::MTDError {
+                reason: "Seed is empty".into(),
+            });
+        }
+        Ok(Self {
+            inner: Arc::new(SharedInner {
+                seed: Zeroizing::new(seed.to_vec()),
+                tenant,
+                cache: Mutex::new(ParamsCache::new(capacity)?),
+            }),
+        })
+    }
+
+    fn derive(&self, epoch: Epoch) -> Result<WarpingParams> {
+        match &self.inner.tenant {
+            Some(tenant) => WarpingParams::generate_for_tenant(&self.inner.seed, epoch, tenant),
+            None => WarpingParams::generate(&self.inner.seed, epoch),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ParamsCache> {
+        self.inner.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Params for `epoch`, derived and cached on a miss.
+    pub fn get(&self, epoch: Epoch) -> Result<WarpingParams> {
+        if let Some(params) = self.lock().get(epoch) {
+            return Ok(params);
+        }
+        let params = self.derive(epoch)?;
+        self.lock().insert(params.clone());
+        Ok(params)
+    }
+
+    /// Derive the `count` epochs following `after` ahead of use.
+    pub fn prefetch(&self, after: Epoch, count: u64) -> Result<usize> {
+        self.fill(following_epochs(after, count))
+    }
+
+    /// Derive every epoch in `range` ahead of use.
+    pub fn precompute(&self, range: EpochRange) -> Result<usize> {
+        self.fill(range_epochs(range))
+    }
+
+    fn fill<I: IntoIterator<Item = Epoch>>(&self, epochs: I) -> Result<usize> {
+        let capacity = self.lock().capacity();
+        let epochs: Vec<Epoch> = epochs.into_iter().take(capacity + 1).collect();
+        if epochs.len() > capacity {
+            return Err(exceeds_capacity(capacity));
+        }
+        let missing: Vec<Epoch> = {
+            let mut cache = self.lock();
+            epochs.into_iter().filter(|e| !cache.touch(*e)).collect()
+        };
+        let derived = missing
+            .into_iter()
+            .map(|epoch| self.derive(epoch))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut cache = self.lock();
+        let count = derived.len();
+        for params in derived {
+            cache.insert_prefetched(params);
+        }
+        Ok(count)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats()
+    }
+
+    pub fn set_capacity(&self, capacity: usize) -> Result<()> {
+        self.lock().set_capacity(capacity)
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::MAX_EPOCH;
+
+    fn params(epoch: u64) -> WarpingParams {
+        WarpingParams::generate(b"cache-seed", Epoch::new(epoch)).unwrap()
+    }
+
+    #[test]
+    fn test_lru_eviction_and_stats() {
+        let mut cache = ParamsCache::new(3).unwrap();
+        for e in 1..=3 {
+            cache.insert(params(e));
+        }
+        // Touch 1 so 2 becomes least recently used
+        assert_eq!(cache.get(Epoch::new(1)), Some(params(1)));
+        cache.insert(params(4));
+        assert!(!cache.contains(Epoch::new(2)));
+        assert_eq!(cache.epochs(), [3, 1, 4].map(Epoch::new));
+        assert_eq!(cache.get(Epoch::new(2)), None);
+
+        let stats = cache.stats();
+        assert_eq!((stats.size, stats.capacity), (3, 3));
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 1, 1));
+        assert_eq!(stats.hit_rate(), 0.5);
+
+        cache.set_capacity(1).unwrap();
+        assert_eq!(cache.epochs(), [Epoch::new(4)]);
+        assert!(ParamsCache::new(0).is_err());
+        assert!(cache.set_capacity(MAX_PARAM_CACHE_CAPACITY + 1).is_err());
+    }
+
+    #[test]
+    fn test_fill_respects_capacity() {
+        let mut cache = ParamsCache::new(4).unwrap();
+        cache.insert(params(11));
+        let derived = cache
+            .fill(following_epochs(Epoch::new(10), 4), |e| {
+                Ok(params(e.value()))
+            })
+            .unwrap();
+        assert_eq!(derived, 3);
+        assert_eq!(cache.stats().prefetched, 3);
+        assert!(cache
+            .fill(following_epochs(Epoch::new(10), 5), |e| Ok(params(
+                e.value()
+            )))
+            .is_err());
+        assert_eq!(following_epochs(Epoch::new(MAX_EPOCH - 1), 5).count(), 1);
+    }
+
+    #[test]
+    fn test_fill_keeps_cached_epochs_in_range() {
+        let mut cache = ParamsCache::new(4).unwrap();
+        for e in [11, 20, 21, 22] {
+            cache.insert(params(e));
+        }
+        let derived = cache
+            .fill(following_epochs(Epoch::new(10), 4), |e| {
+                Ok(params(e.value()))
+            })
+            .unwrap();
+        assert_eq!(derived, 3);
+        assert_eq!(cache.epochs(), [11, 12, 13, 14].map(Epoch::new));
+        assert_eq!(cache.stats().hits, 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_shared_fill_keeps_cached_epochs_in_range() {
+        let shared = SharedParamsCache::new(b"cache-seed", 4).unwrap();
+        for e in [11, 20, 21, 22] {
+            shared.get(Epoch::new(e)).unwrap();
+        }
+        assert_eq!(shared.prefetch(Epoch::new(10), 4).unwrap(), 3);
+        assert_eq!(shared.lock().epochs(), [11, 12, 13, 14].map(Epoch::new));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_shared_cache_across_threads() {
+        let shared = SharedParamsCache::new(b"cache-seed", 32).unwrap();
+        assert_eq!(
+            shared
+                .precompute(EpochRange {
+                    start: Epoch::new(100),
+                    end: Epoch::new(115),
+                })
+                .unwrap(),
+            16
+        );
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    for e in 100..116 {
+                        assert_eq!(shared.get(Epoch::new(e)).unwrap(), params(e));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let stats = shared.stats();
+        assert_eq!((stats.hits, stats.misses, stats.prefetched), (64, 0, 16));
+        assert_eq!(shared.prefetch(Epoch::new(115), 2).unwrap(), 2);
+        assert!(shared.prefetch(Epoch::new(0), 33).is_err());
+    }
+}
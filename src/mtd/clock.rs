@@ -0,0 +1,94 @@
+//! Injectable time sources for epoch tracking
+
+use crate::core::errors::Result;
+use crate::mtd::Epoch;
+
+#[cfg(feature = "alloc")]
+use alloc::sync::Arc;
+#[cfg(feature = "alloc")]
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of wall-clock time in Unix seconds.
+pub trait Clock {
+    fn now_secs(&self) -> Result<u64>;
+
+    fn current_epoch(&self) -> Result<Epoch> {
+        Ok(Epoch::from_timestamp(self.now_secs()?))
+    }
+}
+
+/// Operating-system clock.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now_secs(&self) -> Result<u64> {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| crate::core::errors::ZKMTDError::InternalError {
+                reason: alloc::format!("System time error: {}", e),
+            })?
+            .as_secs())
+    }
+}
+
+/// Manually driven clock. Clones share the same time, so a test can hold one
+/// handle while a scheduler or verifier reads another.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Arc<AtomicU64>,
+}
+
+#[cfg(feature = "alloc")]
+impl ManualClock {
+    pub fn new(now_secs: u64) -> Self {
+        Self {
+            now: Arc::new(AtomicU64::new(now_secs)),
+        }
+    }
+
+    pub fn set(&self, now_secs: u64) {
+        self.now.store(now_secs, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, secs: u64) {
+        self.now.fetch_add(secs, Ordering::SeqCst);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Clock for ManualClock {
+    fn now_secs(&self) -> Result<u64> {
+        Ok(self.now.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::EPOCH_DURATION_SECS;
+
+    #[test]
+    fn test_manual_clock_shared() {
+        let clock = ManualClock::new(100 * EPOCH_DURATION_SECS);
+        let handle = clock.clone();
+        assert_eq!(handle.current_epoch().unwrap(), Epoch::new(100));
+
+        clock.advance(EPOCH_DURATION_SECS);
+        assert_eq!(handle.current_epoch().unwrap(), Epoch::new(101));
+        clock.set(5);
+        assert_eq!(handle.now_secs().unwrap(), 5);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_system_clock() {
+        let epoch = SystemClock.current_epoch().unwrap();
+        assert!(epoch.distance(&Epoch::current().unwrap()) <= 1);
+    }
+}
@@ -0,0 +1,424 @@
+//! DrbgEntropy - deterministic random bit generator over Poseidon2 (no_std)
+//!
+//! Follows the HMAC-DRBG construction of NIST SP 800-90A with Poseidon2 as the
+//! keyed PRF: a `(key, value)` state, an update step that runs after every
+//! request (backtracking resistance), a reseed counter that fails closed once
+//! the reseed interval is exhausted, and optional prediction-resistant
+//! requests that reseed from a caller-supplied source first.
+//!
+//! Variable-length inputs are compressed to a digest before entering the
+//! state, so neither `std` nor `alloc` is needed.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::traits::EntropySource;
+use crate::core::types::HashDigest;
+use crate::utils::constants::{
+    DOMAIN_DRBG_INPUT, DOMAIN_DRBG_KEY, DOMAIN_DRBG_VALUE, DRBG_DEFAULT_RESEED_INTERVAL,
+    DRBG_MAX_REQUEST_BYTES, DRBG_MAX_RESEED_INTERVAL, DRBG_MIN_ENTROPY_BYTES,
+};
+use crate::utils::hash::poseidon_hash;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use zeroize::Zeroize;
+
+/// Nonce bytes drawn alongside the entropy input by [`DrbgEntropy::from_source`].
+const NONCE_BYTES: usize = 16;
+
+pub struct DrbgEntropy {
+    key: HashDigest,
+    value: HashDigest,
+    reseed_counter: u64,
+    reseed_interval: u64,
+    entropy_bits: usize,
+}
+
+impl core::fmt::Debug for DrbgEntropy {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DrbgEntropy")
+            .field("state", &"<redacted>")
+            .field("reseed_counter", &self.reseed_counter)
+            .field("reseed_interval", &self.reseed_interval)
+            .finish()
+    }
+}
+
+impl Drop for DrbgEntropy {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.value.zeroize();
+    }
+}
+
+impl DrbgEntropy {
+    /// Instantiate from caller-provided entropy (at least
+    /// `DRBG_MIN_ENTROPY_BYTES`), a nonce and an optional personalization string.
+    pub fn new(entropy_input: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Self> {
+        check_entropy_input(entropy_input)?;
+
+        let mut drbg = Self {
+            key: [0u8; 32],
+            value: [1u8; 32],
+            reseed_counter: 1,
+            reseed_interval: DRBG_DEFAULT_RESEED_INTERVAL,
+            entropy_bits: (entropy_input.len() * 8).min(256),
+        };
+        let mut seed_material = digest_inputs(&[entropy_input, nonce, personalization]);
+        drbg.update(Some(&seed_material));
+        seed_material.zeroize();
+        Ok(drbg)
+    }
+
+    /// Instantiate from a cryptographically secure source.
+    pub fn from_source<E: EntropySource>(source: &mut E, personalization: &[u8]) -> Result<Self> {
+        let mut input = [0u8; DRBG_MIN_ENTROPY_BYTES + NONCE_BYTES];
+        let result = draw_secure(source, &mut input).and_then(|_| {
+            let (entropy_input, nonce) = input.split_at(DRBG_MIN_ENTROPY_BYTES);
+            Self::new(entropy_input, nonce, personalization)
+        });
+        input.zeroize();
+        result
+    }
+
+    pub fn with_reseed_interval(mut self, interval: u64) -> Result<Self> {
+        if interval == 0 || interval > DRBG_MAX_RESEED_INTERVAL {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: "DRBG reseed interval out of range".into(),
+            });
+        }
+        self.reseed_interval = interval;
+        Ok(self)
+    }
+
+    pub fn reseed_interval(&self) -> u64 {
+        self.reseed_interval
+    }
+
+    /// Requests served since the last (re)seed.
+    pub fn requests_since_reseed(&self) -> u64 {
+        self.reseed_counter - 1
+    }
+
+    pub fn needs_reseed(&self) -> bool {
+        self.reseed_counter > self.reseed_interval
+    }
+
+    pub fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) -> Result<()> {
+        check_entropy_input(entropy_input)?;
+        let mut seed_material = digest_inputs(&[&self.value, entropy_input, additional_input]);
+        self.update(Some(&seed_material));
+        seed_material.zeroize();
+        self.reseed_counter = 1;
+        self.entropy_bits = self.entropy_bits.max((entropy_input.len() * 8).min(256));
+        Ok(())
+    }
+
+    /// Reseed from a cryptographically secure source.
+    pub fn reseed_from<E: EntropySource>(
+        &mut self,
+        source: &mut E,
+        additional_input: &[u8],
+    ) -> Result<()> {
+        let mut input = [0u8; DRBG_MIN_ENTROPY_BYTES];
+        let result =
+            draw_secure(source, &mut input).and_then(|_| self.reseed(&input, additional_input));
+        input.zeroize();
+        result
+    }
+
+    /// Fill `output`, mixing in optional additional input. Fails with
+    /// `EntropyError` once the reseed interval is exhausted.
+    pub fn generate_with(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<()> {
+        if output.len() > DRBG_MAX_REQUEST_BYTES {
+            return Err(ZKMTDError::ResourceLimitExceeded {
+                reason: "DRBG request exceeds DRBG_MAX_REQUEST_BYTES".into(),
+            });
+        }
+        if self.needs_reseed() {
+            return Err(ZKMTDError::EntropyError {
+                reason: "DRBG reseed required".into(),
+            });
+        }
+
+        let mut additional =
+            (!additional_input.is_empty()).then(|| digest_inputs(&[additional_input]));
+        if let Some(digest) = &additional {
+            self.update(Some(digest));
+        }
+
+        for chunk in output.chunks_mut(32) {
+            self.value = self.prf_value();
+            chunk.copy_from_slice(&self.value[..chunk.len()]);
+        }
+
+        // Backtracking resistance: the state that produced `output` is gone.
+        self.update(additional.as_ref());
+        if let Some(digest) = additional.as_mut() {
+            digest.zeroize();
+        }
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    /// Prediction-resistant request: reseed from `source` before generating.
+    pub fn fill_bytes_with_prediction_resistance<E: EntropySource>(
+        &mut self,
+        output: &mut [u8],
+        source: &mut E,
+        additional_input: &[u8],
+    ) -> Result<()> {
+        self.reseed_from(source, additional_input)?;
+        self.generate_with(output, &[])
+    }
+
+    /// SP 800-90A update: `K = F(K, V ‖ 0x00 ‖ data)`, `V = F(K, V)`, and a
+    /// second round with `0x01` when data is provided.
+    fn update(&mut self, provided: Option<&HashDigest>) {
+        self.key = self.prf_key(0x00, provided);
+        self.value = self.prf_value();
+        if provided.is_some() {
+            self.key = self.prf_key(0x01, provided);
+            self.value = self.prf_value();
+        }
+    }
+
+    fn prf_key(&self, round: u8, provided: Option<&HashDigest>) -> HashDigest {
+        let mut buf = [0u8; 97];
+        buf[..32].copy_from_slice(&self.key);
+        buf[32..64].copy_from_slice(&self.value);
+        buf[64] = round;
+        let len = match provided {
+            Some(data) => {
+                buf[65..].copy_from_slice(data);
+                97
+            }
+            None => 65,
+        };
+        let key = poseidon_hash(&buf[..len], DOMAIN_DRBG_KEY);
+        buf.zeroize();
+        key
+    }
+
+    fn prf_value(&self) -> HashDigest {
+        let mut buf = [0u8; 64];
+        buf[..32].copy_from_slice(&self.key);
+        buf[32..].copy_from_slice(&self.value);
+        let value = poseidon_hash(&buf, DOMAIN_DRBG_VALUE);
+        buf.zeroize();
+        value
+    }
+}
+
+impl EntropySource for DrbgEntropy {
+    #[cfg(feature = "alloc")]
+    fn generate(&mut self, num_bytes: usize) -> Result<Vec<u8>> {
+        let mut buffer = vec![0u8; num_bytes];
+        self.fill_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn fill_bytes(&mut self, output: &mut [u8]) -> Result<()> {
+        self.generate_with(output, &[])
+    }
+
+    fn entropy_bits(&self) -> usize {
+        self.entropy_bits
+    }
+
+    fn is_cryptographically_secure(&self) -> bool {
+        !self.needs_reseed()
+    }
+}
+
+fn check_entropy_input(entropy_input: &[u8]) -> Result<()> {
+    if entropy_input.len() < DRBG_MIN_ENTROPY_BYTES {
+        return Err(ZKMTDError::EntropyError {
+            reason: "DRBG entropy input shorter than DRBG_MIN_ENTROPY_BYTES".into(),
+        });
+    }
+    Ok(())
+}
+
+fn draw_secure<E: EntropySource>(source: &mut E, output: &mut [u8]) -> Result<()> {
+    if !source.is_cryptographically_secure() {
+        return Err(ZKMTDError::EntropyError {
+            reason: "DRBG seed source is not cryptographically secure".into(),
+        });
+    }
+    source.fill_bytes(output)
+}
+
+/// Chain-hash a fixed list of inputs into one digest without concatenating.
+fn digest_inputs(parts: &[&[u8]]) -> HashDigest {
+    let mut buf = [0u8; 64];
+    for part in parts {
+        let part_digest = poseidon_hash(part, DOMAIN_DRBG_INPUT);
+        buf[32..].copy_from_slice(&part_digest);
+        let acc = poseidon_hash(&buf, DOMAIN_DRBG_INPUT);
+        buf[..32].copy_from_slice(&acc);
+    }
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(&buf[..32]);
+    buf.zeroize();
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtd::entropy::{DeterministicEntropy, TrustedEntropy};
+    use alloc::string::String;
+
+    const ENTROPY: [u8; 32] = [0x11; 32];
+    const NONCE: &[u8] = b"drbg-nonce";
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| alloc::format!("{:02x}", b)).collect()
+    }
+
+    // Known-answer vectors pin the construction: any change to the state
+    // update, input encoding or domain tags must show up here.
+    #[test]
+    fn test_known_answer_instantiate_generate() {
+        let mut drbg = DrbgEntropy::new(&ENTROPY, NONCE, b"").unwrap();
+        let mut first = [0u8; 48];
+        drbg.fill_bytes(&mut first).unwrap();
+        let mut second = [0u8; 32];
+        drbg.fill_bytes(&mut second).unwrap();
+
+        assert_eq!(
+            hex(&first),
+            "19619ae3784f434295bff3cad6792fc0ce4d71df430b2e7cac6c2b833a8fa9245782ac1e531f541e3ddfa2808d3ed00f"
+        );
+        assert_eq!(
+            hex(&second),
+            "d37099ebaaf7c72978449bf44baf5d05d40054f6425483ebcaabac63b4d62e88"
+        );
+    }
+
+    #[test]
+    fn test_known_answer_personalization_and_additional_input() {
+        let mut drbg = DrbgEntropy::new(&ENTROPY, NONCE, b"zkmtd-kat").unwrap();
+        let mut output = [0u8; 32];
+        drbg.generate_with(&mut output, b"additional").unwrap();
+        assert_eq!(
+            hex(&output),
+            "018f3c2ccb3821d55cc16bc039623815f9ad07b42dad20e20bb80b483e79b9dc"
+        );
+    }
+
+    #[test]
+    fn test_known_answer_reseed() {
+        let mut drbg = DrbgEntropy::new(&ENTROPY, NONCE, b"").unwrap();
+        let mut output = [0u8; 32];
+        drbg.fill_bytes(&mut output).unwrap();
+        drbg.reseed(&[0x22; 32], b"").unwrap();
+        drbg.fill_bytes(&mut output).unwrap();
+        assert_eq!(
+            hex(&output),
+            "ba839d9fa32dd18dd1af4db9e6280619992aedc8a1e268943fb340025a6f1324"
+        );
+    }
+
+    #[test]
+    fn test_inputs_change_output() {
+        let output = |entropy: &[u8], nonce: &[u8], pers: &[u8]| {
+            let mut drbg = DrbgEntropy::new(entropy, nonce, pers).unwrap();
+            let mut out = [0u8; 32];
+            drbg.fill_bytes(&mut out).unwrap();
+            out
+        };
+        let base = output(&ENTROPY, NONCE, b"");
+        assert_eq!(base, output(&ENTROPY, NONCE, b""));
+        assert_ne!(base, output(&[0x12; 32], NONCE, b""));
+        assert_ne!(base, output(&ENTROPY, b"other-nonce", b""));
+        assert_ne!(base, output(&ENTROPY, NONCE, b"pers"));
+    }
+
+    #[test]
+    fn test_short_entropy_rejected() {
+        assert!(DrbgEntropy::new(&[0u8; 31], NONCE, b"").is_err());
+        let mut drbg = DrbgEntropy::new(&ENTROPY, NONCE, b"").unwrap();
+        assert!(drbg.reseed(&[0u8; 16], b"").is_err());
+    }
+
+    #[test]
+    fn test_reseed_interval_fails_closed() {
+        let mut drbg = DrbgEntropy::new(&ENTROPY, NONCE, b"")
+            .unwrap()
+            .with_reseed_interval(2)
+            .unwrap();
+        let mut output = [0u8; 16];
+        drbg.fill_bytes(&mut output).unwrap();
+        drbg.fill_bytes(&mut output).unwrap();
+        assert!(drbg.needs_reseed());
+        assert!(!drbg.is_cryptographically_secure());
+        assert!(matches!(
+            drbg.fill_bytes(&mut output),
+            Err(ZKMTDError::EntropyError { .. })
+        ));
+
+        drbg.reseed(&[0x33; 32], b"").unwrap();
+        assert_eq!(drbg.requests_since_reseed(), 0);
+        assert!(drbg.fill_bytes(&mut output).is_ok());
+
+        assert!(DrbgEntropy::new(&ENTROPY, NONCE, b"")
+            .unwrap()
+            .with_reseed_interval(0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_prediction_resistance_reseeds() {
+        let mut plain = DrbgEntropy::new(&ENTROPY, NONCE, b"").unwrap();
+        let mut resistant = DrbgEntropy::new(&ENTROPY, NONCE, b"").unwrap();
+        let mut source = TrustedEntropy(DeterministicEntropy::new(5));
+
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        plain.fill_bytes(&mut a).unwrap();
+        resistant
+            .fill_bytes_with_prediction_resistance(&mut b, &mut source, b"")
+            .unwrap();
+        assert_ne!(a, b);
+
+        // Insecure sources are refused
+        let mut insecure = DeterministicEntropy::new(5);
+        assert!(resistant
+            .fill_bytes_with_prediction_resistance(&mut b, &mut insecure, b"")
+            .is_err());
+    }
+
+    #[test]
+    fn test_backtracking_resistance() {
+        // The post-request state must not reproduce the previous output.
+        let mut drbg = DrbgEntropy::new(&ENTROPY, NONCE, b"").unwrap();
+        let mut output = [0u8; 32];
+        drbg.fill_bytes(&mut output).unwrap();
+        assert_ne!(drbg.value, output);
+        assert_ne!(drbg.prf_value(), output);
+    }
+
+    #[test]
+    fn test_from_source_and_limits() {
+        let mut source = TrustedEntropy(DeterministicEntropy::new(9));
+        let mut drbg = DrbgEntropy::from_source(&mut source, b"app").unwrap();
+        assert!(drbg.is_cryptographically_secure());
+        assert_eq!(drbg.entropy_bits(), 256);
+
+        let mut too_big = vec![0u8; DRBG_MAX_REQUEST_BYTES + 1];
+        assert!(drbg.fill_bytes(&mut too_big).is_err());
+        assert_eq!(drbg.generate(100).unwrap().len(), 100);
+
+        assert!(DrbgEntropy::from_source(&mut DeterministicEntropy::new(9), b"").is_err());
+    }
+
+    #[test]
+    fn test_debug_redacts_state() {
+        let drbg = DrbgEntropy::new(&ENTROPY, NONCE, b"").unwrap();
+        let debug = alloc::format!("{:?}", drbg);
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains("key"));
+    }
+}
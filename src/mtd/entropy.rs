@@ -252,6 +252,39 @@ impl EntropySource for DeterministicEntropy {
     }
 }
 
+/// Test-only wrapper that vouches for a [`DeterministicEntropy`], so code
+/// gated on secure entropy can be exercised reproducibly.
+#[cfg(test)]
+#[derive(Debug)]
+pub struct TrustedEntropy(pub DeterministicEntropy);
+
+#[cfg(test)]
+impl TrustedEntropy {
+    pub fn new(seed: u64) -> Self {
+        Self(DeterministicEntropy::new(seed))
+    }
+}
+
+#[cfg(test)]
+impl EntropySource for TrustedEntropy {
+    #[cfg(feature = "alloc")]
+    fn generate(&mut self, num_bytes: usize) -> Result<Vec<u8>> {
+        self.0.generate(num_bytes)
+    }
+
+    fn fill_bytes(&mut self, output: &mut [u8]) -> Result<()> {
+        self.0.fill_bytes(output)
+    }
+
+    fn entropy_bits(&self) -> usize {
+        256
+    }
+
+    fn is_cryptographically_secure(&self) -> bool {
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,531 @@
+//! Hierarchical MTD seed derivation along labelled paths (BIP32-style)
+//!
+//! Child seeds are derived from a master seed with Poseidon2 along paths such
+//! as `m/prod/eu/payments`. A trailing `'` marks a hardened component
+//! (`m/prod'/eu`).
+//!
+//! Hash-based derivation has no key homomorphism, so "non-hardened" keeps
+//! BIP32's *delegation* property rather than its public-key property: a
+//! non-hardened child is derived from the parent's delegation part (chain
+//! code and node id) alone. That part is not public: holding an
+//! [`ExtendedDelegationSeed`] for `m/prod` lets e.g. a verifier fleet derive
+//! every non-hardened seed below it, and no hardened one, so it is as secret
+//! as those seeds. Hardened children need the parent's secret key. In both cases
+//! a leaked child seed does not reveal its parent.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+use crate::mtd::{Epoch, MTDManager};
+use crate::utils::constants::{
+    DOMAIN_KEYTREE_CHILD_CHAIN, DOMAIN_KEYTREE_CHILD_KEY, DOMAIN_KEYTREE_FINGERPRINT,
+    DOMAIN_KEYTREE_MASTER_CHAIN, DOMAIN_KEYTREE_MASTER_KEY, DOMAIN_KEYTREE_PUBLIC,
+    MAX_KEYTREE_DEPTH, MAX_KEYTREE_LABEL_LEN,
+};
+use crate::utils::hash::poseidon_hash;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use zeroize::{Zeroize, Zeroizing};
+
+/// First four bytes of a node's id hash.
+pub type Fingerprint = [u8; 4];
+
+const HARDENED_PREFIX: u8 = 0x00;
+const NORMAL_PREFIX: u8 = 0x01;
+
+/// One path component.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChildLabel {
+    label: String,
+    hardened: bool,
+}
+
+impl ChildLabel {
+    pub fn normal(label: &str) -> Result<Self> {
+        Self::new(label, false)
+    }
+
+    pub fn hardened(label: &str) -> Result<Self> {
+        Self::new(label, true)
+    }
+
+    fn new(label: &str, hardened: bool) -> Result<Self> {
+        let valid_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.');
+        if label.is_empty() || label.len() > MAX_KEYTREE_LABEL_LEN || !label.chars().all(valid_char)
+        {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: alloc::format!("Invalid key path label: {:?}", label),
+            });
+        }
+        Ok(Self {
+            label: label.to_string(),
+            hardened,
+        })
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn is_hardened(&self) -> bool {
+        self.hardened
+    }
+}
+
+impl core::fmt::Display for ChildLabel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}", self.label, if self.hardened { "'" } else { "" })
+    }
+}
+
+/// Path from the master, e.g. `m/prod'/eu/payments`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DerivationPath {
+    components: Vec<ChildLabel>,
+}
+
+impl DerivationPath {
+    pub fn master() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(path: &str) -> Result<Self> {
+        let mut parts = path.split('/');
+        if parts.next() != Some("m") {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: alloc::format!("Key path must start with \"m\": {:?}", path),
+            });
+        }
+        let mut result = Self::master();
+        for part in parts {
+            let child = match part.strip_suffix('\'') {
+                Some(label) => ChildLabel::hardened(label)?,
+                None => ChildLabel::normal(part)?,
+            };
+            result = result.child(child)?;
+        }
+        Ok(result)
+    }
+
+    pub fn child(mut self, child: ChildLabel) -> Result<Self> {
+        if self.components.len() >= MAX_KEYTREE_DEPTH {
+            return Err(ZKMTDError::ResourceLimitExceeded {
+                reason: alloc::format!("Key path deeper than {}", MAX_KEYTREE_DEPTH),
+            });
+        }
+        self.components.push(child);
+        Ok(self)
+    }
+
+    pub fn components(&self) -> &[ChildLabel] {
+        &self.components
+    }
+
+    pub fn depth(&self) -> usize {
+        self.components.len()
+    }
+}
+
+impl core::fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "m")?;
+        for component in &self.components {
+            write!(f, "/{}", component)?;
+        }
+        Ok(())
+    }
+}
+
+/// Secret tree node: a 32-byte seed plus chain code.
+pub struct ExtendedSeed {
+    key: HashDigest,
+    chain_code: HashDigest,
+    depth: u8,
+    parent_fingerprint: Fingerprint,
+}
+
+impl core::fmt::Debug for ExtendedSeed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ExtendedSeed")
+            .field("key", &"<redacted>")
+            .field("depth", &self.depth)
+            .field("fingerprint", &self.fingerprint())
+            .field("parent_fingerprint", &self.parent_fingerprint)
+            .finish()
+    }
+}
+
+impl Drop for ExtendedSeed {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.chain_code.zeroize();
+    }
+}
+
+impl ExtendedSeed {
+    pub fn master(seed: &[u8]) -> Result<Self> {
+        if seed.is_empty() {
+            return Err(ZKMTDError::MTDError {
+                reason: "Seed is empty".into(),
+            });
+        }
+        Ok(Self {
+            key: poseidon_hash(seed, DOMAIN_KEYTREE_MASTER_KEY),
+            chain_code: poseidon_hash(seed, DOMAIN_KEYTREE_MASTER_CHAIN),
+            depth: 0,
+            parent_fingerprint: [0u8; 4],
+        })
+    }
+
+    /// Derive the node at `path` below the master seed.
+    pub fn from_path(master_seed: &[u8], path: &DerivationPath) -> Result<Self> {
+        Self::master(master_seed)?.derive_path(path)
+    }
+
+    pub fn derive_child(&self, child: &ChildLabel) -> Result<Self> {
+        if child.hardened {
+            derive(
+                HARDENED_PREFIX,
+                &self.key,
+                &self.chain_code,
+                self.depth,
+                self.fingerprint(),
+                child,
+            )
+        } else {
+            self.delegation().derive_child(child)
+        }
+    }
+
+    /// Derive along `path`, read relative to this node (`m` is this node).
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self> {
+        let mut node = Self {
+            key: self.key,
+            chain_code: self.chain_code,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+        };
+        for child in path.components() {
+            node = node.derive_child(child)?;
+        }
+        Ok(node)
+    }
+
+    /// The derived MTD seed for this node.
+    pub fn seed(&self) -> &[u8; 32] {
+        &self.key
+    }
+
+    /// Delegation part, sufficient for non-hardened derivation below this
+    /// node.
+    pub fn delegation(&self) -> ExtendedDelegationSeed {
+        let mut data = Zeroizing::new([0u8; 64]);
+        data[..32].copy_from_slice(&self.key);
+        data[32..].copy_from_slice(&self.chain_code);
+        ExtendedDelegationSeed {
+            id: poseidon_hash(data.as_ref(), DOMAIN_KEYTREE_PUBLIC),
+            chain_code: self.chain_code,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+        }
+    }
+
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.delegation().fingerprint()
+    }
+
+    pub fn parent_fingerprint(&self) -> Fingerprint {
+        self.parent_fingerprint
+    }
+
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+}
+
+/// Delegation node: derives non-hardened children only. Secret, since it
+/// derives their seeds. Auto-cleared on drop via zeroize.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExtendedDelegationSeed {
+    id: HashDigest,
+    chain_code: HashDigest,
+    depth: u8,
+    parent_fingerprint: Fingerprint,
+}
+
+impl core::fmt::Debug for ExtendedDelegationSeed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ExtendedDelegationSeed")
+            .field("depth", &self.depth)
+            .field("fingerprint", &self.fingerprint())
+            .field("parent_fingerprint", &self.parent_fingerprint)
+            .finish()
+    }
+}
+
+impl Drop for ExtendedDelegationSeed {
+    fn drop(&mut self) {
+        self.id.zeroize();
+        self.chain_code.zeroize();
+    }
+}
+
+impl ExtendedDelegationSeed {
+    pub fn derive_child(&self, child: &ChildLabel) -> Result<ExtendedSeed> {
+        if child.hardened {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: alloc::format!("Hardened child {} needs the secret parent seed", child),
+            });
+        }
+        derive(
+            NORMAL_PREFIX,
+            &self.id,
+            &self.chain_code,
+            self.depth,
+            self.fingerprint(),
+            child,
+        )
+    }
+
+    /// Derive along a relative path of non-hardened components.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<ExtendedSeed> {
+        let (first, rest) =
+            path.components()
+                .split_first()
+                .ok_or_else(|| ZKMTDError::ConfigurationError {
+                    reason: "Delegated derivation needs at least one path component".into(),
+                })?;
+        let mut node = self.derive_child(first)?;
+        for child in rest {
+            node = node.delegation().derive_child(child)?;
+        }
+        Ok(node)
+    }
+
+    pub fn fingerprint(&self) -> Fingerprint {
+        let digest = poseidon_hash(&self.id, DOMAIN_KEYTREE_FINGERPRINT);
+        let mut fingerprint = [0u8; 4];
+        fingerprint.copy_from_slice(&digest[..4]);
+        fingerprint
+    }
+
+    pub fn parent_fingerprint(&self) -> Fingerprint {
+        self.parent_fingerprint
+    }
+
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// `depth(1) ∥ parent_fingerprint(4) ∥ chain_code(32) ∥ id(32)`
+    pub fn to_bytes(&self) -> [u8; 69] {
+        let mut bytes = [0u8; 69];
+        bytes[0] = self.depth;
+        bytes[1..5].copy_from_slice(&self.parent_fingerprint);
+        bytes[5..37].copy_from_slice(&self.chain_code);
+        bytes[37..].copy_from_slice(&self.id);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 69 {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Invalid byte length: {} (expected: 69)", bytes.len()),
+            });
+        }
+        if bytes[0] as usize > MAX_KEYTREE_DEPTH {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Key tree depth {} exceeds {}", bytes[0], MAX_KEYTREE_DEPTH),
+            });
+        }
+        let mut parent_fingerprint = [0u8; 4];
+        parent_fingerprint.copy_from_slice(&bytes[1..5]);
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&bytes[5..37]);
+        let mut id = [0u8; 32];
+        id.copy_from_slice(&bytes[37..]);
+        Ok(Self {
+            id,
+            chain_code,
+            depth: bytes[0],
+            parent_fingerprint,
+        })
+    }
+}
+
+/// `prefix ∥ parent ∥ chain_code ∥ label` hashed under the child key and
+/// child chain-code domains.
+fn derive(
+    prefix: u8,
+    parent: &HashDigest,
+    chain_code: &HashDigest,
+    depth: u8,
+    parent_fingerprint: Fingerprint,
+    child: &ChildLabel,
+) -> Result<ExtendedSeed> {
+    if depth as usize >= MAX_KEYTREE_DEPTH {
+        return Err(ZKMTDError::ResourceLimitExceeded {
+            reason: alloc::format!("Key path deeper than {}", MAX_KEYTREE_DEPTH),
+        });
+    }
+    let mut data = Zeroizing::new(Vec::with_capacity(65 + child.label.len()));
+    data.push(prefix);
+    data.extend_from_slice(parent);
+    data.extend_from_slice(chain_code);
+    data.extend_from_slice(child.label.as_bytes());
+
+    Ok(ExtendedSeed {
+        key: poseidon_hash(&data, DOMAIN_KEYTREE_CHILD_KEY),
+        chain_code: poseidon_hash(&data, DOMAIN_KEYTREE_CHILD_CHAIN),
+        depth: depth + 1,
+        parent_fingerprint,
+    })
+}
+
+impl MTDManager {
+    /// Manual-mode manager for the seed at `path` below `master_seed`.
+    pub fn from_key_path(master_seed: &[u8], path: &DerivationPath, epoch: Epoch) -> Result<Self> {
+        let node = ExtendedSeed::from_path(master_seed, path)?;
+        Self::with_epoch(node.seed(), epoch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASTER: &[u8] = b"keytree-master-seed";
+
+    fn path(s: &str) -> DerivationPath {
+        DerivationPath::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_path_parse_and_display() {
+        let p = path("m/prod'/eu/payments");
+        assert_eq!(p.depth(), 3);
+        assert!(p.components()[0].is_hardened());
+        assert!(!p.components()[1].is_hardened());
+        assert_eq!(p.components()[2].label(), "payments");
+        assert_eq!(alloc::format!("{}", p), "m/prod'/eu/payments");
+        assert_eq!(path("m"), DerivationPath::master());
+
+        for bad in ["", "prod/eu", "m/", "m//eu", "m/pr od", "m/'", "x/prod"] {
+            assert!(DerivationPath::parse(bad).is_err(), "{:?} accepted", bad);
+        }
+        let long = alloc::format!("m/{}", "a".repeat(MAX_KEYTREE_LABEL_LEN + 1));
+        assert!(DerivationPath::parse(&long).is_err());
+
+        let mut deep = DerivationPath::master();
+        for _ in 0..MAX_KEYTREE_DEPTH {
+            deep = deep.child(ChildLabel::normal("x").unwrap()).unwrap();
+        }
+        assert!(deep.child(ChildLabel::normal("x").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_derivation_deterministic_and_distinct() {
+        let a = ExtendedSeed::from_path(MASTER, &path("m/prod/eu/payments")).unwrap();
+        let b = ExtendedSeed::from_path(MASTER, &path("m/prod/eu/payments")).unwrap();
+        assert_eq!(a.seed(), b.seed());
+        assert_eq!(a.depth(), 3);
+
+        let seeds = [
+            path("m"),
+            path("m/prod"),
+            path("m/prod'"),
+            path("m/prod/eu"),
+            path("m/prod/us"),
+            path("m/prod/eu/payments"),
+            path("m/staging/eu/payments"),
+        ]
+        .map(|p| *ExtendedSeed::from_path(MASTER, &p).unwrap().seed());
+        for i in 0..seeds.len() {
+            for j in (i + 1)..seeds.len() {
+                assert_ne!(seeds[i], seeds[j], "paths {} and {} collide", i, j);
+            }
+        }
+
+        let other = ExtendedSeed::from_path(b"other-master", &path("m/prod/eu/payments")).unwrap();
+        assert_ne!(a.seed(), other.seed());
+        assert!(ExtendedSeed::master(b"").is_err());
+    }
+
+    #[test]
+    fn test_delegated_derivation_matches_non_hardened() {
+        let prod = ExtendedSeed::from_path(MASTER, &path("m/prod")).unwrap();
+        let delegation = prod.delegation();
+
+        let via_secret = prod.derive_path(&path("m/eu/payments")).unwrap();
+        let via_delegation = delegation.derive_path(&path("m/eu/payments")).unwrap();
+        assert_eq!(via_secret.seed(), via_delegation.seed());
+        assert_eq!(
+            via_delegation.seed(),
+            ExtendedSeed::from_path(MASTER, &path("m/prod/eu/payments"))
+                .unwrap()
+                .seed()
+        );
+
+        // Hardened components are out of reach of the delegation part
+        assert!(delegation
+            .derive_child(&ChildLabel::hardened("eu").unwrap())
+            .is_err());
+        assert!(delegation.derive_path(&path("m/eu'/payments")).is_err());
+        assert!(delegation.derive_path(&path("m/eu/payments'")).is_err());
+        assert!(delegation.derive_path(&DerivationPath::master()).is_err());
+    }
+
+    #[test]
+    fn test_fingerprints_link_parent_and_child() {
+        let master = ExtendedSeed::master(MASTER).unwrap();
+        let child = master
+            .derive_child(&ChildLabel::hardened("prod").unwrap())
+            .unwrap();
+        let grandchild = child
+            .derive_child(&ChildLabel::normal("eu").unwrap())
+            .unwrap();
+
+        assert_eq!(master.parent_fingerprint(), [0u8; 4]);
+        assert_eq!(child.parent_fingerprint(), master.fingerprint());
+        assert_eq!(grandchild.parent_fingerprint(), child.fingerprint());
+        assert_ne!(child.fingerprint(), grandchild.fingerprint());
+        assert_eq!(child.delegation().fingerprint(), child.fingerprint());
+    }
+
+    #[test]
+    fn test_delegation_seed_serialization() {
+        let delegation = ExtendedSeed::from_path(MASTER, &path("m/prod"))
+            .unwrap()
+            .delegation();
+        let decoded = ExtendedDelegationSeed::from_bytes(&delegation.to_bytes()).unwrap();
+        assert_eq!(decoded, delegation);
+        assert_eq!(decoded.depth(), 1);
+        let debug = alloc::format!("{:?}", decoded);
+        assert!(!debug.contains("chain_code") && !debug.contains("id"));
+        assert!(ExtendedDelegationSeed::from_bytes(&[0u8; 68]).is_err());
+
+        let mut too_deep = delegation.to_bytes();
+        too_deep[0] = MAX_KEYTREE_DEPTH as u8 + 1;
+        assert!(ExtendedDelegationSeed::from_bytes(&too_deep).is_err());
+    }
+
+    #[test]
+    fn test_manager_from_key_path() {
+        let p = path("m/prod/eu/payments");
+        let manager = MTDManager::from_key_path(MASTER, &p, Epoch::new(100)).unwrap();
+        let node = ExtendedSeed::from_path(MASTER, &p).unwrap();
+        let expected = MTDManager::with_epoch(node.seed(), Epoch::new(100)).unwrap();
+        assert_eq!(manager.current_params(), expected.current_params());
+
+        let other = MTDManager::from_key_path(MASTER, &path("m/prod/us/payments"), Epoch::new(100))
+            .unwrap();
+        assert_ne!(manager.current_params(), other.current_params());
+    }
+
+    #[test]
+    fn test_extended_seed_debug_redacts_key() {
+        let node = ExtendedSeed::master(MASTER).unwrap();
+        let debug = alloc::format!("{:?}", node);
+        assert!(debug.contains("<redacted>"));
+    }
+}
@@ -2,21 +2,40 @@
 
 use crate::core::errors::{Result, ZKMTDError};
 use crate::core::traits::EntropySource;
-use crate::mtd::{Epoch, WarpingParams};
+#[cfg(feature = "std")]
+use crate::mtd::clock::SystemClock;
+use crate::mtd::clock::Clock;
+use crate::mtd::{Epoch, TenantContext, WarpingParams};
 use crate::utils::constants::{MTD_PARAM_CACHE_SIZE, TIMESTAMP_TOLERANCE_SECS};
 
 #[cfg(feature = "alloc")]
-use alloc::{collections::VecDeque, vec::Vec};
+use crate::mtd::beacon::BeaconEntropy;
+#[cfg(feature = "alloc")]
+use crate::mtd::cache::{following_epochs, range_epochs, ParamsCache};
+#[cfg(feature = "std")]
+use crate::mtd::cache::SharedParamsCache;
+#[cfg(feature = "alloc")]
+use crate::mtd::revocation::EpochRange;
+#[cfg(feature = "alloc")]
+use crate::mtd::snapshot::{EpochWatermark, SnapshotContents, SNAPSHOT_NONCE_BYTES};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+pub use crate::mtd::cache::CacheStats;
 
 use zeroize::Zeroize;
+#[cfg(feature = "alloc")]
+use zeroize::Zeroizing;
 
 pub struct MTDManager {
     seed: Vec<u8>,
     current_epoch: Epoch,
     current_params: WarpingParams,
     #[cfg(feature = "alloc")]
-    cache: VecDeque<WarpingParams>,
+    cache: ParamsCache,
     auto_advance: bool,
+    tenant: Option<TenantContext>,
 }
 
 impl core::fmt::Debug for MTDManager {
@@ -25,6 +44,7 @@ impl core::fmt::Debug for MTDManager {
             .field("seed", &"<redacted>")
             .field("current_epoch", &self.current_epoch)
             .field("auto_advance", &self.auto_advance)
+            .field("tenant", &self.tenant)
             .finish()
     }
 }
@@ -65,8 +85,9 @@ impl MTDManager {
             current_epoch,
             current_params,
             #[cfg(feature = "alloc")]
-            cache: VecDeque::with_capacity(MTD_PARAM_CACHE_SIZE),
+            cache: ParamsCache::new(MTD_PARAM_CACHE_SIZE)?,
             auto_advance: true,
+            tenant: None,
         })
     }
 
@@ -84,11 +105,40 @@ impl MTDManager {
             current_epoch: epoch,
             current_params,
             #[cfg(feature = "alloc")]
-            cache: VecDeque::with_capacity(MTD_PARAM_CACHE_SIZE),
+            cache: ParamsCache::new(MTD_PARAM_CACHE_SIZE)?,
             auto_advance: false, // Manual management mode
+            tenant: None,
         })
     }
 
+    /// Manual-mode manager for `beacon`'s epoch, with params derived from
+    /// the seed bound to the verified beacon round (see `mtd::beacon`).
+    #[cfg(feature = "alloc")]
+    pub fn from_beacon(seed: &[u8], beacon: &BeaconEntropy) -> Result<Self> {
+        if seed.is_empty() {
+            return Err(ZKMTDError::MTDError {
+                reason: "Seed is empty".into(),
+            });
+        }
+        Self::with_epoch(beacon.bind_seed(seed).as_slice(), beacon.epoch())
+    }
+
+    /// Manual-mode manager whose params are scoped to `tenant`.
+    pub fn with_epoch_for_tenant(seed: &[u8], epoch: Epoch, tenant: TenantContext) -> Result<Self> {
+        let mut manager = Self::with_epoch(seed, epoch)?;
+        manager.tenant = Some(tenant);
+        manager.current_params = manager.generate(epoch)?;
+        Ok(manager)
+    }
+
+    pub fn tenant(&self) -> Option<&TenantContext> {
+        self.tenant.as_ref()
+    }
+
+    fn generate(&self, epoch: Epoch) -> Result<WarpingParams> {
+        derive(&self.seed, self.tenant.as_ref(), epoch)
+    }
+
     pub fn current_epoch(&self) -> Epoch {
         self.current_epoch
     }
@@ -97,6 +147,11 @@ impl MTDManager {
         &self.current_params
     }
 
+    /// Fingerprint of the managed seed, as listed in revocation lists.
+    pub fn seed_fingerprint(&self) -> u64 {
+        crate::mtd::seed_fingerprint(&self.seed)
+    }
+
     pub fn get_params(&mut self, epoch: Epoch) -> Result<WarpingParams> {
         // If it's the current Epoch, return immediately
         if epoch == self.current_epoch {
@@ -106,22 +161,17 @@ impl MTDManager {
         // Search cache
         #[cfg(feature = "alloc")]
         {
-            if let Some(cached) = self.cache.iter().find(|p| p.epoch == epoch) {
-                return Ok(cached.clone());
+            if let Some(cached) = self.cache.get(epoch) {
+                return Ok(cached);
             }
         }
 
         // Cache miss: regenerate
-        let params = WarpingParams::generate(&self.seed, epoch)?;
+        let params = self.generate(epoch)?;
 
         // Add to cache
         #[cfg(feature = "alloc")]
-        {
-            if self.cache.len() >= MTD_PARAM_CACHE_SIZE {
-                self.cache.pop_front(); // Remove oldest entry
-            }
-            self.cache.push_back(params.clone());
-        }
+        self.cache.insert(params.clone());
 
         Ok(params)
     }
@@ -131,32 +181,40 @@ impl MTDManager {
 
         // Save previous parameters to cache
         #[cfg(feature = "alloc")]
-        {
-            if self.cache.len() >= MTD_PARAM_CACHE_SIZE {
-                self.cache.pop_front();
-            }
-            self.cache.push_back(self.current_params.clone());
-        }
+        self.cache.insert(self.current_params.clone());
+
+        // Generate new parameters (possibly prefetched)
+        #[cfg(feature = "alloc")]
+        let params = match self.cache.remove(next_epoch) {
+            Some(params) => params,
+            None => self.generate(next_epoch)?,
+        };
+        #[cfg(not(feature = "alloc"))]
+        let params = self.generate(next_epoch)?;
 
-        // Generate new parameters
         self.current_epoch = next_epoch;
-        self.current_params = WarpingParams::generate(&self.seed, next_epoch)?;
+        self.current_params = params;
 
         Ok(&self.current_params)
     }
 
     #[cfg(feature = "std")]
     pub fn sync(&mut self) -> Result<bool> {
+        self.sync_with(&SystemClock)
+    }
+
+    /// [`sync`](Self::sync) against an injected clock.
+    pub fn sync_with<C: Clock + ?Sized>(&mut self, clock: &C) -> Result<bool> {
         if !self.auto_advance {
             return Ok(false);
         }
 
-        let system_epoch = Epoch::current()?;
+        let system_epoch = clock.current_epoch()?;
 
         if system_epoch > self.current_epoch {
             // Epoch is behind: synchronization needed
             self.current_epoch = system_epoch;
-            self.current_params = WarpingParams::generate(&self.seed, system_epoch)?;
+            self.current_params = self.generate(system_epoch)?;
 
             // Clear cache (data is too old)
             #[cfg(feature = "alloc")]
@@ -178,6 +236,28 @@ impl MTDManager {
         }
     }
 
+    /// Move forward to `epoch`, reusing cached (e.g. prefetched) params.
+    /// The previous epoch's params stay in the cache.
+    pub fn advance_to(&mut self, epoch: Epoch) -> Result<&WarpingParams> {
+        if epoch <= self.current_epoch {
+            return Err(ZKMTDError::InvalidEpoch {
+                current: self.current_epoch.value(),
+                reason: alloc::format!("Cannot advance to epoch {}", epoch.value()),
+            });
+        }
+
+        let params = self.get_params(epoch)?;
+        #[cfg(feature = "alloc")]
+        {
+            self.cache.remove(epoch);
+            self.cache.insert(self.current_params.clone());
+        }
+
+        self.current_epoch = epoch;
+        self.current_params = params;
+        Ok(&self.current_params)
+    }
+
     pub fn set_auto_advance(&mut self, enabled: bool) {
         self.auto_advance = enabled;
     }
@@ -194,23 +274,138 @@ impl MTDManager {
 
     #[cfg(feature = "alloc")]
     pub fn cache_stats(&self) -> CacheStats {
-        CacheStats {
-            size: self.cache.len(),
-            capacity: MTD_PARAM_CACHE_SIZE,
-        }
+        self.cache.stats()
     }
 
     #[cfg(feature = "alloc")]
     pub fn clear_cache(&mut self) {
         self.cache.clear();
     }
+
+    /// Resize the params cache (default `MTD_PARAM_CACHE_SIZE`), evicting
+    /// least recently used entries if it shrinks.
+    #[cfg(feature = "alloc")]
+    pub fn set_cache_capacity(&mut self, capacity: usize) -> Result<()> {
+        self.cache.set_capacity(capacity)
+    }
+
+    /// Derive the `count` epochs after the current one ahead of use.
+    /// Returns how many were not already cached.
+    #[cfg(feature = "alloc")]
+    pub fn prefetch(&mut self, count: u64) -> Result<usize> {
+        let after = self.current_epoch;
+        let (cache, seed, tenant) = (&mut self.cache, &self.seed, &self.tenant);
+        cache.fill(following_epochs(after, count), |epoch| {
+            derive(seed, tenant.as_ref(), epoch)
+        })
+    }
+
+    /// Derive every epoch in `range` ahead of use (e.g. a verifier's
+    /// accepted window). Fails if the range does not fit the cache.
+    #[cfg(feature = "alloc")]
+    pub fn precompute(&mut self, range: EpochRange) -> Result<usize> {
+        let (cache, seed, tenant) = (&mut self.cache, &self.seed, &self.tenant);
+        cache.fill(range_epochs(range), |epoch| {
+            derive(seed, tenant.as_ref(), epoch)
+        })
+    }
+
+    /// A thread-safe cache for this manager's seed and tenant, for sharing
+    /// params between verifier threads.
+    #[cfg(feature = "std")]
+    pub fn shared_cache(&self, capacity: usize) -> Result<SharedParamsCache> {
+        match self.tenant {
+            Some(tenant) => SharedParamsCache::for_tenant(&self.seed, tenant, capacity),
+            None => SharedParamsCache::new(&self.seed, capacity),
+        }
+    }
+
+    /// Serialize the manager into a versioned blob: seed encrypted under
+    /// `kek`, epoch, auto-advance flag and cached epochs, all MAC'd.
+    /// `entropy` supplies the per-snapshot encryption nonce. The epoch is
+    /// persisted to `watermark` before the blob is returned, so no snapshot
+    /// taken earlier can be restored afterwards.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot<E: EntropySource, W: EpochWatermark>(
+        &self,
+        kek: &[u8; 32],
+        entropy: &mut E,
+        watermark: &mut W,
+    ) -> Result<Vec<u8>> {
+        if !entropy.is_cryptographically_secure() {
+            return Err(ZKMTDError::EntropyError {
+                reason: "Entropy source is not cryptographically secure".into(),
+            });
+        }
+        check_watermark(self.current_epoch, watermark)?;
+
+        let mut nonce = [0u8; SNAPSHOT_NONCE_BYTES];
+        entropy.fill_bytes(&mut nonce)?;
+
+        let contents = SnapshotContents {
+            seed: Zeroizing::new(self.seed.clone()),
+            epoch: self.current_epoch,
+            auto_advance: self.auto_advance,
+            tenant: self.tenant,
+            cached_epochs: self.cache.epochs(),
+        };
+        let blob = contents.seal(kek, &nonce);
+        watermark.persist_watermark(self.current_epoch)?;
+        Ok(blob)
+    }
+
+    /// Rebuild a manager from [`snapshot`](Self::snapshot) output. Rejects
+    /// blobs older than `watermark`, then raises it to the restored epoch so
+    /// a later restart cannot roll back past this point.
+    #[cfg(feature = "alloc")]
+    pub fn restore<W: EpochWatermark>(
+        blob: &[u8],
+        kek: &[u8; 32],
+        watermark: &mut W,
+    ) -> Result<Self> {
+        let contents = SnapshotContents::open(blob, kek)?;
+        check_watermark(contents.epoch, watermark)?;
+
+        let mut manager = match contents.tenant {
+            Some(tenant) => Self::with_epoch_for_tenant(&contents.seed, contents.epoch, tenant)?,
+            None => Self::with_epoch(&contents.seed, contents.epoch)?,
+        };
+        manager.auto_advance = contents.auto_advance;
+        if contents.cached_epochs.len() > MTD_PARAM_CACHE_SIZE {
+            manager.set_cache_capacity(contents.cached_epochs.len())?;
+        }
+        // Least recently used first, so recency order survives the restore
+        for epoch in &contents.cached_epochs {
+            let params = manager.generate(*epoch)?;
+            manager.cache.insert(params);
+        }
+
+        watermark.persist_watermark(contents.epoch)?;
+        Ok(manager)
+    }
 }
 
+/// Refuse `epoch` if it is older than the persisted watermark.
 #[cfg(feature = "alloc")]
-#[derive(Debug, Clone, Copy)]
-pub struct CacheStats {
-    pub size: usize,
-    pub capacity: usize,
+fn check_watermark<W: EpochWatermark>(epoch: Epoch, watermark: &W) -> Result<()> {
+    match watermark.load_watermark()? {
+        Some(last) if epoch < last => Err(ZKMTDError::InvalidEpoch {
+            current: epoch.value(),
+            reason: alloc::format!(
+                "Snapshot rollback: epoch {} is older than persisted epoch {}",
+                epoch.value(),
+                last.value()
+            ),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn derive(seed: &[u8], tenant: Option<&TenantContext>, epoch: Epoch) -> Result<WarpingParams> {
+    match tenant {
+        Some(tenant) => WarpingParams::generate_for_tenant(seed, epoch, tenant),
+        None => WarpingParams::generate(seed, epoch),
+    }
 }
 
 #[cfg(test)]
@@ -322,4 +517,159 @@ mod tests {
         manager.clear_cache();
         assert_eq!(manager.cache_stats().size, 0);
     }
+
+    #[test]
+    fn test_mtd_manager_advance_to() {
+        let mut manager = MTDManager::with_epoch(b"test-seed", Epoch::new(100)).unwrap();
+        let prefetched = manager.get_params(Epoch::new(103)).unwrap();
+
+        assert_eq!(manager.advance_to(Epoch::new(103)).unwrap(), &prefetched);
+        assert_eq!(manager.current_epoch(), Epoch::new(103));
+        assert!(manager.advance_to(Epoch::new(103)).is_err());
+        assert!(manager.advance_to(Epoch::new(99)).is_err());
+        // Previous epoch stays available from the cache
+        assert_eq!(
+            manager.get_params(Epoch::new(100)).unwrap(),
+            WarpingParams::generate(b"test-seed", Epoch::new(100)).unwrap()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mtd_manager_sync_with_clock() {
+        use crate::mtd::clock::ManualClock;
+        use crate::utils::constants::EPOCH_DURATION_SECS;
+
+        let clock = ManualClock::new(100 * EPOCH_DURATION_SECS);
+        let mut manager = MTDManager::with_epoch(b"test-seed", Epoch::new(100)).unwrap();
+        manager.set_auto_advance(true);
+        assert!(!manager.sync_with(&clock).unwrap());
+
+        clock.advance(2 * EPOCH_DURATION_SECS);
+        assert!(manager.sync_with(&clock).unwrap());
+        assert_eq!(manager.current_epoch(), Epoch::new(102));
+
+        clock.set(50 * EPOCH_DURATION_SECS);
+        assert!(manager.sync_with(&clock).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mtd_manager_snapshot_restore() {
+        use crate::mtd::entropy::{DeterministicEntropy, TrustedEntropy};
+        use crate::mtd::snapshot::MemoryWatermark;
+
+        let kek = [3u8; 32];
+        let mut entropy = TrustedEntropy::new(1);
+        let mut manager = MTDManager::with_epoch(b"test-seed", Epoch::new(100)).unwrap();
+        manager.advance().unwrap();
+        manager.get_params(Epoch::new(90)).unwrap();
+        manager.set_auto_advance(true);
+
+        let mut watermark = MemoryWatermark::new();
+        let blob = manager
+            .snapshot(&kek, &mut entropy, &mut watermark)
+            .unwrap();
+        assert_eq!(watermark.load_watermark().unwrap(), Some(Epoch::new(101)));
+        let mut restored = MTDManager::restore(&blob, &kek, &mut watermark).unwrap();
+
+        assert_eq!(restored.current_epoch(), Epoch::new(101));
+        assert_eq!(restored.current_params(), manager.current_params());
+        assert!(restored.auto_advance);
+        assert_eq!(restored.cache_stats().size, manager.cache_stats().size);
+        assert_eq!(
+            restored.get_params(Epoch::new(90)).unwrap(),
+            manager.get_params(Epoch::new(90)).unwrap()
+        );
+        assert_eq!(watermark.load_watermark().unwrap(), Some(Epoch::new(101)));
+
+        // Wrong KEK
+        assert!(MTDManager::restore(&blob, &[4u8; 32], &mut MemoryWatermark::new()).is_err());
+        // Insecure nonce source
+        assert!(manager
+            .snapshot(&kek, &mut DeterministicEntropy::new(1), &mut watermark)
+            .is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mtd_manager_restore_refuses_rollback() {
+        use crate::mtd::entropy::TrustedEntropy;
+        use crate::mtd::snapshot::MemoryWatermark;
+
+        let kek = [3u8; 32];
+        let mut entropy = TrustedEntropy::new(2);
+        let mut watermark = MemoryWatermark::new();
+        let mut manager = MTDManager::with_epoch(b"test-seed", Epoch::new(100)).unwrap();
+        let old = manager
+            .snapshot(&kek, &mut entropy, &mut watermark)
+            .unwrap();
+        manager.advance().unwrap();
+        let new = manager
+            .snapshot(&kek, &mut entropy, &mut watermark)
+            .unwrap();
+
+        // Taking the newer snapshot alone retires the older one
+        assert!(matches!(
+            MTDManager::restore(&old, &kek, &mut watermark),
+            Err(ZKMTDError::InvalidEpoch { current: 100, .. })
+        ));
+        // A manager behind the watermark cannot snapshot either
+        let stale = MTDManager::with_epoch(b"test-seed", Epoch::new(100)).unwrap();
+        assert!(stale.snapshot(&kek, &mut entropy, &mut watermark).is_err());
+
+        // Re-restoring the newest snapshot after a crash is allowed
+        assert!(MTDManager::restore(&new, &kek, &mut watermark).is_ok());
+        assert!(MTDManager::restore(&new, &kek, &mut watermark).is_ok());
+        assert_eq!(watermark.load_watermark().unwrap(), Some(Epoch::new(101)));
+    }
+
+    #[test]
+    fn test_mtd_manager_lru_cache_stats() {
+        let mut manager = MTDManager::with_epoch(b"test-seed", Epoch::new(100)).unwrap();
+        manager.set_cache_capacity(4).unwrap();
+
+        // A working set that fits the cache stays warm
+        for _ in 0..3 {
+            for i in 90..94 {
+                manager.get_params(Epoch::new(i)).unwrap();
+            }
+        }
+        let stats = manager.cache_stats();
+        assert_eq!((stats.size, stats.capacity), (4, 4));
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (8, 4, 0));
+
+        manager.get_params(Epoch::new(50)).unwrap();
+        assert_eq!(manager.cache_stats().evictions, 1);
+        assert!(manager.set_cache_capacity(0).is_err());
+    }
+
+    #[test]
+    fn test_mtd_manager_prefetch_and_precompute() {
+        let mut manager = MTDManager::with_epoch(b"test-seed", Epoch::new(100)).unwrap();
+        assert_eq!(manager.prefetch(3).unwrap(), 3);
+        assert_eq!(manager.prefetch(3).unwrap(), 0);
+
+        manager.advance().unwrap();
+        let stats = manager.cache_stats();
+        assert_eq!((stats.prefetched, stats.misses), (3, 0));
+        assert_eq!(
+            manager.current_params(),
+            &WarpingParams::generate(b"test-seed", Epoch::new(101)).unwrap()
+        );
+
+        let range = EpochRange {
+            start: Epoch::new(80),
+            end: Epoch::new(89),
+        };
+        assert_eq!(manager.precompute(range).unwrap(), 10);
+        manager.get_params(Epoch::new(85)).unwrap();
+        assert_eq!(manager.cache_stats().misses, 0);
+
+        let too_wide = EpochRange {
+            start: Epoch::new(0),
+            end: Epoch::new(MTD_PARAM_CACHE_SIZE as u64),
+        };
+        assert!(manager.precompute(too_wide).is_err());
+    }
 }
@@ -1,14 +1,70 @@
 //! MTD (Moving Target Defense) - epoch-based parameter rotation for replay prevention
 
+#[cfg(feature = "alloc")]
+pub mod announcement;
+#[cfg(feature = "alloc")]
+pub mod beacon;
+#[cfg(feature = "alloc")]
+pub mod cache;
+pub mod clock;
+pub mod drbg;
 pub mod entropy;
 pub mod epoch;
+#[cfg(feature = "alloc")]
+pub mod keytree;
 pub mod manager;
+#[cfg(feature = "alloc")]
+pub mod pool;
+pub mod revocation;
+#[cfg(feature = "std")]
+pub mod scheduler;
+#[cfg(feature = "alloc")]
+pub mod snapshot;
+pub mod tenant;
+#[cfg(feature = "alloc")]
+pub mod threshold;
 pub mod warping;
 
+#[cfg(feature = "alloc")]
+pub use announcement::{AnnouncementPublisher, EpochAnnouncement, ScheduleCommitment};
+#[cfg(feature = "alloc")]
+pub use beacon::{BeaconEntropy, BeaconInfo, BeaconRound, BeaconSource, MemoryBeacon};
+#[cfg(feature = "std")]
+pub use beacon::FileBeacon;
+#[cfg(feature = "alloc")]
+pub use cache::{CacheStats, ParamsCache};
+#[cfg(feature = "std")]
+pub use cache::SharedParamsCache;
+#[cfg(feature = "alloc")]
+pub use clock::ManualClock;
+#[cfg(feature = "std")]
+pub use clock::SystemClock;
+pub use clock::Clock;
+pub use drbg::DrbgEntropy;
 #[cfg(any(feature = "solana-adapter", feature = "solana-program"))]
 pub use entropy::SolanaEntropy;
 #[cfg(feature = "std")]
 pub use entropy::SystemEntropy;
 pub use epoch::Epoch;
+#[cfg(feature = "alloc")]
+pub use keytree::{ChildLabel, DerivationPath, ExtendedDelegationSeed, ExtendedSeed};
 pub use manager::MTDManager;
+#[cfg(feature = "alloc")]
+pub use pool::EntropyPool;
+#[cfg(feature = "alloc")]
+pub use revocation::RevocationList;
+pub use revocation::{seed_fingerprint, EpochRange, RevocationCommitment, RevocationReason};
+#[cfg(feature = "std")]
+pub use scheduler::{EpochListener, EpochScheduler, SchedulerConfig, TickOutcome};
+#[cfg(feature = "std")]
+pub use snapshot::FileWatermark;
+#[cfg(feature = "alloc")]
+pub use snapshot::{EpochWatermark, MemoryWatermark};
+#[cfg(feature = "alloc")]
+pub use tenant::MultiTenantMTDManager;
+pub use tenant::{ApplicationDomain, TenantContext, TenantId};
+#[cfg(feature = "alloc")]
+pub use threshold::{
+    combine_epoch_key, split_seed, EpochMask, SeedShare, ShareCommitments, ThresholdEpochKey,
+};
 pub use warping::WarpingParams;
@@ -0,0 +1,546 @@
+//! Health-tested entropy pool - mixes several sources through Poseidon2
+//!
+//! Every byte drawn from a source runs through the NIST SP 800-90B continuous
+//! health tests (repetition count, adaptive proportion) before it is mixed.
+//! A failed test poisons the pool permanently: all further requests return
+//! `EntropyError` rather than degrading silently.
+//!
+//! `entropy_bits` is not the sources' self-reported figure but a conservative
+//! most-common-value estimate (SP 800-90B 6.3.1) over the samples observed,
+//! capped by each source's declared and assessed entropy. Sources that do not
+//! claim to be cryptographically secure contribute nothing to the estimate.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::traits::EntropySource;
+use crate::core::types::HashDigest;
+use crate::utils::constants::{
+    DOMAIN_ENTROPY_POOL_MIX, DOMAIN_ENTROPY_POOL_OUTPUT, MIN_ENTROPY_BITS,
+};
+use crate::utils::hash::poseidon_hash;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Samples run through the health tests when a source is added (SP 800-90B 4.3).
+pub const STARTUP_SAMPLES: usize = 1024;
+/// Bytes drawn from each source per `fill_bytes` call.
+pub const SAMPLES_PER_SOURCE: usize = 64;
+/// Assessed min-entropy per byte used by [`EntropyPool::add_source`].
+pub const DEFAULT_ASSESSED_BITS_PER_BYTE: u8 = 4;
+
+/// Adaptive proportion test window for non-binary samples.
+const APT_WINDOW: u32 = 512;
+/// APT cutoffs for W = 512, alpha = 2^-20, indexed by assessed bits per byte - 1.
+const APT_CUTOFFS: [u32; 8] = [311, 177, 103, 62, 39, 25, 18, 13];
+/// False-positive exponent: alpha = 2^-20.
+const HEALTH_ALPHA_BITS: u32 = 20;
+/// Histogram size at which counts are halved, so the estimate tracks recent output.
+const MAX_HISTOGRAM_SAMPLES: u32 = 1 << 20;
+
+/// Continuous health tests and min-entropy bookkeeping for one source.
+struct HealthMonitor {
+    assessed_bits: u8,
+    rct_cutoff: u32,
+    apt_cutoff: u32,
+    last: u8,
+    run: u32,
+    apt_first: u8,
+    apt_count: u32,
+    apt_seen: u32,
+    counts: [u32; 256],
+    total: u32,
+}
+
+impl HealthMonitor {
+    fn new(assessed_bits: u8) -> Self {
+        let h = assessed_bits as u32;
+        Self {
+            assessed_bits,
+            // C = 1 + ceil(-log2(alpha) / H)
+            rct_cutoff: 1 + HEALTH_ALPHA_BITS.div_ceil(h),
+            apt_cutoff: APT_CUTOFFS[assessed_bits as usize - 1],
+            last: 0,
+            run: 0,
+            apt_first: 0,
+            apt_count: 0,
+            apt_seen: 0,
+            counts: [0; 256],
+            total: 0,
+        }
+    }
+
+    fn feed(&mut self, sample: u8) -> core::result::Result<(), &'static str> {
+        // Repetition count test
+        if self.run > 0 && sample == self.last {
+            self.run += 1;
+            if self.run >= self.rct_cutoff {
+                return Err("repetition count test failed");
+            }
+        } else {
+            self.last = sample;
+            self.run = 1;
+        }
+
+        // Adaptive proportion test
+        if self.apt_seen == 0 {
+            self.apt_first = sample;
+            self.apt_count = 1;
+        } else if sample == self.apt_first {
+            self.apt_count += 1;
+            if self.apt_count >= self.apt_cutoff {
+                return Err("adaptive proportion test failed");
+            }
+        }
+        self.apt_seen = (self.apt_seen + 1) % APT_WINDOW;
+
+        if self.total == MAX_HISTOGRAM_SAMPLES {
+            self.counts.iter_mut().for_each(|c| *c /= 2);
+            self.total = self.counts.iter().sum();
+        }
+        self.counts[sample as usize] += 1;
+        self.total += 1;
+        Ok(())
+    }
+
+    /// Most-common-value min-entropy estimate per byte, in 1/65536 bits.
+    /// Zero until the startup sample count has been observed.
+    fn min_entropy_q16(&self) -> u64 {
+        let n = self.total as u64;
+        if n < STARTUP_SAMPLES as u64 {
+            return 0;
+        }
+        let c = *self.counts.iter().max().unwrap_or(&0) as u64;
+
+        // p_u = min(1, p + 2.576 * sqrt(p(1-p) / (n-1))), scaled by n * 2^16
+        let variance_q32 = ((c * (n - c)) as u128) << 32;
+        let sigma_q16 = isqrt(variance_q32 / (n - 1) as u128) as u64;
+        let upper = ((c << 16) + (sigma_q16 * 2576).div_ceil(1000)).min(n << 16);
+
+        // -log2(p_u); one ulp off to stay below the true value
+        (log2_q16(n << 16).saturating_sub(log2_q16(upper))).saturating_sub(1)
+    }
+
+    /// Conservative entropy credited for `samples` bytes from this source.
+    fn credited_bits(&self, samples: usize) -> usize {
+        let per_byte = self
+            .min_entropy_q16()
+            .min((self.assessed_bits as u64) << 16);
+        ((per_byte * samples as u64) >> 16) as usize
+    }
+}
+
+impl Drop for HealthMonitor {
+    fn drop(&mut self) {
+        self.last.zeroize();
+        self.apt_first.zeroize();
+        self.counts.zeroize();
+    }
+}
+
+struct PooledSource {
+    source: Box<dyn EntropySource + Send>,
+    health: HealthMonitor,
+}
+
+/// Entropy source that mixes several health-tested sources.
+pub struct EntropyPool {
+    sources: Vec<PooledSource>,
+    state: Zeroizing<HashDigest>,
+    counter: u64,
+    failure: Option<String>,
+}
+
+impl core::fmt::Debug for EntropyPool {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EntropyPool")
+            .field("sources", &self.sources.len())
+            .field("state", &"<redacted>")
+            .field("counter", &self.counter)
+            .field("failure", &self.failure)
+            .finish()
+    }
+}
+
+impl Default for EntropyPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntropyPool {
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            state: Zeroizing::new([0u8; 32]),
+            counter: 0,
+            failure: None,
+        }
+    }
+
+    /// Add a source assessed at [`DEFAULT_ASSESSED_BITS_PER_BYTE`].
+    pub fn add_source<E: EntropySource + Send + 'static>(&mut self, source: E) -> Result<()> {
+        self.add_source_with_assessment(source, DEFAULT_ASSESSED_BITS_PER_BYTE)
+    }
+
+    /// Add a source whose output has been assessed at `bits_per_byte` of
+    /// min-entropy (1..=8). The assessment sets the health-test cutoffs and
+    /// caps the entropy credited to the source. [`STARTUP_SAMPLES`] bytes are
+    /// health-tested first; a source failing them is rejected.
+    pub fn add_source_with_assessment<E: EntropySource + Send + 'static>(
+        &mut self,
+        source: E,
+        bits_per_byte: u8,
+    ) -> Result<()> {
+        self.ensure_healthy()?;
+        if !(1..=8).contains(&bits_per_byte) {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: alloc::format!(
+                    "Assessed entropy {} bits/byte out of range (1..=8)",
+                    bits_per_byte
+                ),
+            });
+        }
+
+        let mut pooled = PooledSource {
+            source: Box::new(source),
+            health: HealthMonitor::new(bits_per_byte),
+        };
+        let mut samples = Zeroizing::new(vec![0u8; STARTUP_SAMPLES]);
+        pooled.source.fill_bytes(&mut samples)?;
+        for &sample in samples.iter() {
+            pooled
+                .health
+                .feed(sample)
+                .map_err(|test| ZKMTDError::EntropyError {
+                    reason: alloc::format!("Startup health test: {}", test),
+                })?;
+        }
+
+        self.absorb(self.sources.len(), &samples);
+        self.sources.push(pooled);
+        Ok(())
+    }
+
+    pub fn num_sources(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// False once any health test has failed; the pool never recovers.
+    pub fn is_healthy(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    fn ensure_healthy(&self) -> Result<()> {
+        match &self.failure {
+            Some(reason) => Err(ZKMTDError::EntropyError {
+                reason: alloc::format!("Entropy pool disabled: {}", reason),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn absorb(&mut self, index: usize, samples: &[u8]) {
+        let mut input = Zeroizing::new(Vec::with_capacity(49 + samples.len()));
+        input.push(0);
+        input.extend_from_slice(self.state.as_ref());
+        input.extend_from_slice(&self.counter.to_le_bytes());
+        input.extend_from_slice(&(index as u64).to_le_bytes());
+        input.extend_from_slice(samples);
+        *self.state = poseidon_hash(&input, DOMAIN_ENTROPY_POOL_MIX);
+    }
+
+    /// Draw fresh samples from every source, health-testing each byte.
+    fn reseed(&mut self) -> Result<()> {
+        let mut samples = Zeroizing::new([0u8; SAMPLES_PER_SOURCE]);
+        for index in 0..self.sources.len() {
+            let pooled = &mut self.sources[index];
+            pooled.source.fill_bytes(samples.as_mut())?;
+            for &sample in samples.iter() {
+                if let Err(test) = pooled.health.feed(sample) {
+                    self.failure = Some(alloc::format!("source {}: {}", index, test));
+                    self.state.zeroize();
+                    return self.ensure_healthy();
+                }
+            }
+            self.absorb(index, samples.as_ref());
+        }
+        Ok(())
+    }
+}
+
+impl EntropySource for EntropyPool {
+    fn generate(&mut self, num_bytes: usize) -> Result<Vec<u8>> {
+        let mut buffer = vec![0u8; num_bytes];
+        self.fill_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn fill_bytes(&mut self, output: &mut [u8]) -> Result<()> {
+        self.ensure_healthy()?;
+        if output.is_empty() {
+            return Ok(());
+        }
+        self.reseed()?;
+
+        let credited = self.entropy_bits();
+        if credited < MIN_ENTROPY_BITS {
+            return Err(ZKMTDError::EntropyError {
+                reason: alloc::format!(
+                    "Estimated pool entropy too low: {} < {}",
+                    credited,
+                    MIN_ENTROPY_BITS
+                ),
+            });
+        }
+
+        let mut block_input = Zeroizing::new([0u8; 48]);
+        block_input[..32].copy_from_slice(self.state.as_ref());
+        block_input[32..40].copy_from_slice(&self.counter.to_le_bytes());
+        for (i, chunk) in output.chunks_mut(32).enumerate() {
+            block_input[40..].copy_from_slice(&(i as u64).to_le_bytes());
+            let block = Zeroizing::new(poseidon_hash(
+                block_input.as_ref(),
+                DOMAIN_ENTROPY_POOL_OUTPUT,
+            ));
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+
+        // Ratchet so a later state compromise does not reveal this output
+        let mut ratchet = Zeroizing::new([0u8; 41]);
+        ratchet[0] = 1;
+        ratchet[1..33].copy_from_slice(self.state.as_ref());
+        ratchet[33..].copy_from_slice(&self.counter.to_le_bytes());
+        *self.state = poseidon_hash(ratchet.as_ref(), DOMAIN_ENTROPY_POOL_MIX);
+        self.counter = self.counter.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Conservative estimate of the entropy mixed into each output, capped at
+    /// the 256-bit pool state.
+    fn entropy_bits(&self) -> usize {
+        if !self.is_healthy() {
+            return 0;
+        }
+        let total: usize = self
+            .sources
+            .iter()
+            .filter(|p| p.source.is_cryptographically_secure())
+            .map(|p| {
+                p.health
+                    .credited_bits(SAMPLES_PER_SOURCE)
+                    .min(p.source.entropy_bits())
+            })
+            .sum();
+        total.min(256)
+    }
+
+    /// Derived from the health-tested estimate, not from the sources' claims.
+    fn is_cryptographically_secure(&self) -> bool {
+        self.entropy_bits() >= MIN_ENTROPY_BITS
+    }
+}
+
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// floor(log2(x) * 2^16) for x > 0.
+fn log2_q16(x: u64) -> u64 {
+    let int = 63 - x.leading_zeros() as u64;
+    // Mantissa in [1, 2) as Q32
+    let mut m = ((x as u128) << 32) >> int;
+    let mut frac = 0u64;
+    for bit in (0..16).rev() {
+        m = (m * m) >> 32;
+        if m >= 2 << 32 {
+            m >>= 1;
+            frac |= 1 << bit;
+        }
+    }
+    (int << 16) | frac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtd::entropy::{DeterministicEntropy, TrustedEntropy};
+
+    /// Emits `good` output but replaces bytes according to `fault`.
+    struct FaultySource {
+        inner: DeterministicEntropy,
+        emitted: usize,
+        healthy_for: usize,
+        fault: fn(usize, u8) -> u8,
+    }
+
+    impl EntropySource for FaultySource {
+        fn generate(&mut self, num_bytes: usize) -> Result<Vec<u8>> {
+            let mut buffer = vec![0u8; num_bytes];
+            self.fill_bytes(&mut buffer)?;
+            Ok(buffer)
+        }
+        fn fill_bytes(&mut self, output: &mut [u8]) -> Result<()> {
+            self.inner.fill_bytes(output)?;
+            for byte in output.iter_mut() {
+                if self.emitted >= self.healthy_for {
+                    *byte = (self.fault)(self.emitted, *byte);
+                }
+                self.emitted += 1;
+            }
+            Ok(())
+        }
+        fn entropy_bits(&self) -> usize {
+            256
+        }
+        fn is_cryptographically_secure(&self) -> bool {
+            true
+        }
+    }
+
+    fn faulty(healthy_for: usize, fault: fn(usize, u8) -> u8) -> FaultySource {
+        FaultySource {
+            inner: DeterministicEntropy::new(7),
+            emitted: 0,
+            healthy_for,
+            fault,
+        }
+    }
+
+    #[test]
+    fn test_log2_and_isqrt() {
+        assert_eq!(log2_q16(1), 0);
+        assert_eq!(log2_q16(1024), 10 << 16);
+        // log2(3) = 1.58496...
+        assert_eq!(log2_q16(3) >> 8, (1.58496f64 * 256.0) as u64);
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(99), 9);
+        assert_eq!(isqrt(1 << 70), 1 << 35);
+    }
+
+    #[test]
+    fn test_pool_generates_and_mixes() {
+        let mut pool = EntropyPool::new();
+        pool.add_source(TrustedEntropy(DeterministicEntropy::new(1)))
+            .unwrap();
+        pool.add_source(TrustedEntropy(DeterministicEntropy::new(2)))
+            .unwrap();
+        assert_eq!(pool.num_sources(), 2);
+        assert!(pool.is_cryptographically_secure());
+        assert_eq!(pool.entropy_bits(), 256);
+
+        let a = pool.generate(80).unwrap();
+        let b = pool.generate(80).unwrap();
+        assert_eq!(a.len(), 80);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_min_entropy_estimate_is_conservative() {
+        let mut pool = EntropyPool::new();
+        pool.add_source_with_assessment(TrustedEntropy(DeterministicEntropy::new(3)), 8)
+            .unwrap();
+        // Near-uniform bytes over 1024 samples estimate well below 8 bits/byte
+        let estimate = pool.entropy_bits();
+        assert!(estimate >= MIN_ENTROPY_BITS, "estimate {}", estimate);
+        assert!(estimate < 8 * SAMPLES_PER_SOURCE, "estimate {}", estimate);
+    }
+
+    #[test]
+    fn test_insecure_sources_not_credited() {
+        let mut pool = EntropyPool::new();
+        pool.add_source(DeterministicEntropy::new(1)).unwrap();
+        assert_eq!(pool.entropy_bits(), 0);
+        assert!(!pool.is_cryptographically_secure());
+        assert!(pool.generate(32).is_err());
+
+        pool.add_source(TrustedEntropy(DeterministicEntropy::new(2)))
+            .unwrap();
+        assert!(pool.generate(32).is_ok());
+    }
+
+    #[test]
+    fn test_stuck_source_rejected_at_startup() {
+        let mut pool = EntropyPool::new();
+        let err = pool.add_source(faulty(0, |_, _| 0xAA)).unwrap_err();
+        assert!(matches!(err, ZKMTDError::EntropyError { .. }));
+        assert_eq!(pool.num_sources(), 0);
+        assert!(pool.is_healthy());
+    }
+
+    #[test]
+    fn test_repetition_count_failure_poisons_pool() {
+        let mut pool = EntropyPool::new();
+        pool.add_source(TrustedEntropy(DeterministicEntropy::new(1)))
+            .unwrap();
+        pool.add_source(faulty(STARTUP_SAMPLES, |_, _| 0)).unwrap();
+        assert!(pool.generate(32).is_err());
+        assert!(!pool.is_healthy());
+        assert_eq!(pool.entropy_bits(), 0);
+        // Fails closed: the healthy source cannot revive the pool
+        assert!(pool.generate(32).is_err());
+        assert!(pool
+            .add_source(TrustedEntropy(DeterministicEntropy::new(9)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_adaptive_proportion_failure() {
+        // Every other byte is 0x00: no long runs, but far too frequent
+        let source = faulty(0, |i, b| if i % 2 == 0 { 0 } else { b | 1 });
+        let mut monitor = HealthMonitor::new(DEFAULT_ASSESSED_BITS_PER_BYTE);
+        let mut inner = source;
+        let mut samples = [0u8; APT_WINDOW as usize];
+        inner.fill_bytes(&mut samples).unwrap();
+        let result = samples.iter().try_for_each(|&s| monitor.feed(s));
+        assert_eq!(result, Err("adaptive proportion test failed"));
+
+        let mut pool = EntropyPool::new();
+        assert!(pool
+            .add_source(faulty(0, |i, b| if i % 2 == 0 { 0 } else { b | 1 }))
+            .is_err());
+    }
+
+    #[test]
+    fn test_invalid_assessment() {
+        let mut pool = EntropyPool::new();
+        assert!(pool
+            .add_source_with_assessment(TrustedEntropy(DeterministicEntropy::new(1)), 0)
+            .is_err());
+        assert!(pool
+            .add_source_with_assessment(TrustedEntropy(DeterministicEntropy::new(1)), 9)
+            .is_err());
+    }
+
+    #[test]
+    fn test_pool_debug_redacts_state() {
+        let mut pool = EntropyPool::new();
+        pool.add_source(TrustedEntropy(DeterministicEntropy::new(1)))
+            .unwrap();
+        let debug = alloc::format!("{:?}", pool);
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_pool_with_system_entropy() {
+        use crate::mtd::{MTDManager, SystemEntropy};
+
+        let mut pool = EntropyPool::new();
+        pool.add_source(SystemEntropy::new()).unwrap();
+        assert!(pool.is_cryptographically_secure());
+        assert!(MTDManager::new(b"pool-seed", &mut pool).is_ok());
+    }
+}
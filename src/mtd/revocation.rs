@@ -0,0 +1,454 @@
+//! Revocation lists - reject proofs from leaked epochs or seeds
+//!
+//! A [`RevocationList`] names revoked epoch ranges and revoked seed
+//! fingerprints (the `seed_fingerprint` of
+//! [`ProofCommitment`](crate::solana::ProofCommitment)). It serializes to a
+//! canonical encoding, commits to a Merkle root together with a monotonic
+//! sequence number, and that commitment can be signed with an XMSS key
+//! (`XmssSigner::sign_revocation_list`). Verifiers configured with a list
+//! reject matching proofs with a dedicated [`ZKMTDError::Revoked`] error (or
+//! a `Revoked*` on-chain status) rather than an ordinary verification failure.
+//!
+//! Only the newest list (highest sequence) should be installed; the sequence
+//! is signed so an older list cannot be replayed as if it were current.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::mtd::Epoch;
+use crate::utils::constants::DOMAIN_SEED_FINGERPRINT;
+use crate::utils::hash::poseidon_hash;
+
+#[cfg(feature = "alloc")]
+use crate::batching::merkle::MerkleTree;
+#[cfg(feature = "alloc")]
+use crate::core::types::HashDigest;
+#[cfg(feature = "alloc")]
+use crate::utils::constants::{DOMAIN_REVOCATION_ENTRY, MAX_REVOCATION_ENTRIES};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+const MAGIC: &[u8; 8] = b"ZKMTDREV";
+#[cfg(feature = "alloc")]
+const FORMAT_VERSION: u8 = 1;
+/// magic ∥ version ∥ sequence ∥ range count ∥ fingerprint count
+#[cfg(feature = "alloc")]
+const HEADER_LEN: usize = 8 + 1 + 8 + 4 + 4;
+
+#[cfg(feature = "alloc")]
+const LEAF_HEADER: u8 = 0;
+#[cfg(feature = "alloc")]
+const LEAF_EPOCH_RANGE: u8 = 1;
+#[cfg(feature = "alloc")]
+const LEAF_SEED: u8 = 2;
+
+/// 64-bit seed fingerprint, identical to `ProofCommitment::seed_fingerprint`.
+pub fn seed_fingerprint(seed: &[u8]) -> u64 {
+    let hash = poseidon_hash(seed, DOMAIN_SEED_FINGERPRINT);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Why a proof was rejected by a revocation list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevocationReason {
+    Epoch(Epoch),
+    Seed(u64),
+    /// The list revokes seeds but the proof's seed is not known, so it
+    /// cannot be cleared.
+    UnknownSeed,
+}
+
+impl core::fmt::Display for RevocationReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Epoch(epoch) => write!(f, "{} is revoked", epoch),
+            Self::Seed(fingerprint) => write!(f, "seed {:016x} is revoked", fingerprint),
+            Self::UnknownSeed => write!(f, "seed is unknown while seeds are revoked"),
+        }
+    }
+}
+
+impl From<RevocationReason> for ZKMTDError {
+    fn from(reason: RevocationReason) -> Self {
+        ZKMTDError::Revoked {
+            #[cfg(feature = "alloc")]
+            reason: alloc::format!("{}", reason),
+            #[cfg(not(feature = "alloc"))]
+            reason: match reason {
+                RevocationReason::Epoch(_) => "epoch is revoked",
+                RevocationReason::Seed(_) => "seed is revoked",
+                RevocationReason::UnknownSeed => "seed is unknown while seeds are revoked",
+            },
+        }
+    }
+}
+
+/// Inclusive range of revoked epochs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EpochRange {
+    pub start: Epoch,
+    pub end: Epoch,
+}
+
+impl EpochRange {
+    pub fn contains(&self, epoch: Epoch) -> bool {
+        self.start <= epoch && epoch <= self.end
+    }
+}
+
+/// Signed-over summary of a revocation list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RevocationCommitment {
+    pub sequence: u64,
+    pub root: [u8; 32],
+}
+
+impl RevocationCommitment {
+    pub fn to_bytes(&self) -> [u8; 40] {
+        let mut bytes = [0u8; 40];
+        bytes[..8].copy_from_slice(&self.sequence.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.root);
+        bytes
+    }
+}
+
+/// Revoked epoch ranges and seed fingerprints, kept in canonical form:
+/// ranges sorted, merged and non-adjacent, fingerprints sorted and unique.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RevocationList {
+    sequence: u64,
+    epoch_ranges: Vec<EpochRange>,
+    seed_fingerprints: Vec<u64>,
+}
+
+#[cfg(feature = "alloc")]
+impl RevocationList {
+    /// Empty list. `sequence` must grow with every list the issuer publishes.
+    pub fn new(sequence: u64) -> Self {
+        Self {
+            sequence,
+            epoch_ranges: Vec::new(),
+            seed_fingerprints: Vec::new(),
+        }
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn epoch_ranges(&self) -> &[EpochRange] {
+        &self.epoch_ranges
+    }
+
+    pub fn seed_fingerprints(&self) -> &[u64] {
+        &self.seed_fingerprints
+    }
+
+    pub fn len(&self) -> usize {
+        self.epoch_ranges.len() + self.seed_fingerprints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Revoke every epoch in `start..=end`, merging with overlapping or
+    /// adjacent ranges.
+    pub fn revoke_epochs(&mut self, start: Epoch, end: Epoch) -> Result<()> {
+        if start > end {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: alloc::format!("Empty epoch range {}..={}", start.value(), end.value()),
+            });
+        }
+
+        let mut merged = EpochRange { start, end };
+        let mut kept = Vec::with_capacity(self.epoch_ranges.len() + 1);
+        for range in self.epoch_ranges.drain(..) {
+            let touches = range.start.value() <= merged.end.value().saturating_add(1)
+                && merged.start.value() <= range.end.value().saturating_add(1);
+            if touches {
+                merged.start = merged.start.min(range.start);
+                merged.end = merged.end.max(range.end);
+            } else {
+                kept.push(range);
+            }
+        }
+        let at = kept.partition_point(|r| r.start < merged.start);
+        kept.insert(at, merged);
+        self.epoch_ranges = kept;
+        self.check_size()
+    }
+
+    pub fn revoke_epoch(&mut self, epoch: Epoch) -> Result<()> {
+        self.revoke_epochs(epoch, epoch)
+    }
+
+    pub fn revoke_seed(&mut self, fingerprint: u64) -> Result<()> {
+        if let Err(at) = self.seed_fingerprints.binary_search(&fingerprint) {
+            self.seed_fingerprints.insert(at, fingerprint);
+        }
+        self.check_size()
+    }
+
+    pub fn is_epoch_revoked(&self, epoch: Epoch) -> bool {
+        let at = self.epoch_ranges.partition_point(|r| r.start <= epoch);
+        at > 0 && self.epoch_ranges[at - 1].contains(epoch)
+    }
+
+    pub fn is_seed_revoked(&self, fingerprint: u64) -> bool {
+        self.seed_fingerprints.binary_search(&fingerprint).is_ok()
+    }
+
+    /// First revocation matching a proof's epoch or its seed. A proof whose
+    /// seed is unknown (`None`) fails closed once any seed is revoked.
+    pub fn check(&self, epoch: Epoch, fingerprint: Option<u64>) -> Option<RevocationReason> {
+        match fingerprint {
+            Some(fingerprint) if self.is_seed_revoked(fingerprint) => {
+                return Some(RevocationReason::Seed(fingerprint));
+            }
+            None if !self.seed_fingerprints.is_empty() => {
+                return Some(RevocationReason::UnknownSeed);
+            }
+            _ => {}
+        }
+        if self.is_epoch_revoked(epoch) {
+            return Some(RevocationReason::Epoch(epoch));
+        }
+        None
+    }
+
+    /// Merkle root over the sequence number and every entry.
+    pub fn merkle_root(&self) -> HashDigest {
+        let mut leaves = Vec::with_capacity(1 + self.len());
+        leaves.push(entry_leaf(LEAF_HEADER, &self.sequence.to_le_bytes()));
+        for range in &self.epoch_ranges {
+            let mut data = [0u8; 16];
+            data[..8].copy_from_slice(&range.start.to_bytes());
+            data[8..].copy_from_slice(&range.end.to_bytes());
+            leaves.push(entry_leaf(LEAF_EPOCH_RANGE, &data));
+        }
+        for fingerprint in &self.seed_fingerprints {
+            leaves.push(entry_leaf(LEAF_SEED, &fingerprint.to_le_bytes()));
+        }
+        // Never empty: the header leaf is always present.
+        match MerkleTree::new(leaves) {
+            Ok(tree) => *tree.root(),
+            Err(_) => [0u8; 32],
+        }
+    }
+
+    pub fn commitment(&self) -> RevocationCommitment {
+        RevocationCommitment {
+            sequence: self.sequence,
+            root: self.merkle_root(),
+        }
+    }
+
+    /// `"ZKMTDREV" ∥ version ∥ sequence ∥ #ranges ∥ #seeds ∥ ranges ∥ seeds`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            HEADER_LEN + self.epoch_ranges.len() * 16 + self.seed_fingerprints.len() * 8,
+        );
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&self.sequence.to_le_bytes());
+        bytes.extend_from_slice(&(self.epoch_ranges.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.seed_fingerprints.len() as u32).to_le_bytes());
+        for range in &self.epoch_ranges {
+            bytes.extend_from_slice(&range.start.to_bytes());
+            bytes.extend_from_slice(&range.end.to_bytes());
+        }
+        for fingerprint in &self.seed_fingerprints {
+            bytes.extend_from_slice(&fingerprint.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Parse a list, rejecting any non-canonical encoding so that one list
+    /// has exactly one byte representation and one commitment.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return Err(ZKMTDError::SerializationError {
+                reason: "Not a revocation list".into(),
+            });
+        }
+        if bytes[8] != FORMAT_VERSION {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Unsupported revocation list version {}", bytes[8]),
+            });
+        }
+        let read_u64 = |at: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&bytes[at..at + 8]);
+            u64::from_le_bytes(buf)
+        };
+        let read_u32 = |at: usize| {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(&bytes[at..at + 4]);
+            u32::from_le_bytes(buf) as usize
+        };
+        let sequence = read_u64(9);
+        let num_ranges = read_u32(17);
+        let num_seeds = read_u32(21);
+        if num_ranges + num_seeds > MAX_REVOCATION_ENTRIES
+            || bytes.len() != HEADER_LEN + num_ranges * 16 + num_seeds * 8
+        {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!(
+                    "Invalid revocation list length: {} bytes for {} ranges, {} seeds",
+                    bytes.len(),
+                    num_ranges,
+                    num_seeds
+                ),
+            });
+        }
+
+        let mut list = Self::new(sequence);
+        let mut at = HEADER_LEN;
+        for _ in 0..num_ranges {
+            let start = Epoch::try_new(read_u64(at))?;
+            let end = Epoch::try_new(read_u64(at + 8))?;
+            list.revoke_epochs(start, end)?;
+            at += 16;
+        }
+        for _ in 0..num_seeds {
+            list.revoke_seed(read_u64(at))?;
+            at += 8;
+        }
+
+        if list.to_bytes() != bytes {
+            return Err(ZKMTDError::SerializationError {
+                reason: "Non-canonical revocation list encoding".into(),
+            });
+        }
+        Ok(list)
+    }
+
+    fn check_size(&self) -> Result<()> {
+        if self.len() > MAX_REVOCATION_ENTRIES {
+            return Err(ZKMTDError::ResourceLimitExceeded {
+                reason: alloc::format!(
+                    "Revocation list exceeds {} entries",
+                    MAX_REVOCATION_ENTRIES
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn entry_leaf(kind: u8, data: &[u8]) -> HashDigest {
+    let mut buf = [0u8; 17];
+    buf[0] = kind;
+    buf[1..1 + data.len()].copy_from_slice(data);
+    poseidon_hash(&buf[..1 + data.len()], DOMAIN_REVOCATION_ENTRY)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    fn sample() -> RevocationList {
+        let mut list = RevocationList::new(7);
+        list.revoke_epochs(Epoch::new(5000), Epoch::new(5010))
+            .unwrap();
+        list.revoke_epoch(Epoch::new(42)).unwrap();
+        list.revoke_seed(seed_fingerprint(b"leaked-seed")).unwrap();
+        list
+    }
+
+    #[test]
+    fn test_revocation_checks() {
+        let mut list = sample();
+        assert!(list.is_epoch_revoked(Epoch::new(5000)));
+        assert!(list.is_epoch_revoked(Epoch::new(5010)));
+        assert!(list.is_epoch_revoked(Epoch::new(42)));
+        assert!(!list.is_epoch_revoked(Epoch::new(4999)));
+        assert!(!list.is_epoch_revoked(Epoch::new(5011)));
+
+        let leaked = seed_fingerprint(b"leaked-seed");
+        assert_eq!(
+            list.check(Epoch::new(1), Some(leaked)),
+            Some(RevocationReason::Seed(leaked))
+        );
+        assert_eq!(
+            list.check(Epoch::new(5005), Some(seed_fingerprint(b"fine"))),
+            Some(RevocationReason::Epoch(Epoch::new(5005)))
+        );
+        assert_eq!(
+            list.check(Epoch::new(1), None),
+            Some(RevocationReason::UnknownSeed)
+        );
+        let mut epochs_only = RevocationList::new(8);
+        epochs_only.revoke_epoch(Epoch::new(42)).unwrap();
+        assert_eq!(epochs_only.check(Epoch::new(1), None), None);
+        assert!(list.revoke_epochs(Epoch::new(9), Epoch::new(8)).is_err());
+    }
+
+    #[test]
+    fn test_epoch_ranges_merge() {
+        let mut list = RevocationList::new(1);
+        list.revoke_epochs(Epoch::new(10), Epoch::new(20)).unwrap();
+        list.revoke_epochs(Epoch::new(30), Epoch::new(40)).unwrap();
+        list.revoke_epochs(Epoch::new(21), Epoch::new(29)).unwrap();
+        list.revoke_epochs(Epoch::new(1), Epoch::new(2)).unwrap();
+        assert_eq!(
+            list.epoch_ranges(),
+            [
+                EpochRange {
+                    start: Epoch::new(1),
+                    end: Epoch::new(2)
+                },
+                EpochRange {
+                    start: Epoch::new(10),
+                    end: Epoch::new(40)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialization_roundtrip_and_canonical() {
+        let list = sample();
+        let bytes = list.to_bytes();
+        assert_eq!(RevocationList::from_bytes(&bytes).unwrap(), list);
+        assert!(RevocationList::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        // Fingerprints out of order, or ranges that should have been merged
+        let mut unsorted = RevocationList::new(1);
+        unsorted.revoke_seed(1).unwrap();
+        unsorted.revoke_seed(2).unwrap();
+        let mut bytes = unsorted.to_bytes();
+        bytes.swap(HEADER_LEN, HEADER_LEN + 8);
+        assert!(RevocationList::from_bytes(&bytes).is_err());
+
+        let mut bad_magic = list.to_bytes();
+        bad_magic[0] ^= 1;
+        assert!(RevocationList::from_bytes(&bad_magic).is_err());
+    }
+
+    #[test]
+    fn test_commitment_binds_entries_and_sequence() {
+        let list = sample();
+        let root = list.merkle_root();
+
+        let mut more = list.clone();
+        more.revoke_epoch(Epoch::new(9000)).unwrap();
+        assert_ne!(more.merkle_root(), root);
+
+        let mut newer = RevocationList::new(8);
+        newer
+            .revoke_epochs(Epoch::new(5000), Epoch::new(5010))
+            .unwrap();
+        newer.revoke_epoch(Epoch::new(42)).unwrap();
+        newer.revoke_seed(seed_fingerprint(b"leaked-seed")).unwrap();
+        assert_ne!(newer.merkle_root(), root);
+        assert_ne!(
+            RevocationList::new(0).merkle_root(),
+            RevocationList::new(1).merkle_root()
+        );
+    }
+}
@@ -0,0 +1,412 @@
+//! EpochScheduler - background epoch rollover with listener hooks (std)
+//!
+//! A background thread polls an injected [`Clock`] and drives a shared
+//! [`MTDManager`]: shortly before an epoch ends it pre-derives the next
+//! epoch's params into the manager's cache, and at rollover it advances the
+//! manager and notifies listeners, handing them the new params so provers and
+//! verifiers can re-key without touching the manager themselves.
+//!
+//! The manager is advanced under its lock in a single step; listeners run
+//! after the lock is released, so they may lock the manager without
+//! deadlocking. The scheduler drives the manager regardless of its
+//! `auto_advance` flag.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::mtd::clock::Clock;
+use crate::mtd::{Epoch, MTDManager, WarpingParams};
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, RwLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use alloc::vec::Vec;
+
+/// Epoch lifecycle callbacks. All methods default to no-ops.
+pub trait EpochListener: Send + Sync {
+    /// A new epoch became current; `params` are its params.
+    fn on_epoch_start(&self, _epoch: Epoch, _params: &WarpingParams) {}
+
+    /// `epoch` stopped being current.
+    fn on_epoch_end(&self, _epoch: Epoch) {}
+
+    /// The clock moved backwards, or skipped over whole epochs. `observed` is
+    /// the clock's epoch, `current` the manager's. A clock that stays behind
+    /// is reported once, and again only if it falls further back.
+    fn on_clock_skew(&self, _current: Epoch, _observed: Epoch) {}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SchedulerConfig {
+    /// How often the background thread reads the clock.
+    pub poll_interval: Duration,
+    /// How long before rollover the next epoch's params are pre-derived.
+    pub prefetch_lead_secs: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(1),
+            prefetch_lead_secs: 60,
+        }
+    }
+}
+
+/// What a single [`EpochScheduler::tick`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickOutcome {
+    Idle,
+    Prefetched(Epoch),
+    Advanced { from: Epoch, to: Epoch },
+    ClockSkew { current: Epoch, observed: Epoch },
+}
+
+type Listeners = RwLock<Vec<Arc<dyn EpochListener>>>;
+
+struct Shared {
+    manager: Arc<Mutex<MTDManager>>,
+    clock: Box<dyn Clock + Send + Sync>,
+    listeners: Listeners,
+    config: SchedulerConfig,
+    prefetched: Mutex<Option<Epoch>>,
+    /// Earliest epoch reported while the clock is behind; cleared once it
+    /// catches up.
+    behind: Mutex<Option<Epoch>>,
+    stop: (Mutex<bool>, Condvar),
+}
+
+pub struct EpochScheduler {
+    shared: Arc<Shared>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl core::fmt::Debug for EpochScheduler {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EpochScheduler")
+            .field("config", &self.shared.config)
+            .field("running", &self.is_running())
+            .finish()
+    }
+}
+
+impl EpochScheduler {
+    pub fn new<C: Clock + Send + Sync + 'static>(
+        manager: Arc<Mutex<MTDManager>>,
+        clock: C,
+    ) -> Self {
+        Self::with_config(manager, clock, SchedulerConfig::default())
+    }
+
+    pub fn with_config<C: Clock + Send + Sync + 'static>(
+        manager: Arc<Mutex<MTDManager>>,
+        clock: C,
+        config: SchedulerConfig,
+    ) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                manager,
+                clock: Box::new(clock),
+                listeners: RwLock::new(Vec::new()),
+                config,
+                prefetched: Mutex::new(None),
+                behind: Mutex::new(None),
+                stop: (Mutex::new(false), Condvar::new()),
+            }),
+            handle: None,
+        }
+    }
+
+    pub fn add_listener(&self, listener: Arc<dyn EpochListener>) {
+        self.shared
+            .listeners
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(listener);
+    }
+
+    pub fn manager(&self) -> &Arc<Mutex<MTDManager>> {
+        &self.shared.manager
+    }
+
+    /// Epoch whose params were pre-derived and are waiting in the cache.
+    pub fn prefetched_epoch(&self) -> Option<Epoch> {
+        *lock(&self.shared.prefetched)
+    }
+
+    /// Run one scheduling step synchronously (what the background thread
+    /// does every `poll_interval`).
+    pub fn tick(&self) -> Result<TickOutcome> {
+        self.shared.tick()
+    }
+
+    /// Spawn the background thread. Errors are retried on the next poll.
+    pub fn start(&mut self) -> Result<()> {
+        if self.handle.is_some() {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: "Epoch scheduler is already running".into(),
+            });
+        }
+        *lock(&self.shared.stop.0) = false;
+
+        let shared = Arc::clone(&self.shared);
+        let handle = std::thread::Builder::new()
+            .name("zkmtd-epoch-scheduler".into())
+            .spawn(move || loop {
+                let _ = shared.tick();
+                let (stopped, wake) = &shared.stop;
+                let guard = lock(stopped);
+                let (guard, _) = wake
+                    .wait_timeout_while(guard, shared.config.poll_interval, |stop| !*stop)
+                    .unwrap_or_else(|e| e.into_inner());
+                if *guard {
+                    break;
+                }
+            })
+            .map_err(|e| ZKMTDError::InternalError {
+                reason: alloc::format!("Failed to spawn epoch scheduler: {}", e),
+            })?;
+        self.handle = Some(handle);
+        Ok(())
+    }
+
+    /// Stop and join the background thread, if running.
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            *lock(&self.shared.stop.0) = true;
+            self.shared.stop.1.notify_all();
+            let _ = handle.join();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.handle.is_some()
+    }
+}
+
+impl Drop for EpochScheduler {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl Shared {
+    fn tick(&self) -> Result<TickOutcome> {
+        let now = self.clock.now_secs()?;
+        let observed = Epoch::from_timestamp(now);
+
+        let mut manager = lock(&self.manager);
+        let current = manager.current_epoch();
+
+        if observed < current {
+            drop(manager);
+            let mut behind = lock(&self.behind);
+            if behind.is_none_or(|reported| observed < reported) {
+                *behind = Some(observed);
+                drop(behind);
+                self.notify(|l| l.on_clock_skew(current, observed));
+            }
+            return Ok(TickOutcome::ClockSkew { current, observed });
+        }
+        *lock(&self.behind) = None;
+
+        if observed > current {
+            let params = manager.advance_to(observed)?.clone();
+            drop(manager);
+            *lock(&self.prefetched) = None;
+
+            if observed.value() - current.value() > 1 {
+                self.notify(|l| l.on_clock_skew(current, observed));
+            }
+            self.notify(|l| l.on_epoch_end(current));
+            self.notify(|l| l.on_epoch_start(observed, &params));
+            return Ok(TickOutcome::Advanced {
+                from: current,
+                to: observed,
+            });
+        }
+
+        // Same epoch: pre-derive the next one shortly before rollover.
+        let next = current.next()?;
+        let due = now + self.config.prefetch_lead_secs >= next.start_timestamp();
+        let mut prefetched = lock(&self.prefetched);
+        if due && *prefetched != Some(next) {
+            manager.prefetch(1)?;
+            *prefetched = Some(next);
+            return Ok(TickOutcome::Prefetched(next));
+        }
+        Ok(TickOutcome::Idle)
+    }
+
+    fn notify<F: Fn(&dyn EpochListener)>(&self, f: F) {
+        let listeners = self.listeners.read().unwrap_or_else(|e| e.into_inner());
+        for listener in listeners.iter() {
+            f(listener.as_ref());
+        }
+    }
+}
+
+/// Lock, recovering from poisoning: scheduler state stays consistent because
+/// every update is a single assignment.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtd::clock::ManualClock;
+    use crate::utils::constants::EPOCH_DURATION_SECS;
+    use std::sync::mpsc;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl EpochListener for Recorder {
+        fn on_epoch_start(&self, epoch: Epoch, params: &WarpingParams) {
+            assert_eq!(params.epoch, epoch);
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("start {}", epoch.value()));
+        }
+        fn on_epoch_end(&self, epoch: Epoch) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("end {}", epoch.value()));
+        }
+        fn on_clock_skew(&self, current: Epoch, observed: Epoch) {
+            self.events.lock().unwrap().push(format!(
+                "skew {} {}",
+                current.value(),
+                observed.value()
+            ));
+        }
+    }
+
+    fn setup(start_secs: u64) -> (EpochScheduler, ManualClock, Arc<Recorder>) {
+        let clock = ManualClock::new(start_secs);
+        let epoch = Epoch::from_timestamp(start_secs);
+        let manager = Arc::new(Mutex::new(
+            MTDManager::with_epoch(b"sched-seed", epoch).unwrap(),
+        ));
+        let scheduler = EpochScheduler::new(manager, clock.clone());
+        let recorder = Arc::new(Recorder::default());
+        scheduler.add_listener(recorder.clone());
+        (scheduler, clock, recorder)
+    }
+
+    #[test]
+    fn test_tick_prefetch_then_advance() {
+        let (scheduler, clock, recorder) = setup(100 * EPOCH_DURATION_SECS);
+        assert_eq!(scheduler.tick().unwrap(), TickOutcome::Idle);
+
+        // Within the prefetch lead of rollover
+        clock.set(101 * EPOCH_DURATION_SECS - 30);
+        assert_eq!(
+            scheduler.tick().unwrap(),
+            TickOutcome::Prefetched(Epoch::new(101))
+        );
+        assert_eq!(scheduler.prefetched_epoch(), Some(Epoch::new(101)));
+        assert_eq!(scheduler.tick().unwrap(), TickOutcome::Idle);
+
+        clock.set(101 * EPOCH_DURATION_SECS);
+        assert_eq!(
+            scheduler.tick().unwrap(),
+            TickOutcome::Advanced {
+                from: Epoch::new(100),
+                to: Epoch::new(101)
+            }
+        );
+        assert_eq!(scheduler.prefetched_epoch(), None);
+        assert_eq!(
+            scheduler.manager().lock().unwrap().current_params(),
+            &WarpingParams::generate(b"sched-seed", Epoch::new(101)).unwrap()
+        );
+        assert_eq!(*recorder.events.lock().unwrap(), ["end 100", "start 101"]);
+    }
+
+    #[test]
+    fn test_tick_clock_skew() {
+        let (scheduler, clock, recorder) = setup(100 * EPOCH_DURATION_SECS);
+
+        clock.set(99 * EPOCH_DURATION_SECS);
+        assert_eq!(
+            scheduler.tick().unwrap(),
+            TickOutcome::ClockSkew {
+                current: Epoch::new(100),
+                observed: Epoch::new(99)
+            }
+        );
+        assert_eq!(
+            scheduler.manager().lock().unwrap().current_epoch(),
+            Epoch::new(100)
+        );
+
+        // Still behind: reported once, again only when it falls further back
+        scheduler.tick().unwrap();
+        clock.set(99 * EPOCH_DURATION_SECS + 10);
+        scheduler.tick().unwrap();
+        clock.set(98 * EPOCH_DURATION_SECS);
+        scheduler.tick().unwrap();
+        scheduler.tick().unwrap();
+
+        // Caught up, then a new regression is reported afresh
+        clock.set(100 * EPOCH_DURATION_SECS);
+        assert_eq!(scheduler.tick().unwrap(), TickOutcome::Idle);
+        clock.set(99 * EPOCH_DURATION_SECS);
+        scheduler.tick().unwrap();
+
+        // Forward jump over several epochs: skew reported, then rollover
+        clock.set(104 * EPOCH_DURATION_SECS);
+        scheduler.tick().unwrap();
+        assert_eq!(
+            *recorder.events.lock().unwrap(),
+            [
+                "skew 100 99",
+                "skew 100 98",
+                "skew 100 99",
+                "skew 100 104",
+                "end 100",
+                "start 104"
+            ]
+        );
+    }
+
+    struct Notify(Mutex<mpsc::Sender<Epoch>>);
+
+    impl EpochListener for Notify {
+        fn on_epoch_start(&self, epoch: Epoch, _params: &WarpingParams) {
+            let _ = self.0.lock().unwrap().send(epoch);
+        }
+    }
+
+    #[test]
+    fn test_background_thread_advances() {
+        let clock = ManualClock::new(200 * EPOCH_DURATION_SECS);
+        let manager = Arc::new(Mutex::new(
+            MTDManager::with_epoch(b"sched-seed", Epoch::new(200)).unwrap(),
+        ));
+        let config = SchedulerConfig {
+            poll_interval: Duration::from_millis(5),
+            prefetch_lead_secs: 60,
+        };
+        let mut scheduler = EpochScheduler::with_config(manager.clone(), clock.clone(), config);
+        let (tx, rx) = mpsc::channel();
+        scheduler.add_listener(Arc::new(Notify(Mutex::new(tx))));
+
+        scheduler.start().unwrap();
+        assert!(scheduler.is_running());
+        assert!(scheduler.start().is_err());
+
+        clock.advance(EPOCH_DURATION_SECS);
+        let started = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(started, Epoch::new(201));
+        assert_eq!(manager.lock().unwrap().current_epoch(), Epoch::new(201));
+
+        scheduler.stop();
+        assert!(!scheduler.is_running());
+    }
+}
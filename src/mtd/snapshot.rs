@@ -0,0 +1,388 @@
+//! MTDManager snapshots - encrypted, authenticated, rollback-protected state
+//!
+//! Blob layout (version 1, little-endian):
+//! `magic(8) ∥ version(1) ∥ flags(1) ∥ epoch(8) ∥ nonce(16) ∥ seed_len(4) ∥
+//! encrypted_seed ∥ [tenant(33)] ∥ cache_len(2) ∥ cached_epochs(8 each) ∥ mac(32)`
+//!
+//! The tenant section is present only when the tenant flag is set.
+//!
+//! The seed is encrypted with a Poseidon2 keystream under a key derived from
+//! the caller's key-encryption key (KEK); the MAC, under a second derived key,
+//! covers every preceding byte (encrypt-then-MAC). Cached params are stored as
+//! epoch numbers only and re-derived on restore, so no derived secret leaves
+//! the process in the clear.
+//!
+//! Rollback protection relies on an [`EpochWatermark`]: snapshot and restore
+//! both raise it, and restore refuses any snapshot older than the highest
+//! epoch it has recorded.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+use crate::mtd::{Epoch, TenantContext};
+use crate::utils::constants::{
+    DOMAIN_SNAPSHOT_ENC, DOMAIN_SNAPSHOT_MAC, DOMAIN_SNAPSHOT_MAC_KEY, DOMAIN_SNAPSHOT_STREAM,
+    MAX_PARAM_CACHE_CAPACITY,
+};
+use crate::utils::hash::{constant_time_eq_fixed, poseidon_hash};
+
+use alloc::vec::Vec;
+use zeroize::{Zeroize, Zeroizing};
+
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"ZKMTDSNP";
+pub const SNAPSHOT_VERSION: u8 = 1;
+pub const SNAPSHOT_NONCE_BYTES: usize = 16;
+
+const FLAG_AUTO_ADVANCE: u8 = 0x01;
+const FLAG_TENANT: u8 = 0x02;
+const TENANT_BYTES: usize = 33;
+/// magic ∥ version ∥ flags ∥ epoch ∥ nonce ∥ seed_len
+const HEADER_BYTES: usize = 8 + 1 + 1 + 8 + SNAPSHOT_NONCE_BYTES + 4;
+const MAC_BYTES: usize = 32;
+
+/// Highest epoch ever snapshotted or restored. Implementations must make
+/// `persist_watermark` durable before returning `Ok`.
+pub trait EpochWatermark {
+    fn load_watermark(&self) -> Result<Option<Epoch>>;
+    fn persist_watermark(&mut self, epoch: Epoch) -> Result<()>;
+}
+
+/// Volatile watermark for tests and single-process use.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryWatermark {
+    epoch: Option<Epoch>,
+}
+
+impl MemoryWatermark {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl EpochWatermark for MemoryWatermark {
+    fn load_watermark(&self) -> Result<Option<Epoch>> {
+        Ok(self.epoch)
+    }
+
+    fn persist_watermark(&mut self, epoch: Epoch) -> Result<()> {
+        self.epoch = Some(epoch);
+        Ok(())
+    }
+}
+
+/// File-backed watermark. Writes go to a temporary file that is synced and
+/// then renamed over the target, so a crash leaves either the old or new value.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct FileWatermark {
+    path: std::path::PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl FileWatermark {
+    pub fn new<P: Into<std::path::PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[cfg(feature = "std")]
+impl EpochWatermark for FileWatermark {
+    fn load_watermark(&self) -> Result<Option<Epoch>> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => {
+                let bytes: [u8; 8] =
+                    bytes
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| ZKMTDError::SerializationError {
+                            reason: alloc::format!(
+                                "Corrupt epoch watermark ({} bytes)",
+                                bytes.len()
+                            ),
+                        })?;
+                Ok(Some(Epoch::from_bytes(bytes)?))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ZKMTDError::InternalError {
+                reason: alloc::format!("Epoch watermark read failed: {}", e),
+            }),
+        }
+    }
+
+    fn persist_watermark(&mut self, epoch: Epoch) -> Result<()> {
+        use std::io::Write;
+
+        let io_err = |e: std::io::Error| ZKMTDError::InternalError {
+            reason: alloc::format!("Epoch watermark write failed: {}", e),
+        };
+        let tmp = self.path.with_extension("tmp");
+        let mut file = std::fs::File::create(&tmp).map_err(io_err)?;
+        file.write_all(&epoch.to_bytes()).map_err(io_err)?;
+        file.sync_all().map_err(io_err)?;
+        std::fs::rename(&tmp, &self.path).map_err(io_err)
+    }
+}
+
+/// Decoded snapshot contents.
+pub(crate) struct SnapshotContents {
+    pub seed: Zeroizing<Vec<u8>>,
+    pub epoch: Epoch,
+    pub auto_advance: bool,
+    pub tenant: Option<TenantContext>,
+    pub cached_epochs: Vec<Epoch>,
+}
+
+impl SnapshotContents {
+    pub fn seal(&self, kek: &[u8; 32], nonce: &[u8; SNAPSHOT_NONCE_BYTES]) -> Vec<u8> {
+        let mut blob = Vec::with_capacity(
+            HEADER_BYTES + self.seed.len() + 2 + self.cached_epochs.len() * 8 + MAC_BYTES,
+        );
+        blob.extend_from_slice(SNAPSHOT_MAGIC);
+        blob.push(SNAPSHOT_VERSION);
+        let mut flags = 0;
+        if self.auto_advance {
+            flags |= FLAG_AUTO_ADVANCE;
+        }
+        if self.tenant.is_some() {
+            flags |= FLAG_TENANT;
+        }
+        blob.push(flags);
+        blob.extend_from_slice(&self.epoch.to_bytes());
+        blob.extend_from_slice(nonce);
+        blob.extend_from_slice(&(self.seed.len() as u32).to_le_bytes());
+
+        let start = blob.len();
+        blob.extend_from_slice(&self.seed);
+        apply_keystream(kek, nonce, &mut blob[start..]);
+        if let Some(tenant) = &self.tenant {
+            blob.extend_from_slice(&tenant.to_bytes());
+        }
+
+        blob.extend_from_slice(&(self.cached_epochs.len() as u16).to_le_bytes());
+        for epoch in &self.cached_epochs {
+            blob.extend_from_slice(&epoch.to_bytes());
+        }
+
+        let mac = compute_mac(kek, &blob);
+        blob.extend_from_slice(&mac);
+        blob
+    }
+
+    pub fn open(blob: &[u8], kek: &[u8; 32]) -> Result<Self> {
+        if blob.len() < HEADER_BYTES + 2 + MAC_BYTES {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Snapshot too short: {} bytes", blob.len()),
+            });
+        }
+        if &blob[..8] != SNAPSHOT_MAGIC {
+            return Err(ZKMTDError::SerializationError {
+                reason: "Not an MTD snapshot".into(),
+            });
+        }
+        if blob[8] != SNAPSHOT_VERSION {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Unsupported snapshot version {}", blob[8]),
+            });
+        }
+
+        // Authenticate before interpreting anything else.
+        let (body, mac) = blob.split_at(blob.len() - MAC_BYTES);
+        let mut expected = [0u8; MAC_BYTES];
+        expected.copy_from_slice(mac);
+        if !constant_time_eq_fixed(&compute_mac(kek, body), &expected) {
+            return Err(ZKMTDError::VerificationFailed {
+                reason: "Snapshot MAC mismatch (wrong key or tampered blob)".into(),
+            });
+        }
+
+        let read_u64 = |at: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&body[at..at + 8]);
+            buf
+        };
+        let flags = body[9];
+        let epoch = Epoch::from_bytes(read_u64(10))?;
+        let mut nonce = [0u8; SNAPSHOT_NONCE_BYTES];
+        nonce.copy_from_slice(&body[18..18 + SNAPSHOT_NONCE_BYTES]);
+        let mut len = [0u8; 4];
+        len.copy_from_slice(&body[HEADER_BYTES - 4..HEADER_BYTES]);
+        let seed_len = u32::from_le_bytes(len) as usize;
+
+        let seed_end = HEADER_BYTES.checked_add(seed_len);
+        let tenant_len = if flags & FLAG_TENANT != 0 {
+            TENANT_BYTES
+        } else {
+            0
+        };
+        let cache_at = seed_end
+            .map(|end| end + tenant_len)
+            .filter(|&at| at + 2 <= body.len())
+            .ok_or_else(|| ZKMTDError::SerializationError {
+                reason: "Snapshot seed length exceeds blob".into(),
+            })?;
+        let cache_len = u16::from_le_bytes([body[cache_at], body[cache_at + 1]]) as usize;
+        if cache_len > MAX_PARAM_CACHE_CAPACITY || body.len() != cache_at + 2 + cache_len * 8 {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Invalid snapshot cache section ({} entries)", cache_len),
+            });
+        }
+        if seed_len == 0 {
+            return Err(ZKMTDError::MTDError {
+                reason: "Seed is empty".into(),
+            });
+        }
+
+        let seed_end = cache_at - tenant_len;
+        let tenant = match tenant_len {
+            0 => None,
+            _ => Some(TenantContext::from_bytes(&body[seed_end..cache_at])?),
+        };
+        let mut seed = Zeroizing::new(body[HEADER_BYTES..seed_end].to_vec());
+        apply_keystream(kek, &nonce, &mut seed);
+
+        let cached_epochs = body[cache_at + 2..]
+            .chunks_exact(8)
+            .map(|c| {
+                let mut buf = [0u8; 8];
+                buf.copy_from_slice(c);
+                Epoch::from_bytes(buf)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            seed,
+            epoch,
+            auto_advance: flags & FLAG_AUTO_ADVANCE != 0,
+            tenant,
+            cached_epochs,
+        })
+    }
+}
+
+fn derive_key(kek: &[u8; 32], domain: &[u8]) -> Zeroizing<HashDigest> {
+    Zeroizing::new(poseidon_hash(kek, domain))
+}
+
+/// XOR `data` with the Poseidon2 keystream for `(kek, nonce)`.
+fn apply_keystream(kek: &[u8; 32], nonce: &[u8; SNAPSHOT_NONCE_BYTES], data: &mut [u8]) {
+    let key = derive_key(kek, DOMAIN_SNAPSHOT_ENC);
+    let mut block_input = Zeroizing::new([0u8; 32 + SNAPSHOT_NONCE_BYTES + 8]);
+    block_input[..32].copy_from_slice(key.as_ref());
+    block_input[32..32 + SNAPSHOT_NONCE_BYTES].copy_from_slice(nonce);
+    for (i, chunk) in data.chunks_mut(32).enumerate() {
+        block_input[32 + SNAPSHOT_NONCE_BYTES..].copy_from_slice(&(i as u64).to_le_bytes());
+        let mut stream = poseidon_hash(block_input.as_ref(), DOMAIN_SNAPSHOT_STREAM);
+        for (byte, k) in chunk.iter_mut().zip(stream.iter()) {
+            *byte ^= k;
+        }
+        stream.zeroize();
+    }
+}
+
+fn compute_mac(kek: &[u8; 32], body: &[u8]) -> HashDigest {
+    let key = derive_key(kek, DOMAIN_SNAPSHOT_MAC_KEY);
+    let mut input = Zeroizing::new(Vec::with_capacity(32 + body.len()));
+    input.extend_from_slice(key.as_ref());
+    input.extend_from_slice(body);
+    poseidon_hash(&input, DOMAIN_SNAPSHOT_MAC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEK: [u8; 32] = [7u8; 32];
+    const NONCE: [u8; SNAPSHOT_NONCE_BYTES] = [9u8; SNAPSHOT_NONCE_BYTES];
+
+    fn contents() -> SnapshotContents {
+        SnapshotContents {
+            seed: Zeroizing::new(b"snapshot-seed".to_vec()),
+            epoch: Epoch::new(500),
+            auto_advance: true,
+            tenant: None,
+            cached_epochs: alloc::vec![Epoch::new(498), Epoch::new(499)],
+        }
+    }
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let blob = contents().seal(&KEK, &NONCE);
+        let opened = SnapshotContents::open(&blob, &KEK).unwrap();
+        assert_eq!(opened.seed.as_slice(), b"snapshot-seed");
+        assert_eq!(opened.epoch, Epoch::new(500));
+        assert!(opened.auto_advance);
+        assert_eq!(
+            opened.cached_epochs,
+            alloc::vec![Epoch::new(498), Epoch::new(499)]
+        );
+    }
+
+    #[test]
+    fn test_seal_open_with_tenant() {
+        use crate::mtd::{ApplicationDomain, TenantId};
+
+        let tenant = TenantContext::new(TenantId::from_name(b"acme"), ApplicationDomain::Medical);
+        let mut with_tenant = contents();
+        with_tenant.tenant = Some(tenant);
+        let opened = SnapshotContents::open(&with_tenant.seal(&KEK, &NONCE), &KEK).unwrap();
+        assert_eq!(opened.tenant, Some(tenant));
+        assert_eq!(opened.seed.as_slice(), b"snapshot-seed");
+        assert_eq!(opened.cached_epochs.len(), 2);
+    }
+
+    #[test]
+    fn test_seed_not_in_clear() {
+        let blob = contents().seal(&KEK, &NONCE);
+        assert!(!blob.windows(13).any(|w| w == b"snapshot-seed"));
+    }
+
+    #[test]
+    fn test_wrong_kek_and_tampering_rejected() {
+        let blob = contents().seal(&KEK, &NONCE);
+        assert!(matches!(
+            SnapshotContents::open(&blob, &[8u8; 32]),
+            Err(ZKMTDError::VerificationFailed { .. })
+        ));
+
+        for i in [9, 10, 20, HEADER_BYTES, blob.len() - 1] {
+            let mut tampered = blob.clone();
+            tampered[i] ^= 0x01;
+            assert!(
+                SnapshotContents::open(&tampered, &KEK).is_err(),
+                "byte {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_malformed_blobs() {
+        let blob = contents().seal(&KEK, &NONCE);
+        assert!(SnapshotContents::open(&blob[..20], &KEK).is_err());
+
+        let mut wrong_magic = blob.clone();
+        wrong_magic[0] = b'X';
+        assert!(SnapshotContents::open(&wrong_magic, &KEK).is_err());
+
+        let mut wrong_version = blob;
+        wrong_version[8] = 2;
+        assert!(SnapshotContents::open(&wrong_version, &KEK).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_watermark_roundtrip() {
+        let path = std::env::temp_dir().join(alloc::format!(
+            "zkmtd-watermark-{}.state",
+            std::process::id()
+        ));
+        let mut store = FileWatermark::new(&path);
+        assert_eq!(store.load_watermark().unwrap(), None);
+
+        store.persist_watermark(Epoch::new(77)).unwrap();
+        assert_eq!(
+            FileWatermark::new(&path).load_watermark().unwrap(),
+            Some(Epoch::new(77))
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,308 @@
+//! Multi-tenant MTD - per-tenant, per-application parameter domains
+//!
+//! A [`TenantContext`] (tenant id + application domain) replaces the global
+//! `SYSTEM_SALT` in parameter derivation and is bound into the integrated
+//! proof binding hash. Tenants served from one master seed therefore get
+//! unrelated epoch params, and a proof made for one tenant never verifies
+//! for another.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+#[cfg(feature = "alloc")]
+use crate::mtd::{Epoch, MTDManager, WarpingParams};
+use crate::utils::constants::{
+    DOMAIN_BIOMETRIC, DOMAIN_COMMUNICATION, DOMAIN_CREDENTIAL, DOMAIN_FINANCIAL, DOMAIN_IDENTITY,
+    DOMAIN_LOCATION, DOMAIN_MEDICAL, DOMAIN_TENANT_ID, DOMAIN_TENANT_SALT, SYSTEM_SALT,
+};
+use crate::utils::hash::poseidon_hash;
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, vec::Vec};
+
+/// Opaque 32-byte tenant identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TenantId(pub [u8; 32]);
+
+impl TenantId {
+    /// Derive an id from a human-readable tenant name.
+    pub fn from_name(name: &[u8]) -> Self {
+        Self(poseidon_hash(name, DOMAIN_TENANT_ID))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// Application (privacy) domain a proof belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ApplicationDomain {
+    Identity,
+    Financial,
+    Medical,
+    Location,
+    Biometric,
+    Credential,
+    Communication,
+}
+
+impl ApplicationDomain {
+    pub fn tag(&self) -> &'static [u8] {
+        match self {
+            Self::Identity => DOMAIN_IDENTITY,
+            Self::Financial => DOMAIN_FINANCIAL,
+            Self::Medical => DOMAIN_MEDICAL,
+            Self::Location => DOMAIN_LOCATION,
+            Self::Biometric => DOMAIN_BIOMETRIC,
+            Self::Credential => DOMAIN_CREDENTIAL,
+            Self::Communication => DOMAIN_COMMUNICATION,
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Self::Identity => 0,
+            Self::Financial => 1,
+            Self::Medical => 2,
+            Self::Location => 3,
+            Self::Biometric => 4,
+            Self::Credential => 5,
+            Self::Communication => 6,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => Self::Identity,
+            1 => Self::Financial,
+            2 => Self::Medical,
+            3 => Self::Location,
+            4 => Self::Biometric,
+            5 => Self::Credential,
+            6 => Self::Communication,
+            _ => {
+                return Err(ZKMTDError::SerializationError {
+                    reason: "Unknown application domain".into(),
+                })
+            }
+        })
+    }
+}
+
+/// Tenant + application pair that scopes all MTD params.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TenantContext {
+    pub tenant: TenantId,
+    pub domain: ApplicationDomain,
+}
+
+impl TenantContext {
+    pub fn new(tenant: TenantId, domain: ApplicationDomain) -> Self {
+        Self { tenant, domain }
+    }
+
+    /// Salt replacing `SYSTEM_SALT` in `derive_mtd_params`.
+    pub fn salt(&self) -> HashDigest {
+        // SYSTEM_SALT ∥ tenant ∥ domain tag (tags are at most 32 bytes)
+        let mut data = [0u8; 96];
+        let mut len = 0;
+        for part in [SYSTEM_SALT, &self.tenant.0, self.domain.tag()] {
+            data[len..len + part.len()].copy_from_slice(part);
+            len += part.len();
+        }
+        poseidon_hash(&data[..len], DOMAIN_TENANT_SALT)
+    }
+
+    /// `tenant ∥ domain` (33 bytes), as bound into hashes and snapshots.
+    pub fn to_bytes(&self) -> [u8; 33] {
+        let mut bytes = [0u8; 33];
+        bytes[..32].copy_from_slice(&self.tenant.0);
+        bytes[32] = self.domain.as_u8();
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 33 {
+            return Err(ZKMTDError::SerializationError {
+                reason: "Invalid tenant context length (expected: 33)".into(),
+            });
+        }
+        let mut tenant = [0u8; 32];
+        tenant.copy_from_slice(&bytes[..32]);
+        Ok(Self {
+            tenant: TenantId(tenant),
+            domain: ApplicationDomain::from_u8(bytes[32])?,
+        })
+    }
+}
+
+/// Per-tenant epochs and caches over one master seed.
+#[cfg(feature = "alloc")]
+pub struct MultiTenantMTDManager {
+    seed: zeroize::Zeroizing<Vec<u8>>,
+    tenants: BTreeMap<TenantContext, MTDManager>,
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for MultiTenantMTDManager {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MultiTenantMTDManager")
+            .field("seed", &"<redacted>")
+            .field("tenants", &self.tenants.len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl MultiTenantMTDManager {
+    pub fn new(master_seed: &[u8]) -> Result<Self> {
+        if master_seed.is_empty() {
+            return Err(ZKMTDError::MTDError {
+                reason: "Seed is empty".into(),
+            });
+        }
+        Ok(Self {
+            seed: zeroize::Zeroizing::new(master_seed.to_vec()),
+            tenants: BTreeMap::new(),
+        })
+    }
+
+    /// Start managing `tenant` at `epoch`. Re-registering is an error, so a
+    /// tenant's epoch cannot be silently reset.
+    pub fn register(&mut self, tenant: TenantContext, epoch: Epoch) -> Result<&mut MTDManager> {
+        if self.tenants.contains_key(&tenant) {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: "Tenant is already registered".into(),
+            });
+        }
+        let manager = MTDManager::with_epoch_for_tenant(&self.seed, epoch, tenant)?;
+        Ok(self.tenants.entry(tenant).or_insert(manager))
+    }
+
+    pub fn remove(&mut self, tenant: &TenantContext) -> Option<MTDManager> {
+        self.tenants.remove(tenant)
+    }
+
+    pub fn manager(&self, tenant: &TenantContext) -> Result<&MTDManager> {
+        self.tenants.get(tenant).ok_or_else(unknown_tenant)
+    }
+
+    pub fn manager_mut(&mut self, tenant: &TenantContext) -> Result<&mut MTDManager> {
+        self.tenants.get_mut(tenant).ok_or_else(unknown_tenant)
+    }
+
+    pub fn current_epoch(&self, tenant: &TenantContext) -> Result<Epoch> {
+        Ok(self.manager(tenant)?.current_epoch())
+    }
+
+    pub fn current_params(&self, tenant: &TenantContext) -> Result<&WarpingParams> {
+        Ok(self.manager(tenant)?.current_params())
+    }
+
+    pub fn get_params(&mut self, tenant: &TenantContext, epoch: Epoch) -> Result<WarpingParams> {
+        self.manager_mut(tenant)?.get_params(epoch)
+    }
+
+    pub fn advance(&mut self, tenant: &TenantContext) -> Result<WarpingParams> {
+        Ok(self.manager_mut(tenant)?.advance()?.clone())
+    }
+
+    pub fn tenants(&self) -> impl Iterator<Item = &TenantContext> {
+        self.tenants.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tenants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tenants.is_empty()
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn unknown_tenant() -> ZKMTDError {
+    ZKMTDError::ConfigurationError {
+        reason: "Tenant is not registered".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(name: &[u8], domain: ApplicationDomain) -> TenantContext {
+        TenantContext::new(TenantId::from_name(name), domain)
+    }
+
+    #[test]
+    fn test_tenant_context_serialization() {
+        let context = ctx(b"acme", ApplicationDomain::Credential);
+        assert_eq!(
+            TenantContext::from_bytes(&context.to_bytes()).unwrap(),
+            context
+        );
+        assert!(TenantContext::from_bytes(&[0u8; 32]).is_err());
+
+        let mut bad_domain = context.to_bytes();
+        bad_domain[32] = 7;
+        assert!(TenantContext::from_bytes(&bad_domain).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_tenant_salts_distinct() {
+        let domains = [
+            ApplicationDomain::Identity,
+            ApplicationDomain::Financial,
+            ApplicationDomain::Medical,
+            ApplicationDomain::Location,
+            ApplicationDomain::Biometric,
+            ApplicationDomain::Credential,
+            ApplicationDomain::Communication,
+        ];
+        let mut salts = Vec::new();
+        for name in [b"tenant-a".as_slice(), b"tenant-b"] {
+            for domain in domains {
+                assert_eq!(ApplicationDomain::from_u8(domain.as_u8()).unwrap(), domain);
+                salts.push(ctx(name, domain).salt());
+            }
+        }
+        for i in 0..salts.len() {
+            for j in (i + 1)..salts.len() {
+                assert_ne!(salts[i], salts[j]);
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_multi_tenant_manager_independent_epochs() {
+        let a = ctx(b"tenant-a", ApplicationDomain::Financial);
+        let b = ctx(b"tenant-b", ApplicationDomain::Financial);
+
+        let mut mtm = MultiTenantMTDManager::new(b"master-seed").unwrap();
+        mtm.register(a, Epoch::new(100)).unwrap();
+        mtm.register(b, Epoch::new(200)).unwrap();
+        assert_eq!(mtm.len(), 2);
+        assert!(mtm.register(a, Epoch::new(5)).is_err());
+
+        mtm.advance(&a).unwrap();
+        assert_eq!(mtm.current_epoch(&a).unwrap(), Epoch::new(101));
+        assert_eq!(mtm.current_epoch(&b).unwrap(), Epoch::new(200));
+
+        let params_a = mtm.get_params(&a, Epoch::new(150)).unwrap();
+        let params_b = mtm.get_params(&b, Epoch::new(150)).unwrap();
+        assert_ne!(params_a, params_b);
+        assert_eq!(
+            params_a,
+            WarpingParams::generate_for_tenant(b"master-seed", Epoch::new(150), &a).unwrap()
+        );
+
+        let unknown = ctx(b"tenant-c", ApplicationDomain::Financial);
+        assert!(mtm.current_epoch(&unknown).is_err());
+        assert!(mtm.remove(&a).is_some());
+        assert!(mtm.current_params(&a).is_err());
+        assert!(MultiTenantMTDManager::new(b"").is_err());
+    }
+}
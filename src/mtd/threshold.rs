@@ -0,0 +1,885 @@
+//! Threshold (t-of-n) sharing of the MTD seed over the Goldilocks field
+//!
+//! The seed is packed into field lanes (7 bytes each) and dealt as replicated
+//! additive pieces: one random piece `r_T` per unqualified set `T` of `t-1`
+//! holders, with `Σ_T r_T = seed`. Holder `i` receives every piece with
+//! `i ∉ T`, plus its Shamir share `f(i) = Σ_T r_T·Z_T(i)` obtained by local
+//! share conversion (`Z_T` vanishes on `T` and is 1 at 0), so any `t` shares
+//! interpolate the seed for disaster recovery.
+//!
+//! Epoch keys never require the seed: every piece doubles as a PRF key and the
+//! epoch key is `Σ_T PRF(r_T, epoch)`. Any `t` holders jointly cover every
+//! `T`, any `t-1` holders miss the piece for their own set, and a partial only
+//! reveals PRF outputs, never the pieces themselves.
+//!
+//! The epoch key is not itself the seed's params, which are a hash of the
+//! seed. For every epoch of a fixed range the dealer therefore also publishes
+//! the seed's base params (`derive_mtd_params`) masked with a pad derived
+//! from that epoch's key, plus a check value committing to the key. A quorum
+//! unmasks the base params and expands them as `MTDManager` does, so its
+//! [`WarpingParams`] match every seed-based prover and verifier, while anyone
+//! short of a quorum only sees the masked values. Epochs past the dealt range
+//! need a new dealing.
+//!
+//! Partial epoch keys are PRF outputs and cannot be checked one at a time. A
+//! wrong contribution changes the combined key, which then fails the dealt
+//! check and is rejected. The cheat is detected, not attributed: the combiner
+//! retries with other quorums to single out the holder.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::traits::EntropySource;
+use crate::core::types::HashDigest;
+use crate::mtd::{Epoch, WarpingParams};
+use crate::utils::constants::{
+    DOMAIN_THRESHOLD_CHECK, DOMAIN_THRESHOLD_COMMIT, DOMAIN_THRESHOLD_PAD, DOMAIN_THRESHOLD_PRF,
+    MAX_THRESHOLD_EPOCHS, MAX_THRESHOLD_PIECES, MAX_THRESHOLD_SHARES, SYSTEM_SALT,
+};
+use crate::utils::hash::{
+    bytes_to_fields, constant_time_eq_fixed, derive_mtd_params, field_to_bytes, poseidon_hash,
+};
+
+use alloc::vec::Vec;
+
+use p3_field::{Field, PrimeCharacteristicRing, PrimeField64};
+use p3_goldilocks::Goldilocks;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+type F = Goldilocks;
+
+/// One replicated additive piece `r_T`, identified by the bitmask of `T`
+/// (bit `j-1` set ⇔ holder `j ∈ T`).
+#[derive(Clone)]
+pub struct SeedPiece {
+    pub subset: u32,
+    lanes: Vec<u64>,
+}
+
+impl Zeroize for SeedPiece {
+    fn zeroize(&mut self) {
+        self.lanes.zeroize();
+    }
+}
+
+impl Drop for SeedPiece {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl core::fmt::Debug for SeedPiece {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SeedPiece")
+            .field("subset", &self.subset)
+            .field("lanes", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Public dealing data for one epoch (see the module docs).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochMask {
+    /// Commitment to the epoch key, checked on every recombination.
+    pub check: HashDigest,
+    /// The seed's base params for the epoch, XORed with a pad from the key.
+    pub masked_params: HashDigest,
+}
+
+/// Public dealing transcript. Holders check their share against it and the
+/// combiner uses it to check that a quorum covers every piece and to unmask
+/// the epoch's params.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareCommitments {
+    pub threshold: u32,
+    pub total: u32,
+    pub seed_len: u32,
+    /// `(subset, Poseidon2(subset ∥ r_T))` for every piece, sorted by subset.
+    pub piece_commitments: Vec<(u32, HashDigest)>,
+    /// First epoch of the dealt range.
+    pub first_epoch: Epoch,
+    /// One entry per epoch from `first_epoch` on.
+    pub epoch_masks: Vec<EpochMask>,
+}
+
+impl ShareCommitments {
+    /// Single digest over the whole transcript, suitable for publishing.
+    pub fn digest(&self) -> HashDigest {
+        let mut data = Vec::with_capacity(12 + self.piece_commitments.len() * 36);
+        data.extend_from_slice(&self.threshold.to_le_bytes());
+        data.extend_from_slice(&self.total.to_le_bytes());
+        data.extend_from_slice(&self.seed_len.to_le_bytes());
+        for (subset, commitment) in &self.piece_commitments {
+            data.extend_from_slice(&subset.to_le_bytes());
+            data.extend_from_slice(commitment);
+        }
+        data.extend_from_slice(&self.first_epoch.to_bytes());
+        for mask in &self.epoch_masks {
+            data.extend_from_slice(&mask.check);
+            data.extend_from_slice(&mask.masked_params);
+        }
+        poseidon_hash(&data, DOMAIN_THRESHOLD_COMMIT)
+    }
+
+    fn epoch_mask(&self, epoch: Epoch) -> Option<&EpochMask> {
+        let offset = epoch.value().checked_sub(self.first_epoch.value())?;
+        self.epoch_masks.get(usize::try_from(offset).ok()?)
+    }
+
+    fn commitment_for(&self, subset: u32) -> Option<&HashDigest> {
+        self.piece_commitments
+            .binary_search_by_key(&subset, |(s, _)| *s)
+            .ok()
+            .map(|i| &self.piece_commitments[i].1)
+    }
+}
+
+/// A holder's share of the seed - auto-cleared on drop via zeroize
+#[derive(Clone)]
+pub struct SeedShare {
+    pub index: u32,
+    pub threshold: u32,
+    pub total: u32,
+    pub seed_len: u32,
+    shamir: Vec<u64>,
+    pieces: Vec<SeedPiece>,
+}
+
+impl core::fmt::Debug for SeedShare {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SeedShare")
+            .field("index", &self.index)
+            .field("threshold", &self.threshold)
+            .field("total", &self.total)
+            .field("pieces", &self.pieces.len())
+            .field("shamir", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Zeroize for SeedShare {
+    fn zeroize(&mut self) {
+        self.shamir.zeroize();
+        for piece in self.pieces.iter_mut() {
+            piece.zeroize();
+        }
+    }
+}
+
+impl ZeroizeOnDrop for SeedShare {}
+
+impl Drop for SeedShare {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl SeedShare {
+    /// Shamir evaluation `f(index)` per lane.
+    pub fn shamir_lanes(&self) -> &[u64] {
+        &self.shamir
+    }
+
+    pub fn pieces(&self) -> &[SeedPiece] {
+        &self.pieces
+    }
+
+    /// Check this share against the public dealing: parameters match, every
+    /// expected piece is present and opens its commitment, and the Shamir
+    /// share is the local conversion of those pieces.
+    pub fn verify(&self, commitments: &ShareCommitments) -> bool {
+        if self.threshold != commitments.threshold
+            || self.total != commitments.total
+            || self.seed_len != commitments.seed_len
+        {
+            return false;
+        }
+        let Some(bit) = holder_bit(self.index, self.total) else {
+            return false;
+        };
+        let expected = commitments
+            .piece_commitments
+            .iter()
+            .filter(|(subset, _)| subset & bit == 0)
+            .count();
+        if self.pieces.len() != expected {
+            return false;
+        }
+
+        let lanes = lane_count(self.seed_len as usize);
+        for piece in &self.pieces {
+            if piece.subset & bit != 0 || piece.lanes.len() != lanes {
+                return false;
+            }
+            match commitments.commitment_for(piece.subset) {
+                Some(c) if constant_time_eq_fixed(c, &commit_piece(piece)) => {}
+                _ => return false,
+            }
+        }
+
+        let recomputed = shamir_from_pieces(self.index, self.total, &self.pieces, lanes);
+        self.shamir.len() == lanes && recomputed.iter().eq(self.shamir.iter())
+    }
+
+    /// Compute this holder's contribution to the epoch key for `quorum`
+    /// (the holder indices taking part, including this one).
+    ///
+    /// Each piece is contributed by exactly one quorum member: the lowest
+    /// index outside its subset. Only PRF outputs leave the holder.
+    pub fn partial_epoch_key(&self, epoch: Epoch, quorum: &[u32]) -> Result<PartialEpochKey> {
+        let bit = holder_bit(self.index, self.total).ok_or_else(|| ZKMTDError::MTDError {
+            reason: alloc::format!("Invalid share index {}", self.index),
+        })?;
+        let quorum_mask = quorum_mask(quorum, self.total)?;
+        if quorum_mask & bit == 0 {
+            return Err(ZKMTDError::MTDError {
+                reason: alloc::format!("Share {} is not part of the quorum", self.index),
+            });
+        }
+
+        let contributions = self
+            .pieces
+            .iter()
+            .filter(|piece| assigned_contributor(piece.subset, quorum_mask) == Some(self.index))
+            .map(|piece| (piece.subset, piece_prf(piece, epoch)))
+            .collect();
+
+        Ok(PartialEpochKey {
+            index: self.index,
+            epoch,
+            contributions,
+        })
+    }
+}
+
+/// One holder's PRF outputs for an epoch. Safe to send to the combiner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialEpochKey {
+    pub index: u32,
+    pub epoch: Epoch,
+    pub contributions: Vec<(u32, HashDigest)>,
+}
+
+/// An epoch's base params, unmasked by a quorum. Auto-cleared on drop via
+/// zeroize.
+pub struct ThresholdEpochKey {
+    epoch: Epoch,
+    base_params: HashDigest,
+}
+
+impl core::fmt::Debug for ThresholdEpochKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ThresholdEpochKey")
+            .field("epoch", &self.epoch)
+            .field("base_params", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Drop for ThresholdEpochKey {
+    fn drop(&mut self) {
+        self.base_params.zeroize();
+    }
+}
+
+impl ThresholdEpochKey {
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// The params `MTDManager` derives from the dealt seed for this epoch.
+    pub fn warping_params(&self) -> WarpingParams {
+        WarpingParams::from_base_params(self.epoch, &self.base_params)
+    }
+
+    pub fn fri_seed(&self) -> HashDigest {
+        self.warping_params().fri_seed
+    }
+}
+
+/// Split `seed` into `total` shares, any `threshold` of which can derive the
+/// params of the `epoch_count` epochs from `first_epoch` on, or recover the
+/// seed.
+pub fn split_seed<E: EntropySource>(
+    seed: &[u8],
+    threshold: u32,
+    total: u32,
+    first_epoch: Epoch,
+    epoch_count: u64,
+    entropy: &mut E,
+) -> Result<(Vec<SeedShare>, ShareCommitments)> {
+    if seed.is_empty() {
+        return Err(ZKMTDError::MTDError {
+            reason: "Seed is empty".into(),
+        });
+    }
+    if threshold < 2 || threshold > total || total as usize > MAX_THRESHOLD_SHARES {
+        return Err(ZKMTDError::ConfigurationError {
+            reason: alloc::format!(
+                "Invalid threshold {}-of-{} (need 2 <= t <= n <= {})",
+                threshold,
+                total,
+                MAX_THRESHOLD_SHARES
+            ),
+        });
+    }
+    let last_epoch = epoch_count
+        .checked_sub(1)
+        .filter(|_| epoch_count <= MAX_THRESHOLD_EPOCHS)
+        .and_then(|n| first_epoch.value().checked_add(n))
+        .ok_or_else(|| ZKMTDError::ConfigurationError {
+            reason: alloc::format!(
+                "Invalid dealt epoch count {} (need 1..={})",
+                epoch_count,
+                MAX_THRESHOLD_EPOCHS
+            ),
+        })?;
+    Epoch::try_new(last_epoch)?;
+    if !entropy.is_cryptographically_secure() {
+        return Err(ZKMTDError::EntropyError {
+            reason: "Entropy source is not cryptographically secure".into(),
+        });
+    }
+
+    let subsets = subsets_of_size(total, threshold - 1);
+    if subsets.len() > MAX_THRESHOLD_PIECES {
+        return Err(ZKMTDError::ResourceLimitExceeded {
+            reason: alloc::format!(
+                "{}-of-{} sharing needs {} pieces (max {})",
+                threshold,
+                total,
+                subsets.len(),
+                MAX_THRESHOLD_PIECES
+            ),
+        });
+    }
+
+    let secret = Zeroizing::new(bytes_to_fields(seed));
+    let lanes = secret.len();
+
+    // Random pieces for all but the last subset; the last one absorbs the
+    // difference so the pieces sum to the seed.
+    let mut pieces = Vec::with_capacity(subsets.len());
+    let mut remainder = secret.clone();
+    for (n, &subset) in subsets.iter().enumerate() {
+        let lanes_vec = if n + 1 == subsets.len() {
+            remainder.to_vec()
+        } else {
+            let mut v = Vec::with_capacity(lanes);
+            for lane in 0..lanes {
+                let r = random_field(entropy)?;
+                remainder[lane] = (F::from_u64(remainder[lane]) - r).as_canonical_u64();
+                v.push(r.as_canonical_u64());
+            }
+            v
+        };
+        pieces.push(SeedPiece {
+            subset,
+            lanes: lanes_vec,
+        });
+    }
+
+    let epoch_masks = (first_epoch.value()..=last_epoch)
+        .map(|epoch| {
+            let epoch = Epoch::new(epoch);
+            let mut acc = Zeroizing::new([0u64; 4]);
+            for piece in &pieces {
+                accumulate(&mut acc, &piece_prf(piece, epoch));
+            }
+            let key = key_bytes(&acc);
+            let mut masked_params = derive_mtd_params(seed, epoch.value(), SYSTEM_SALT)?;
+            xor_into(&mut masked_params, &epoch_pad(&key));
+            Ok(EpochMask {
+                check: epoch_check(&key),
+                masked_params,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let commitments = ShareCommitments {
+        threshold,
+        total,
+        seed_len: seed.len() as u32,
+        piece_commitments: pieces.iter().map(|p| (p.subset, commit_piece(p))).collect(),
+        first_epoch,
+        epoch_masks,
+    };
+
+    let shares = (1..=total)
+        .map(|index| {
+            let bit = 1u32 << (index - 1);
+            let held: Vec<SeedPiece> = pieces
+                .iter()
+                .filter(|p| p.subset & bit == 0)
+                .cloned()
+                .collect();
+            let shamir = shamir_from_pieces(index, total, &held, lanes);
+            SeedShare {
+                index,
+                threshold,
+                total,
+                seed_len: seed.len() as u32,
+                shamir,
+                pieces: held,
+            }
+        })
+        .collect();
+
+    Ok((shares, commitments))
+}
+
+/// Combine one partial per quorum member into the epoch key and unmask the
+/// epoch's params. Fails if the partials disagree on the epoch, overlap,
+/// leave any piece uncovered, or combine to a key that fails the dealt check,
+/// and for epochs outside the dealt range.
+pub fn combine_epoch_key(
+    partials: &[PartialEpochKey],
+    commitments: &ShareCommitments,
+) -> Result<ThresholdEpochKey> {
+    let first = partials.first().ok_or(ZKMTDError::MTDError {
+        reason: "No partial epoch keys supplied".into(),
+    })?;
+    let epoch = first.epoch;
+    let mask = commitments
+        .epoch_mask(epoch)
+        .ok_or_else(|| ZKMTDError::InvalidEpoch {
+            current: epoch.value(),
+            reason: "Epoch is outside the dealt range".into(),
+        })?;
+
+    let indices: Vec<u32> = partials.iter().map(|p| p.index).collect();
+    let quorum = quorum_mask(&indices, commitments.total)?;
+    if (quorum.count_ones()) < commitments.threshold {
+        return Err(ZKMTDError::MTDError {
+            reason: alloc::format!(
+                "Quorum too small: {} < {}",
+                quorum.count_ones(),
+                commitments.threshold
+            ),
+        });
+    }
+
+    let mut seen = Vec::with_capacity(commitments.piece_commitments.len());
+    let mut acc = Zeroizing::new([0u64; 4]);
+    for partial in partials {
+        if partial.epoch != epoch {
+            return Err(ZKMTDError::InvalidEpoch {
+                current: epoch.value(),
+                reason: alloc::format!(
+                    "Partial from share {} is for {}",
+                    partial.index,
+                    partial.epoch
+                ),
+            });
+        }
+        for (subset, output) in &partial.contributions {
+            if commitments.commitment_for(*subset).is_none()
+                || assigned_contributor(*subset, quorum) != Some(partial.index)
+                || seen.contains(subset)
+            {
+                return Err(ZKMTDError::MTDError {
+                    reason: alloc::format!(
+                        "Unexpected contribution for piece {:#x} from share {}",
+                        subset,
+                        partial.index
+                    ),
+                });
+            }
+            seen.push(*subset);
+            accumulate(&mut acc, output);
+        }
+    }
+
+    if seen.len() != commitments.piece_commitments.len() {
+        return Err(ZKMTDError::MTDError {
+            reason: alloc::format!(
+                "Quorum covered {} of {} pieces",
+                seen.len(),
+                commitments.piece_commitments.len()
+            ),
+        });
+    }
+
+    let key = key_bytes(&acc);
+    if !constant_time_eq_fixed(&epoch_check(&key), &mask.check) {
+        return Err(ZKMTDError::MTDError {
+            reason: "Combined epoch key fails the dealt check".into(),
+        });
+    }
+
+    let mut unmasked = ThresholdEpochKey {
+        epoch,
+        base_params: mask.masked_params,
+    };
+    xor_into(&mut unmasked.base_params, &epoch_pad(&key));
+    Ok(unmasked)
+}
+
+/// Recover the seed from at least `threshold` verified shares (disaster
+/// recovery only - epoch derivation never needs this).
+pub fn reconstruct_seed(
+    shares: &[SeedShare],
+    commitments: &ShareCommitments,
+) -> Result<Zeroizing<Vec<u8>>> {
+    if shares.len() < commitments.threshold as usize {
+        return Err(ZKMTDError::MTDError {
+            reason: alloc::format!(
+                "Not enough shares: {} < {}",
+                shares.len(),
+                commitments.threshold
+            ),
+        });
+    }
+    let indices: Vec<u32> = shares.iter().map(|s| s.index).collect();
+    quorum_mask(&indices, commitments.total)?;
+    if let Some(bad) = shares.iter().find(|s| !s.verify(commitments)) {
+        return Err(ZKMTDError::MTDError {
+            reason: alloc::format!("Share {} failed verification", bad.index),
+        });
+    }
+
+    let lanes = lane_count(commitments.seed_len as usize);
+    let xs: Vec<F> = indices.iter().map(|&i| F::from_u32(i)).collect();
+    let mut secret = Zeroizing::new(alloc::vec![0u64; lanes]);
+    for (j, share) in shares.iter().enumerate() {
+        let lambda = lagrange_at_zero(&xs, j);
+        for (acc, &v) in secret.iter_mut().zip(share.shamir.iter()) {
+            *acc = (F::from_u64(*acc) + lambda * F::from_u64(v)).as_canonical_u64();
+        }
+    }
+
+    let mut seed = Zeroizing::new(Vec::with_capacity(lanes * 7));
+    for &value in secret.iter() {
+        if value >= 1 << 56 {
+            return Err(ZKMTDError::MTDError {
+                reason: "Reconstructed lane is out of range".into(),
+            });
+        }
+        seed.extend_from_slice(&field_to_bytes(value));
+    }
+    seed.truncate(commitments.seed_len as usize);
+    Ok(seed)
+}
+
+fn lane_count(seed_len: usize) -> usize {
+    seed_len.div_ceil(crate::utils::hash::BYTES_PER_FIELD)
+}
+
+/// All `k`-element subsets of `{1..=n}` as bitmasks, in increasing order.
+fn subsets_of_size(n: u32, k: u32) -> Vec<u32> {
+    (0u32..(1 << n)).filter(|m| m.count_ones() == k).collect()
+}
+
+/// Bitmask bit of holder `index`, if it is a valid index below `total`.
+fn holder_bit(index: u32, total: u32) -> Option<u32> {
+    (index >= 1 && index <= total).then(|| 1u32.checked_shl(index - 1))?
+}
+
+fn quorum_mask(indices: &[u32], total: u32) -> Result<u32> {
+    let mut mask = 0u32;
+    for &i in indices {
+        if i == 0 || i > total || mask & (1 << (i - 1)) != 0 {
+            return Err(ZKMTDError::MTDError {
+                reason: alloc::format!("Invalid or duplicate share index {}", i),
+            });
+        }
+        mask |= 1 << (i - 1);
+    }
+    Ok(mask)
+}
+
+/// The quorum member responsible for contributing piece `subset`: the lowest
+/// index that is in the quorum but not in the subset.
+fn assigned_contributor(subset: u32, quorum: u32) -> Option<u32> {
+    let holders = quorum & !subset;
+    (holders != 0).then(|| holders.trailing_zeros() + 1)
+}
+
+fn random_field<E: EntropySource>(entropy: &mut E) -> Result<F> {
+    // Rejection sampling keeps the distribution uniform over the field.
+    loop {
+        let mut buf = Zeroizing::new([0u8; 8]);
+        entropy.fill_bytes(buf.as_mut())?;
+        let v = u64::from_le_bytes(*buf);
+        if v < F::ORDER_U64 {
+            return Ok(F::from_u64(v));
+        }
+    }
+}
+
+fn piece_bytes(piece: &SeedPiece) -> Zeroizing<Vec<u8>> {
+    let mut data = Zeroizing::new(Vec::with_capacity(4 + piece.lanes.len() * 8));
+    data.extend_from_slice(&piece.subset.to_le_bytes());
+    for lane in &piece.lanes {
+        data.extend_from_slice(&lane.to_le_bytes());
+    }
+    data
+}
+
+fn commit_piece(piece: &SeedPiece) -> HashDigest {
+    poseidon_hash(&piece_bytes(piece), DOMAIN_THRESHOLD_COMMIT)
+}
+
+fn piece_prf(piece: &SeedPiece, epoch: Epoch) -> HashDigest {
+    let mut data = piece_bytes(piece);
+    data.extend_from_slice(&epoch.to_bytes());
+    poseidon_hash(&data, DOMAIN_THRESHOLD_PRF)
+}
+
+/// Add one PRF output into the epoch key, lane by lane over the field.
+fn accumulate(acc: &mut [u64; 4], output: &HashDigest) {
+    for (lane, chunk) in acc.iter_mut().zip(output.chunks(8)) {
+        let mut limb = Zeroizing::new([0u8; 8]);
+        limb.copy_from_slice(chunk);
+        *lane = (F::from_u64(*lane) + F::from_u64(u64::from_le_bytes(*limb))).as_canonical_u64();
+    }
+}
+
+fn key_bytes(acc: &[u64; 4]) -> Zeroizing<HashDigest> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    for (i, lane) in acc.iter().enumerate() {
+        key[i * 8..(i + 1) * 8].copy_from_slice(&lane.to_le_bytes());
+    }
+    key
+}
+
+fn epoch_check(key: &HashDigest) -> HashDigest {
+    poseidon_hash(key, DOMAIN_THRESHOLD_CHECK)
+}
+
+fn epoch_pad(key: &HashDigest) -> Zeroizing<HashDigest> {
+    Zeroizing::new(poseidon_hash(key, DOMAIN_THRESHOLD_PAD))
+}
+
+fn xor_into(target: &mut HashDigest, pad: &HashDigest) {
+    for (t, p) in target.iter_mut().zip(pad.iter()) {
+        *t ^= p;
+    }
+}
+
+/// `Z_T(x) = Π_{j∈T} (j - x) / j`: degree `|T|`, zero on `T`, one at 0.
+fn vanishing_at(subset: u32, x: u32, total: u32) -> F {
+    let mut acc = F::ONE;
+    for j in 1..=total {
+        if subset & (1 << (j - 1)) != 0 {
+            let fj = F::from_u32(j);
+            acc *= (fj - F::from_u32(x)) * fj.inverse();
+        }
+    }
+    acc
+}
+
+fn shamir_from_pieces(index: u32, total: u32, pieces: &[SeedPiece], lanes: usize) -> Vec<u64> {
+    let mut acc = alloc::vec![0u64; lanes];
+    for piece in pieces {
+        let z = vanishing_at(piece.subset, index, total);
+        for (a, &v) in acc.iter_mut().zip(piece.lanes.iter()) {
+            *a = (F::from_u64(*a) + z * F::from_u64(v)).as_canonical_u64();
+        }
+    }
+    acc
+}
+
+fn lagrange_at_zero(xs: &[F], j: usize) -> F {
+    let mut acc = F::ONE;
+    for (m, &xm) in xs.iter().enumerate() {
+        if m != j {
+            acc *= xm * (xm - xs[j]).inverse();
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtd::entropy::{DeterministicEntropy, TrustedEntropy};
+
+    fn deal(seed: &[u8], t: u32, n: u32) -> (Vec<SeedShare>, ShareCommitments) {
+        let mut entropy = TrustedEntropy(DeterministicEntropy::new(7));
+        split_seed(seed, t, n, Epoch::new(90), 20, &mut entropy).unwrap()
+    }
+
+    fn epoch_key(
+        shares: &[&SeedShare],
+        commitments: &ShareCommitments,
+        epoch: Epoch,
+    ) -> Result<ThresholdEpochKey> {
+        let quorum: Vec<u32> = shares.iter().map(|s| s.index).collect();
+        let partials: Vec<_> = shares
+            .iter()
+            .map(|s| s.partial_epoch_key(epoch, &quorum).unwrap())
+            .collect();
+        combine_epoch_key(&partials, commitments)
+    }
+
+    #[test]
+    fn test_split_and_verify_shares() {
+        let (shares, commitments) = deal(b"threshold-test-seed", 3, 5);
+        assert_eq!(shares.len(), 5);
+        for share in &shares {
+            assert!(share.verify(&commitments), "share {} failed", share.index);
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_any_quorum() {
+        let seed = b"threshold-test-seed-0123456789";
+        let (shares, commitments) = deal(seed, 3, 5);
+
+        for quorum in [[0usize, 1, 2], [0, 2, 4], [1, 3, 4]] {
+            let subset: Vec<SeedShare> = quorum.iter().map(|&i| shares[i].clone()).collect();
+            let recovered = reconstruct_seed(&subset, &commitments).unwrap();
+            assert_eq!(recovered.as_slice(), seed);
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_too_few_shares() {
+        let (shares, commitments) = deal(b"threshold-test-seed", 3, 5);
+        let result = reconstruct_seed(&shares[..2], &commitments);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_tampered_share_rejected() {
+        let (mut shares, commitments) = deal(b"threshold-test-seed", 2, 3);
+        shares[0].shamir[0] ^= 1;
+        assert!(!shares[0].verify(&commitments));
+        assert!(reconstruct_seed(&shares[..2], &commitments).is_err());
+    }
+
+    #[test]
+    fn test_epoch_key_independent_of_quorum() {
+        let (shares, commitments) = deal(b"threshold-test-seed", 3, 5);
+        let epoch = Epoch::new(100);
+
+        let k1 = epoch_key(&[&shares[0], &shares[1], &shares[2]], &commitments, epoch).unwrap();
+        let k2 = epoch_key(&[&shares[4], &shares[1], &shares[3]], &commitments, epoch).unwrap();
+        let k3 = epoch_key(&shares.iter().collect::<Vec<_>>(), &commitments, epoch).unwrap();
+
+        assert_eq!(k1.warping_params(), k2.warping_params());
+        assert_eq!(k1.fri_seed(), k3.fri_seed());
+    }
+
+    #[test]
+    fn test_epoch_key_changes_per_epoch() {
+        let (shares, commitments) = deal(b"threshold-test-seed", 2, 3);
+        let k100 = epoch_key(&[&shares[0], &shares[1]], &commitments, Epoch::new(100)).unwrap();
+        let k101 = epoch_key(&[&shares[0], &shares[1]], &commitments, Epoch::new(101)).unwrap();
+        assert_ne!(k100.fri_seed(), k101.fri_seed());
+        assert_eq!(k101.warping_params().epoch, Epoch::new(101));
+    }
+
+    #[test]
+    fn test_epoch_key_matches_seed_params() {
+        use crate::mtd::MTDManager;
+
+        let seed = b"threshold-test-seed";
+        let (shares, commitments) = deal(seed, 2, 3);
+        let mut manager =
+            MTDManager::new(seed, &mut TrustedEntropy(DeterministicEntropy::new(3))).unwrap();
+
+        for epoch in [Epoch::new(90), Epoch::new(100), Epoch::new(109)] {
+            let key = epoch_key(&[&shares[2], &shares[0]], &commitments, epoch).unwrap();
+            assert_eq!(key.warping_params(), manager.get_params(epoch).unwrap());
+            assert_eq!(
+                &key.warping_params(),
+                MTDManager::with_epoch(seed, epoch)
+                    .unwrap()
+                    .current_params()
+            );
+        }
+    }
+
+    #[test]
+    fn test_epoch_outside_dealt_range_rejected() {
+        let (shares, commitments) = deal(b"threshold-test-seed", 2, 3);
+        for epoch in [Epoch::new(89), Epoch::new(110)] {
+            let result = epoch_key(&[&shares[0], &shares[1]], &commitments, epoch);
+            assert!(matches!(result, Err(ZKMTDError::InvalidEpoch { .. })));
+        }
+    }
+
+    #[test]
+    fn test_tampered_contribution_rejected() {
+        let (shares, commitments) = deal(b"threshold-test-seed", 2, 3);
+        let epoch = Epoch::new(100);
+        let quorum = [1, 2];
+        let mut partials = [
+            shares[0].partial_epoch_key(epoch, &quorum).unwrap(),
+            shares[1].partial_epoch_key(epoch, &quorum).unwrap(),
+        ];
+        partials[1].contributions[0].1[0] ^= 1;
+        assert!(combine_epoch_key(&partials, &commitments).is_err());
+    }
+
+    #[test]
+    fn test_partial_rejects_invalid_index() {
+        let (shares, _) = deal(b"threshold-test-seed", 2, 3);
+        for index in [0, 4, 40] {
+            let mut share = shares[0].clone();
+            share.index = index;
+            assert!(share.partial_epoch_key(Epoch::new(100), &[1, 2]).is_err());
+        }
+    }
+
+    #[cfg(feature = "full-p3")]
+    #[test]
+    fn test_seed_proof_verifies_with_combined_params() {
+        use crate::stark::integrated::{IntegratedProver, IntegratedVerifier};
+
+        let seed = b"threshold-test-seed";
+        let (shares, commitments) = deal(seed, 2, 3);
+        let epoch = Epoch::new(100);
+        let key = epoch_key(&[&shares[1], &shares[2]], &commitments, epoch).unwrap();
+
+        let prover = IntegratedProver::new(seed, epoch).unwrap();
+        let proof = prover.prove_fibonacci(8, [1u8; 32]).unwrap();
+        assert_eq!(proof.params, key.warping_params());
+
+        let verifier = IntegratedVerifier::from_params(key.warping_params()).unwrap();
+        assert!(verifier.verify(&proof).unwrap());
+    }
+
+    #[test]
+    fn test_sub_threshold_quorum_cannot_combine() {
+        let (shares, commitments) = deal(b"threshold-test-seed", 3, 5);
+        let result = epoch_key(&[&shares[0], &shares[1]], &commitments, Epoch::new(100));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_combine_rejects_mixed_epochs() {
+        let (shares, commitments) = deal(b"threshold-test-seed", 2, 3);
+        let quorum = [1, 2];
+        let partials = [
+            shares[0]
+                .partial_epoch_key(Epoch::new(100), &quorum)
+                .unwrap(),
+            shares[1]
+                .partial_epoch_key(Epoch::new(101), &quorum)
+                .unwrap(),
+        ];
+        assert!(combine_epoch_key(&partials, &commitments).is_err());
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let mut entropy = TrustedEntropy(DeterministicEntropy::new(1));
+        let first = Epoch::new(1);
+        assert!(split_seed(b"", 2, 3, first, 1, &mut entropy).is_err());
+        assert!(split_seed(b"seed", 1, 3, first, 1, &mut entropy).is_err());
+        assert!(split_seed(b"seed", 4, 3, first, 1, &mut entropy).is_err());
+        assert!(split_seed(b"seed", 2, 3, first, 0, &mut entropy).is_err());
+        assert!(split_seed(b"seed", 2, 3, first, MAX_THRESHOLD_EPOCHS + 1, &mut entropy).is_err());
+
+        let mut weak = DeterministicEntropy::new(1);
+        assert!(split_seed(b"seed", 2, 3, first, 1, &mut weak).is_err());
+    }
+
+    #[test]
+    fn test_share_debug_redacts_secrets() {
+        let (shares, _) = deal(b"threshold-test-seed", 2, 3);
+        let debug = alloc::format!("{:?}", shares[0]);
+        assert!(debug.contains("<redacted>"));
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::core::errors::{Result, ZKMTDError};
 use crate::core::types::HashDigest;
-use crate::mtd::Epoch;
+use crate::mtd::{Epoch, TenantContext};
 use crate::utils::constants::SYSTEM_SALT;
 use crate::utils::hash::{derive_mtd_params, poseidon_hash};
 
@@ -23,6 +23,16 @@ pub struct WarpingParams {
 
 impl WarpingParams {
     pub fn generate(seed: &[u8], epoch: Epoch) -> Result<Self> {
+        Self::derive(seed, epoch, SYSTEM_SALT)
+    }
+
+    /// Params for one tenant/application. Tenants sharing a master seed get
+    /// unrelated params, so their proofs never cross-verify.
+    pub fn generate_for_tenant(seed: &[u8], epoch: Epoch, tenant: &TenantContext) -> Result<Self> {
+        Self::derive(seed, epoch, &tenant.salt())
+    }
+
+    fn derive(seed: &[u8], epoch: Epoch, system_salt: &[u8]) -> Result<Self> {
         if seed.is_empty() {
             return Err(ZKMTDError::MTDError {
                 reason: "Seed is empty".into(),
@@ -30,8 +40,14 @@ impl WarpingParams {
         }
 
         // 1. Derive base parameters
-        let base_params = derive_mtd_params(seed, epoch.value(), SYSTEM_SALT)?;
+        let base_params = derive_mtd_params(seed, epoch.value(), system_salt)?;
+        Ok(Self::from_base_params(epoch, &base_params))
+    }
 
+    /// Expand an epoch's base parameters (`derive_mtd_params` output) into
+    /// the full params, for holders of the base parameters but not the seed
+    /// (see `mtd::threshold`).
+    pub(crate) fn from_base_params(epoch: Epoch, base_params: &HashDigest) -> Self {
         // 2. Generate domain separator
         // Domain_Sep = Hash(base_params ∥ "DOMAIN")
         #[cfg(feature = "alloc")]
@@ -41,12 +57,12 @@ impl WarpingParams {
 
         #[cfg(feature = "alloc")]
         {
-            domain_data.extend_from_slice(&base_params);
+            domain_data.extend_from_slice(base_params);
             domain_data.extend_from_slice(b"DOMAIN");
         }
         #[cfg(not(feature = "alloc"))]
         {
-            domain_data[..32].copy_from_slice(&base_params);
+            domain_data[..32].copy_from_slice(base_params);
             domain_data[32..38].copy_from_slice(b"DOMAIN");
         }
 
@@ -63,7 +79,7 @@ impl WarpingParams {
         #[cfg(feature = "alloc")]
         {
             domain_data.clear();
-            domain_data.extend_from_slice(&base_params);
+            domain_data.extend_from_slice(base_params);
             domain_data.extend_from_slice(b"SALT");
         }
         #[cfg(not(feature = "alloc"))]
@@ -84,7 +100,7 @@ impl WarpingParams {
         #[cfg(feature = "alloc")]
         {
             domain_data.clear();
-            domain_data.extend_from_slice(&base_params);
+            domain_data.extend_from_slice(base_params);
             domain_data.extend_from_slice(b"FRI");
         }
         #[cfg(not(feature = "alloc"))]
@@ -100,12 +116,12 @@ impl WarpingParams {
             crate::utils::constants::DOMAIN_MTD_FRI_SEED,
         );
 
-        Ok(Self {
+        Self {
             epoch,
             domain_separator,
             salt,
             fri_seed,
-        })
+        }
     }
 
     pub fn next(&self, seed: &[u8]) -> Result<Self> {
@@ -259,6 +275,38 @@ mod tests {
         assert!(!params.verify(b"wrong-seed").unwrap());
     }
 
+    #[test]
+    fn test_warping_params_per_tenant() {
+        use crate::mtd::{ApplicationDomain, TenantId};
+
+        let seed = b"test-seed";
+        let epoch = Epoch::new(100);
+        let a = TenantContext::new(
+            TenantId::from_name(b"tenant-a"),
+            ApplicationDomain::Financial,
+        );
+        let b = TenantContext::new(
+            TenantId::from_name(b"tenant-b"),
+            ApplicationDomain::Financial,
+        );
+        let a_medical = TenantContext::new(a.tenant, ApplicationDomain::Medical);
+
+        let params_a = WarpingParams::generate_for_tenant(seed, epoch, &a).unwrap();
+        assert_eq!(
+            params_a,
+            WarpingParams::generate_for_tenant(seed, epoch, &a).unwrap()
+        );
+        assert_ne!(params_a, WarpingParams::generate(seed, epoch).unwrap());
+        assert_ne!(
+            params_a,
+            WarpingParams::generate_for_tenant(seed, epoch, &b).unwrap()
+        );
+        assert_ne!(
+            params_a,
+            WarpingParams::generate_for_tenant(seed, epoch, &a_medical).unwrap()
+        );
+    }
+
     #[test]
     fn test_warping_params_uniqueness() {
         let seed = b"test-seed";
@@ -0,0 +1,15 @@
+//! Hash-based post-quantum signatures - WOTS+ one-time keys under an XMSS tree
+//!
+//! Built only on `utils::hash::poseidon_hash`, in keeping with the library's
+//! hash-only security assumptions. XMSS is stateful: every signature consumes
+//! one leaf, and the signer persists its leaf counter through a
+//! [`SignerStateStore`] before releasing a signature.
+
+pub mod wots;
+pub mod xmss;
+
+#[cfg(feature = "std")]
+pub use xmss::FileStateStore;
+pub use xmss::{
+    BatchHeader, MemoryStateStore, SignerStateStore, XmssPublicKey, XmssSignature, XmssSigner,
+};
@@ -0,0 +1,190 @@
+//! WOTS+ one-time signatures (w = 16) over Poseidon2
+//!
+//! A 32-byte digest is split into 64 base-16 digits plus a 3-digit checksum;
+//! each of the 67 digits selects a position on its own hash chain. Every chain
+//! step is tweaked with the public seed and its (leaf, chain, step) address so
+//! that chains of different keys and positions never share inputs.
+
+use crate::core::types::HashDigest;
+use crate::utils::constants::{DOMAIN_WOTS_CHAIN, DOMAIN_WOTS_SECRET, DOMAIN_XMSS_LEAF};
+use crate::utils::hash::poseidon_hash;
+
+use alloc::vec::Vec;
+use zeroize::Zeroizing;
+
+/// Winternitz parameter.
+pub const WOTS_W: u8 = 16;
+/// Digits covering the 256-bit message digest.
+pub const WOTS_LEN1: usize = 64;
+/// Checksum digits (max checksum 64 * 15 = 960 < 16^3).
+pub const WOTS_LEN2: usize = 3;
+/// Total number of chains.
+pub const WOTS_LEN: usize = WOTS_LEN1 + WOTS_LEN2;
+
+/// Base-16 digits of `digest` followed by its checksum digits.
+pub fn message_digits(digest: &HashDigest) -> [u8; WOTS_LEN] {
+    let mut digits = [0u8; WOTS_LEN];
+    for (i, byte) in digest.iter().enumerate() {
+        digits[2 * i] = byte >> 4;
+        digits[2 * i + 1] = byte & 0x0F;
+    }
+
+    let checksum: u32 = digits[..WOTS_LEN1]
+        .iter()
+        .map(|&d| (WOTS_W - 1 - d) as u32)
+        .sum();
+    for i in 0..WOTS_LEN2 {
+        let shift = 4 * (WOTS_LEN2 - 1 - i);
+        digits[WOTS_LEN1 + i] = ((checksum >> shift) & 0x0F) as u8;
+    }
+    digits
+}
+
+/// Secret chain start for `chain` of one-time key `leaf`.
+fn secret_element(sk_seed: &HashDigest, leaf: u32, chain: usize) -> Zeroizing<HashDigest> {
+    let mut data = Zeroizing::new([0u8; 37]);
+    data[..32].copy_from_slice(sk_seed);
+    data[32..36].copy_from_slice(&leaf.to_le_bytes());
+    data[36] = chain as u8;
+    Zeroizing::new(poseidon_hash(data.as_ref(), DOMAIN_WOTS_SECRET))
+}
+
+/// Walk `steps` positions along a chain starting at position `start`.
+fn chain(
+    value: &HashDigest,
+    start: u8,
+    steps: u8,
+    pub_seed: &HashDigest,
+    leaf: u32,
+    chain: usize,
+) -> HashDigest {
+    let mut data = [0u8; 70];
+    data[..32].copy_from_slice(pub_seed);
+    data[32..36].copy_from_slice(&leaf.to_le_bytes());
+    data[36] = chain as u8;
+
+    let mut current = *value;
+    for step in start..start + steps {
+        data[37] = step;
+        data[38..].copy_from_slice(&current);
+        current = poseidon_hash(&data, DOMAIN_WOTS_CHAIN);
+    }
+    current
+}
+
+/// Compress the chain ends into the XMSS leaf for key `leaf`.
+fn compress_public_key(pub_seed: &HashDigest, leaf: u32, ends: &[HashDigest]) -> HashDigest {
+    let mut data = Vec::with_capacity(36 + ends.len() * 32);
+    data.extend_from_slice(pub_seed);
+    data.extend_from_slice(&leaf.to_le_bytes());
+    for end in ends {
+        data.extend_from_slice(end);
+    }
+    poseidon_hash(&data, DOMAIN_XMSS_LEAF)
+}
+
+/// Compressed public key (XMSS leaf) of one-time key `leaf`.
+pub fn public_leaf(sk_seed: &HashDigest, pub_seed: &HashDigest, leaf: u32) -> HashDigest {
+    let ends: Vec<HashDigest> = (0..WOTS_LEN)
+        .map(|i| {
+            chain(
+                &secret_element(sk_seed, leaf, i),
+                0,
+                WOTS_W - 1,
+                pub_seed,
+                leaf,
+                i,
+            )
+        })
+        .collect();
+    compress_public_key(pub_seed, leaf, &ends)
+}
+
+/// Sign `digest` with one-time key `leaf`.
+pub fn sign(
+    digest: &HashDigest,
+    sk_seed: &HashDigest,
+    pub_seed: &HashDigest,
+    leaf: u32,
+) -> Vec<HashDigest> {
+    message_digits(digest)
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| chain(&secret_element(sk_seed, leaf, i), 0, d, pub_seed, leaf, i))
+        .collect()
+}
+
+/// Recover the XMSS leaf a signature commits to. The result only matches the
+/// signer's leaf if `signature` was produced over `digest` by that key.
+pub fn leaf_from_signature(
+    digest: &HashDigest,
+    signature: &[HashDigest],
+    pub_seed: &HashDigest,
+    leaf: u32,
+) -> Option<HashDigest> {
+    if signature.len() != WOTS_LEN {
+        return None;
+    }
+    let ends: Vec<HashDigest> = message_digits(digest)
+        .iter()
+        .zip(signature.iter())
+        .enumerate()
+        .map(|(i, (&d, sig))| chain(sig, d, WOTS_W - 1 - d, pub_seed, leaf, i))
+        .collect();
+    Some(compress_public_key(pub_seed, leaf, &ends))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_digits_checksum() {
+        // All-zero digest: every digit 0, checksum 64 * 15 = 960 = 0x3C0
+        let digits = message_digits(&[0u8; 32]);
+        assert!(digits[..WOTS_LEN1].iter().all(|&d| d == 0));
+        assert_eq!(&digits[WOTS_LEN1..], &[0x3, 0xC, 0x0]);
+
+        // All-0xFF digest: checksum 0
+        let digits = message_digits(&[0xFF; 32]);
+        assert!(digits[..WOTS_LEN1].iter().all(|&d| d == 15));
+        assert_eq!(&digits[WOTS_LEN1..], &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_wots_sign_verify() {
+        let sk_seed = [1u8; 32];
+        let pub_seed = [2u8; 32];
+        let digest = poseidon_hash(b"message", b"test");
+
+        let leaf = public_leaf(&sk_seed, &pub_seed, 3);
+        let signature = sign(&digest, &sk_seed, &pub_seed, 3);
+        assert_eq!(
+            leaf_from_signature(&digest, &signature, &pub_seed, 3),
+            Some(leaf)
+        );
+    }
+
+    #[test]
+    fn test_wots_wrong_message_or_leaf() {
+        let sk_seed = [1u8; 32];
+        let pub_seed = [2u8; 32];
+        let digest = poseidon_hash(b"message", b"test");
+        let other = poseidon_hash(b"other message", b"test");
+
+        let leaf = public_leaf(&sk_seed, &pub_seed, 0);
+        let signature = sign(&digest, &sk_seed, &pub_seed, 0);
+        assert_ne!(
+            leaf_from_signature(&other, &signature, &pub_seed, 0),
+            Some(leaf)
+        );
+        assert_ne!(
+            leaf_from_signature(&digest, &signature, &pub_seed, 1),
+            Some(leaf)
+        );
+        assert_eq!(
+            leaf_from_signature(&digest, &signature[1..], &pub_seed, 0),
+            None
+        );
+    }
+}
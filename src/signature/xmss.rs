@@ -0,0 +1,733 @@
+//! XMSS - many-time signatures from a Merkle tree of WOTS+ one-time keys
+//!
+//! The tree is a [`MerkleTree`] over the compressed WOTS+ public keys, so the
+//! public key is its root (leaf count bound in, RT-2). Signing is stateful:
+//! the signer reserves a leaf through its [`SignerStateStore`] *before*
+//! computing the signature, so a crash can burn a leaf but never reuse one.
+
+use crate::batching::merkle::{MerkleArity, MerklePath, MerkleTree};
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::traits::EntropySource;
+use crate::core::types::{HashDigest, ProofBatch};
+use crate::mtd::{EpochAnnouncement, RevocationList, ScheduleCommitment};
+use crate::signature::wots::{self, WOTS_LEN};
+use crate::utils::constants::{
+    DOMAIN_XMSS_MESSAGE, DOMAIN_XMSS_RANDOMIZER, MAX_XMSS_HEIGHT, MIN_XMSS_HEIGHT,
+};
+use crate::utils::hash::{constant_time_eq_fixed, poseidon_hash};
+
+use alloc::vec::Vec;
+use zeroize::{Zeroize, Zeroizing};
+
+// Message tags: one per signed object type, so a signature over one kind of
+// object can never be presented as a signature over another.
+const TAG_BATCH_HEADER: u8 = 1;
+const TAG_ANNOUNCEMENT: u8 = 2;
+const TAG_SCHEDULE: u8 = 3;
+const TAG_REVOCATION: u8 = 4;
+const TAG_PRESENTATION: u8 = 5;
+
+/// Durable storage for the signer's next unused leaf index.
+pub trait SignerStateStore {
+    /// Record that every leaf below `next_index` is spent. Must not return
+    /// `Ok` until the value is durable; signing fails closed otherwise.
+    fn persist_next_index(&mut self, next_index: u64) -> Result<()>;
+
+    /// Last persisted value, or `None` if nothing was stored yet.
+    fn load_next_index(&self) -> Result<Option<u64>>;
+}
+
+/// Volatile store for tests and short-lived signers.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStateStore {
+    next_index: Option<u64>,
+}
+
+impl MemoryStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SignerStateStore for MemoryStateStore {
+    fn persist_next_index(&mut self, next_index: u64) -> Result<()> {
+        self.next_index = Some(next_index);
+        Ok(())
+    }
+
+    fn load_next_index(&self) -> Result<Option<u64>> {
+        Ok(self.next_index)
+    }
+}
+
+/// File-backed store. Writes go to a temporary file that is synced and then
+/// renamed over the target, so a crash leaves either the old or new value.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct FileStateStore {
+    path: std::path::PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl FileStateStore {
+    pub fn new<P: Into<std::path::PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[cfg(feature = "std")]
+impl SignerStateStore for FileStateStore {
+    fn persist_next_index(&mut self, next_index: u64) -> Result<()> {
+        use std::io::Write;
+
+        let io_err = |e: std::io::Error| ZKMTDError::InternalError {
+            reason: alloc::format!("Signer state write failed: {}", e),
+        };
+        let tmp = self.path.with_extension("tmp");
+        let mut file = std::fs::File::create(&tmp).map_err(io_err)?;
+        file.write_all(&next_index.to_le_bytes()).map_err(io_err)?;
+        file.sync_all().map_err(io_err)?;
+        std::fs::rename(&tmp, &self.path).map_err(io_err)
+    }
+
+    fn load_next_index(&self) -> Result<Option<u64>> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => {
+                let bytes: [u8; 8] =
+                    bytes
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| ZKMTDError::SerializationError {
+                            reason: alloc::format!("Corrupt signer state ({} bytes)", bytes.len()),
+                        })?;
+                Ok(Some(u64::from_le_bytes(bytes)))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ZKMTDError::InternalError {
+                reason: alloc::format!("Signer state read failed: {}", e),
+            }),
+        }
+    }
+}
+
+/// Header of a proof batch - what gets signed, and all an on-chain verifier
+/// needs to trust a batch's Merkle root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchHeader {
+    pub epoch: u64,
+    pub merkle_root: HashDigest,
+    pub proof_count: u32,
+}
+
+impl BatchHeader {
+    pub fn from_batch(batch: &ProofBatch) -> Self {
+        Self {
+            epoch: batch.epoch,
+            merkle_root: batch.merkle_root,
+            proof_count: batch.len() as u32,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 44] {
+        let mut bytes = [0u8; 44];
+        bytes[..8].copy_from_slice(&self.epoch.to_le_bytes());
+        bytes[8..40].copy_from_slice(&self.merkle_root);
+        bytes[40..].copy_from_slice(&self.proof_count.to_le_bytes());
+        bytes
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XmssPublicKey {
+    pub height: u8,
+    pub pub_seed: HashDigest,
+    pub root: HashDigest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmssSignature {
+    pub leaf_index: u32,
+    pub randomizer: HashDigest,
+    pub wots: Vec<HashDigest>,
+    pub auth_path: Vec<HashDigest>,
+}
+
+impl XmssPublicKey {
+    /// `2^height`, saturating for heights no key of ours can have.
+    pub fn max_signatures(&self) -> u64 {
+        1u64.checked_shl(self.height as u32).unwrap_or(u64::MAX)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 65] {
+        let mut bytes = [0u8; 65];
+        bytes[0] = self.height;
+        bytes[1..33].copy_from_slice(&self.pub_seed);
+        bytes[33..].copy_from_slice(&self.root);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 65 {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Invalid byte length: {} (expected: 65)", bytes.len()),
+            });
+        }
+        let height = bytes[0];
+        if !(MIN_XMSS_HEIGHT..=MAX_XMSS_HEIGHT).contains(&height) {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Unsupported XMSS height {}", height),
+            });
+        }
+        let mut pub_seed = [0u8; 32];
+        pub_seed.copy_from_slice(&bytes[1..33]);
+        let mut root = [0u8; 32];
+        root.copy_from_slice(&bytes[33..]);
+        Ok(Self {
+            height,
+            pub_seed,
+            root,
+        })
+    }
+
+    /// Verify a signature over raw `message` bytes.
+    pub fn verify(&self, message: &[u8], signature: &XmssSignature) -> bool {
+        // The key may be constructed directly, so re-check what `from_bytes`
+        // would have rejected
+        if !(MIN_XMSS_HEIGHT..=MAX_XMSS_HEIGHT).contains(&self.height)
+            || signature.leaf_index as u64 >= self.max_signatures()
+            || signature.auth_path.len() != self.height as usize
+            || signature.wots.len() != WOTS_LEN
+        {
+            return false;
+        }
+
+        let digest = message_digest(self, signature.leaf_index, &signature.randomizer, message);
+        let leaf = match wots::leaf_from_signature(
+            &digest,
+            &signature.wots,
+            &self.pub_seed,
+            signature.leaf_index,
+        ) {
+            Some(leaf) => leaf,
+            None => return false,
+        };
+
+        let path = MerklePath {
+            leaf_index: signature.leaf_index as usize,
+            siblings: signature.auth_path.clone(),
+            root: self.root,
+            num_leaves: self.max_signatures() as usize,
+            arity: MerkleArity::Binary,
+        };
+        path.verify_against(&leaf, &self.root)
+    }
+
+    pub fn verify_batch_header(&self, header: &BatchHeader, signature: &XmssSignature) -> bool {
+        self.verify(&tagged(TAG_BATCH_HEADER, &header.to_bytes()), signature)
+    }
+
+    /// Verify a signed batch root. Only the header fields are covered, so the
+    /// batch's own Merkle root must still be checked against its proofs.
+    pub fn verify_batch(&self, batch: &ProofBatch, signature: &XmssSignature) -> bool {
+        self.verify_batch_header(&BatchHeader::from_batch(batch), signature)
+    }
+
+    pub fn verify_announcement(
+        &self,
+        announcement: &EpochAnnouncement,
+        signature: &XmssSignature,
+    ) -> bool {
+        self.verify(
+            &tagged(TAG_ANNOUNCEMENT, &announcement.params.to_bytes()),
+            signature,
+        )
+    }
+
+    pub fn verify_schedule(
+        &self,
+        commitment: &ScheduleCommitment,
+        signature: &XmssSignature,
+    ) -> bool {
+        self.verify(&tagged(TAG_SCHEDULE, &commitment.to_bytes()), signature)
+    }
+
+    /// Verify a signed revocation list. The signature covers the list's
+    /// sequence number and Merkle root, so it authenticates every entry.
+    pub fn verify_revocation_list(&self, list: &RevocationList, signature: &XmssSignature) -> bool {
+        self.verify(
+            &tagged(TAG_REVOCATION, &list.commitment().to_bytes()),
+            signature,
+        )
+    }
+
+    /// Verify a holder's signature over a credential presentation binding
+    /// (see `stark::credential`).
+    pub fn verify_presentation(&self, binding: &HashDigest, signature: &XmssSignature) -> bool {
+        self.verify(&tagged(TAG_PRESENTATION, binding), signature)
+    }
+}
+
+impl XmssSignature {
+    /// `leaf_index ∥ randomizer ∥ wots[67] ∥ height ∥ auth_path[height]`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(37 + (self.wots.len() + self.auth_path.len()) * 32);
+        bytes.extend_from_slice(&self.leaf_index.to_le_bytes());
+        bytes.extend_from_slice(&self.randomizer);
+        for node in &self.wots {
+            bytes.extend_from_slice(node);
+        }
+        bytes.push(self.auth_path.len() as u8);
+        for node in &self.auth_path {
+            bytes.extend_from_slice(node);
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let wots_end = 36 + WOTS_LEN * 32;
+        if bytes.len() <= wots_end {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Signature too short: {} bytes", bytes.len()),
+            });
+        }
+        let height = bytes[wots_end] as usize;
+        if height > MAX_XMSS_HEIGHT as usize || bytes.len() != wots_end + 1 + height * 32 {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Invalid signature length for height {}", height),
+            });
+        }
+
+        let digests = |slice: &[u8]| -> Vec<HashDigest> {
+            slice
+                .chunks_exact(32)
+                .map(|c| {
+                    let mut d = [0u8; 32];
+                    d.copy_from_slice(c);
+                    d
+                })
+                .collect()
+        };
+        let mut leaf_index = [0u8; 4];
+        leaf_index.copy_from_slice(&bytes[..4]);
+        let mut randomizer = [0u8; 32];
+        randomizer.copy_from_slice(&bytes[4..36]);
+
+        Ok(Self {
+            leaf_index: u32::from_le_bytes(leaf_index),
+            randomizer,
+            wots: digests(&bytes[36..wots_end]),
+            auth_path: digests(&bytes[wots_end + 1..]),
+        })
+    }
+}
+
+/// Stateful XMSS signer. Holds the secret seed (zeroized on drop) and the
+/// cached tree of one-time public keys.
+pub struct XmssSigner {
+    sk_seed: HashDigest,
+    public_key: XmssPublicKey,
+    tree: MerkleTree,
+    next_index: u64,
+}
+
+impl core::fmt::Debug for XmssSigner {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("XmssSigner")
+            .field("sk_seed", &"<redacted>")
+            .field("public_key", &self.public_key)
+            .field("next_index", &self.next_index)
+            .finish()
+    }
+}
+
+impl Drop for XmssSigner {
+    fn drop(&mut self) {
+        self.sk_seed.zeroize();
+    }
+}
+
+impl XmssSigner {
+    /// Generate a key pair with `2^height` one-time keys. Key generation
+    /// computes every leaf, so cost grows linearly with `2^height`.
+    pub fn generate<E: EntropySource>(height: u8, entropy: &mut E) -> Result<Self> {
+        if !entropy.is_cryptographically_secure() {
+            return Err(ZKMTDError::EntropyError {
+                reason: "Entropy source is not cryptographically secure".into(),
+            });
+        }
+        let mut sk_seed = Zeroizing::new([0u8; 32]);
+        entropy.fill_bytes(sk_seed.as_mut())?;
+        let mut pub_seed = [0u8; 32];
+        entropy.fill_bytes(&mut pub_seed)?;
+
+        Self::from_seeds(&sk_seed, pub_seed, height, 0)
+    }
+
+    /// Rebuild a signer from its seeds, e.g. after loading them from a key
+    /// vault. Call [`sync_state`](Self::sync_state) before signing.
+    pub fn from_seeds(
+        sk_seed: &HashDigest,
+        pub_seed: HashDigest,
+        height: u8,
+        next_index: u64,
+    ) -> Result<Self> {
+        if !(MIN_XMSS_HEIGHT..=MAX_XMSS_HEIGHT).contains(&height) {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: alloc::format!(
+                    "XMSS height {} out of range ({}..={})",
+                    height,
+                    MIN_XMSS_HEIGHT,
+                    MAX_XMSS_HEIGHT
+                ),
+            });
+        }
+
+        let leaves = (0..1u32 << height)
+            .map(|i| wots::public_leaf(sk_seed, &pub_seed, i))
+            .collect();
+        let tree = MerkleTree::new(leaves)?;
+        let public_key = XmssPublicKey {
+            height,
+            pub_seed,
+            root: *tree.root(),
+        };
+
+        Ok(Self {
+            sk_seed: *sk_seed,
+            public_key,
+            tree,
+            next_index,
+        })
+    }
+
+    pub fn public_key(&self) -> &XmssPublicKey {
+        &self.public_key
+    }
+
+    pub fn next_index(&self) -> u64 {
+        self.next_index
+    }
+
+    pub fn remaining_signatures(&self) -> u64 {
+        self.public_key
+            .max_signatures()
+            .saturating_sub(self.next_index)
+    }
+
+    /// Advance to the store's persisted index if it is ahead of ours, so a
+    /// restored signer never reuses a leaf spent before a restart.
+    pub fn sync_state<S: SignerStateStore>(&mut self, store: &S) -> Result<()> {
+        if let Some(stored) = store.load_next_index()? {
+            self.next_index = self.next_index.max(stored);
+        }
+        Ok(())
+    }
+
+    /// Sign raw `message` bytes, spending one leaf.
+    pub fn sign<S: SignerStateStore>(
+        &mut self,
+        message: &[u8],
+        store: &mut S,
+    ) -> Result<XmssSignature> {
+        self.sync_state(store)?;
+        let index = self.next_index;
+        if index >= self.public_key.max_signatures() {
+            return Err(ZKMTDError::ResourceLimitExceeded {
+                reason: alloc::format!(
+                    "All {} XMSS one-time keys are spent",
+                    self.public_key.max_signatures()
+                ),
+            });
+        }
+
+        // Reserve the leaf durably before it is used.
+        store.persist_next_index(index + 1)?;
+        self.next_index = index + 1;
+
+        let leaf_index = index as u32;
+        let randomizer = self.randomizer(leaf_index, message);
+        let digest = message_digest(&self.public_key, leaf_index, &randomizer, message);
+        let auth_path = self.tree.get_proof(leaf_index as usize)?.siblings;
+
+        Ok(XmssSignature {
+            leaf_index,
+            randomizer,
+            wots: wots::sign(
+                &digest,
+                &self.sk_seed,
+                &self.public_key.pub_seed,
+                leaf_index,
+            ),
+            auth_path,
+        })
+    }
+
+    pub fn sign_batch_header<S: SignerStateStore>(
+        &mut self,
+        header: &BatchHeader,
+        store: &mut S,
+    ) -> Result<XmssSignature> {
+        self.sign(&tagged(TAG_BATCH_HEADER, &header.to_bytes()), store)
+    }
+
+    pub fn sign_batch<S: SignerStateStore>(
+        &mut self,
+        batch: &ProofBatch,
+        store: &mut S,
+    ) -> Result<XmssSignature> {
+        self.sign_batch_header(&BatchHeader::from_batch(batch), store)
+    }
+
+    pub fn sign_announcement<S: SignerStateStore>(
+        &mut self,
+        announcement: &EpochAnnouncement,
+        store: &mut S,
+    ) -> Result<XmssSignature> {
+        self.sign(
+            &tagged(TAG_ANNOUNCEMENT, &announcement.params.to_bytes()),
+            store,
+        )
+    }
+
+    pub fn sign_schedule<S: SignerStateStore>(
+        &mut self,
+        commitment: &ScheduleCommitment,
+        store: &mut S,
+    ) -> Result<XmssSignature> {
+        self.sign(&tagged(TAG_SCHEDULE, &commitment.to_bytes()), store)
+    }
+
+    pub fn sign_revocation_list<S: SignerStateStore>(
+        &mut self,
+        list: &RevocationList,
+        store: &mut S,
+    ) -> Result<XmssSignature> {
+        self.sign(
+            &tagged(TAG_REVOCATION, &list.commitment().to_bytes()),
+            store,
+        )
+    }
+
+    pub fn sign_presentation<S: SignerStateStore>(
+        &mut self,
+        binding: &HashDigest,
+        store: &mut S,
+    ) -> Result<XmssSignature> {
+        self.sign(&tagged(TAG_PRESENTATION, binding), store)
+    }
+
+    /// Deterministic per-signature randomizer, keyed by the secret seed.
+    fn randomizer(&self, leaf_index: u32, message: &[u8]) -> HashDigest {
+        let mut data = Zeroizing::new(Vec::with_capacity(36 + message.len()));
+        data.extend_from_slice(&self.sk_seed);
+        data.extend_from_slice(&leaf_index.to_le_bytes());
+        data.extend_from_slice(message);
+        poseidon_hash(&data, DOMAIN_XMSS_RANDOMIZER)
+    }
+}
+
+fn tagged(tag: u8, payload: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(1 + payload.len());
+    message.push(tag);
+    message.extend_from_slice(payload);
+    message
+}
+
+/// Randomized message digest bound to the key and the leaf being used.
+fn message_digest(
+    public_key: &XmssPublicKey,
+    leaf_index: u32,
+    randomizer: &HashDigest,
+    message: &[u8],
+) -> HashDigest {
+    let mut data = Vec::with_capacity(100 + message.len());
+    data.extend_from_slice(randomizer);
+    data.extend_from_slice(&public_key.pub_seed);
+    data.extend_from_slice(&public_key.root);
+    data.extend_from_slice(&leaf_index.to_le_bytes());
+    data.extend_from_slice(message);
+    poseidon_hash(&data, DOMAIN_XMSS_MESSAGE)
+}
+
+/// Constant-time public key comparison, e.g. against a pinned key.
+pub fn public_keys_match(a: &XmssPublicKey, b: &XmssPublicKey) -> bool {
+    a.height == b.height
+        && constant_time_eq_fixed(&a.pub_seed, &b.pub_seed)
+        && constant_time_eq_fixed(&a.root, &b.root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtd::{AnnouncementPublisher, Epoch};
+
+    fn test_signer() -> XmssSigner {
+        XmssSigner::from_seeds(&[7u8; 32], [9u8; 32], MIN_XMSS_HEIGHT, 0).unwrap()
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mut signer = test_signer();
+        let mut store = MemoryStateStore::new();
+
+        let sig = signer.sign(b"hello", &mut store).unwrap();
+        assert!(signer.public_key().verify(b"hello", &sig));
+        assert!(!signer.public_key().verify(b"hellO", &sig));
+    }
+
+    #[test]
+    fn test_leaves_are_never_reused() {
+        let mut signer = test_signer();
+        let mut store = MemoryStateStore::new();
+
+        let first = signer.sign(b"a", &mut store).unwrap();
+        let second = signer.sign(b"a", &mut store).unwrap();
+        assert_ne!(first.leaf_index, second.leaf_index);
+        assert_eq!(store.load_next_index().unwrap(), Some(2));
+    }
+
+    #[test]
+    fn test_exhausted_key_fails_closed() {
+        let mut signer = test_signer();
+        let mut store = MemoryStateStore::new();
+        for _ in 0..signer.public_key().max_signatures() {
+            signer.sign(b"msg", &mut store).unwrap();
+        }
+        assert_eq!(signer.remaining_signatures(), 0);
+        assert!(signer.sign(b"msg", &mut store).is_err());
+    }
+
+    #[test]
+    fn test_restored_signer_resumes_from_store() {
+        let mut store = MemoryStateStore::new();
+        let mut signer = test_signer();
+        signer.sign(b"before restart", &mut store).unwrap();
+        drop(signer);
+
+        // A restored signer starting at 0 must skip the spent leaf
+        let mut restored = test_signer();
+        let sig = restored.sign(b"after restart", &mut store).unwrap();
+        assert_eq!(sig.leaf_index, 1);
+    }
+
+    #[test]
+    fn test_batch_header_signature() {
+        let batch = ProofBatch::new(Vec::new(), [5u8; 32], 100);
+        let mut signer = test_signer();
+        let mut store = MemoryStateStore::new();
+
+        let sig = signer.sign_batch(&batch, &mut store).unwrap();
+        let pk = signer.public_key();
+        assert!(pk.verify_batch(&batch, &sig));
+
+        let mut forged = BatchHeader::from_batch(&batch);
+        forged.merkle_root[0] ^= 1;
+        assert!(!pk.verify_batch_header(&forged, &sig));
+    }
+
+    #[test]
+    fn test_announcement_signature_is_type_bound() {
+        let publisher = AnnouncementPublisher::new(b"seed", Epoch::new(10), 2).unwrap();
+        let announcement = publisher.announce(Epoch::new(10)).unwrap();
+        let commitment = publisher.commitment();
+
+        let mut signer = test_signer();
+        let mut store = MemoryStateStore::new();
+        let pk = *signer.public_key();
+
+        let ann_sig = signer.sign_announcement(&announcement, &mut store).unwrap();
+        let sched_sig = signer.sign_schedule(&commitment, &mut store).unwrap();
+        assert!(pk.verify_announcement(&announcement, &ann_sig));
+        assert!(pk.verify_schedule(&commitment, &sched_sig));
+
+        // Signatures do not transfer between message types
+        assert!(!pk.verify(&announcement.params.to_bytes(), &ann_sig));
+        assert!(!pk.verify_schedule(&commitment, &ann_sig));
+    }
+
+    #[test]
+    fn test_revocation_list_signature() {
+        let mut list = RevocationList::new(3);
+        list.revoke_epochs(Epoch::new(5000), Epoch::new(5010))
+            .unwrap();
+
+        let mut signer = test_signer();
+        let mut store = MemoryStateStore::new();
+        let sig = signer.sign_revocation_list(&list, &mut store).unwrap();
+        let pk = signer.public_key();
+        assert!(pk.verify_revocation_list(&list, &sig));
+
+        // Signed lists survive serialization; edited or replayed-as-newer do not
+        let decoded = RevocationList::from_bytes(&list.to_bytes()).unwrap();
+        assert!(pk.verify_revocation_list(&decoded, &sig));
+        let mut edited = list.clone();
+        edited.revoke_epoch(Epoch::new(1)).unwrap();
+        assert!(!pk.verify_revocation_list(&edited, &sig));
+        let mut renumbered = RevocationList::new(4);
+        renumbered
+            .revoke_epochs(Epoch::new(5000), Epoch::new(5010))
+            .unwrap();
+        assert!(!pk.verify_revocation_list(&renumbered, &sig));
+    }
+
+    #[test]
+    fn test_signature_serialization() {
+        let mut signer = test_signer();
+        let mut store = MemoryStateStore::new();
+        let sig = signer.sign(b"serialize me", &mut store).unwrap();
+
+        let decoded = XmssSignature::from_bytes(&sig.to_bytes()).unwrap();
+        assert_eq!(decoded, sig);
+        assert!(XmssSignature::from_bytes(&sig.to_bytes()[..100]).is_err());
+
+        let pk = signer.public_key();
+        let decoded_pk = XmssPublicKey::from_bytes(&pk.to_bytes()).unwrap();
+        assert!(public_keys_match(pk, &decoded_pk));
+    }
+
+    #[test]
+    fn test_verify_rejects_out_of_range_height() {
+        let mut signer = test_signer();
+        let mut store = MemoryStateStore::new();
+        let sig = signer.sign(b"msg", &mut store).unwrap();
+
+        // A directly constructed key must neither panic nor verify
+        for height in [0, MAX_XMSS_HEIGHT + 1, 64, u8::MAX] {
+            let pk = XmssPublicKey {
+                height,
+                ..*signer.public_key()
+            };
+            let mut padded = sig.clone();
+            padded.auth_path.resize(height as usize, [0u8; 32]);
+            assert!(!pk.verify(b"msg", &padded));
+        }
+        let oversized = XmssPublicKey {
+            height: 64,
+            ..*signer.public_key()
+        };
+        assert_eq!(oversized.max_signatures(), u64::MAX);
+    }
+
+    #[test]
+    fn test_generate_requires_secure_entropy() {
+        use crate::mtd::entropy::DeterministicEntropy;
+        let mut weak = DeterministicEntropy::new(1);
+        assert!(XmssSigner::generate(MIN_XMSS_HEIGHT, &mut weak).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_state_store_roundtrip() {
+        let path =
+            std::env::temp_dir().join(alloc::format!("zkmtd-xmss-{}.state", std::process::id()));
+        let mut store = FileStateStore::new(&path);
+        assert_eq!(store.load_next_index().unwrap(), None);
+
+        store.persist_next_index(42).unwrap();
+        assert_eq!(
+            FileStateStore::new(&path).load_next_index().unwrap(),
+            Some(42)
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -69,6 +69,14 @@ impl LightweightProof {
     pub const fn estimated_cu() -> u64 {
         5_000 // Based on actual measurement (4,232 CU) with safety buffer
     }
+
+    /// Replay nullifier. `OnchainVerifier::verify` checks `committed_values`
+    /// but not `commitment`, so only the former is nullified: one spend per
+    /// committed statement, whatever the other fields say.
+    #[cfg(feature = "alloc")]
+    pub fn nullifier(&self) -> [u8; 32] {
+        crate::batching::nullifier::nullifier_from_committed_values(&self.committed_values)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -86,8 +94,7 @@ impl ProofCommitment {
         use crate::utils::hash::poseidon_hash;
 
         let hash = poseidon_hash(proof_data, DOMAIN_COMMITMENT);
-        let seed_hash = poseidon_hash(seed, crate::utils::constants::DOMAIN_SEED_FINGERPRINT);
-        let seed_fingerprint = u64::from_le_bytes(seed_hash[0..8].try_into().unwrap_or([0u8; 8]));
+        let seed_fingerprint = crate::mtd::seed_fingerprint(seed);
 
         Self {
             hash,
@@ -126,10 +133,19 @@ impl BatchLightweightProof {
     /// from the proof itself — otherwise an attacker constructs a self-consistent
     /// root for any leaf and the check is vacuous. This is the secure entrypoint.
     #[cfg(feature = "alloc")]
-    #[allow(clippy::manual_is_multiple_of)]
     pub fn verify_inclusion_against(&self, trusted_root: &[u8; 32]) -> bool {
-        use crate::utils::constants::{DOMAIN_MERKLE_NODE, MAX_MERKLE_DEPTH};
-        use crate::utils::hash::{constant_time_eq_fixed, poseidon_hash};
+        self.verify_with_arity(trusted_root, crate::batching::merkle::MerkleArity::Binary)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn verify_with_arity(
+        &self,
+        trusted_root: &[u8; 32],
+        arity: crate::batching::merkle::MerkleArity,
+    ) -> bool {
+        use crate::batching::merkle::{bind_root, fold_path};
+        use crate::utils::constants::MAX_MERKLE_DEPTH;
+        use crate::utils::hash::constant_time_eq_fixed;
 
         // RT-5: bound the path depth (defense-in-depth on top of borsh's bounded
         // preallocation).
@@ -137,37 +153,27 @@ impl BatchLightweightProof {
             return false;
         }
 
-        // H-A: the leaf index must lie within the tree and the path depth must
-        // match the tree size, so `leaf_index` is fully consumed by the traversal
-        // (prevents same-parity index forgery / high-bit-ignored replay, and
-        // rejects `leaf_index >= proof_count`).
+        // H-A: the leaf index must lie within the tree and the path length must
+        // match the tree size and arity, so `leaf_index` is fully consumed by
+        // the traversal (prevents same-parity index forgery / high-digit-ignored
+        // replay, and rejects `leaf_index >= proof_count`).
         let count = self.proof_count as usize;
         if count == 0 || (self.leaf_index as usize) >= count {
             return false;
         }
-        let expected_depth = if count <= 1 {
-            0
-        } else {
-            (usize::BITS - (count - 1).leading_zeros()) as usize // ceil(log2(count))
-        };
-        if self.merkle_path.len() != expected_depth {
+        if self.merkle_path.len() != arity.path_len(count) {
             return false;
         }
 
-        let mut current = self.leaf_commitment;
-        let mut index = self.leaf_index;
-        for sibling in &self.merkle_path {
-            let combined = if index % 2 == 0 {
-                [current.as_slice(), sibling.as_slice()].concat()
-            } else {
-                [sibling.as_slice(), current.as_slice()].concat()
-            };
-            current = poseidon_hash(&combined, DOMAIN_MERKLE_NODE);
-            index /= 2;
-        }
-
-        // RT-2: bind the leaf count into the root, matching MerkleTree::new.
-        let computed = crate::batching::merkle::bind_root(&current, count);
+        let inner = fold_path(
+            &self.leaf_commitment,
+            self.leaf_index as usize,
+            &self.merkle_path,
+            arity,
+        );
+        // RT-2: bind the leaf count and arity into the root, matching
+        // MerkleTree::with_arity.
+        let computed = bind_root(&inner, count, arity);
         constant_time_eq_fixed(&computed, trusted_root)
     }
 
@@ -192,6 +198,70 @@ impl BatchLightweightProof {
     }
 }
 
+/// [`BatchLightweightProof`] from a tree of any
+/// [`MerkleArity`](crate::batching::merkle::MerkleArity). A separate type so
+/// the binary proof's layout stays unchanged; its encoding is the arity byte
+/// followed by the binary proof's.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub struct BatchLightweightProofV2 {
+    /// Tree arity: 2, 4 or 8.
+    pub arity: u8,
+    pub proof: BatchLightweightProof,
+}
+
+impl From<BatchLightweightProof> for BatchLightweightProofV2 {
+    fn from(proof: BatchLightweightProof) -> Self {
+        Self { arity: 2, proof }
+    }
+}
+
+impl BatchLightweightProofV2 {
+    /// Verify inclusion against a TRUSTED external root (C-A; see
+    /// [`BatchLightweightProof::verify_inclusion_against`]).
+    #[cfg(feature = "alloc")]
+    pub fn verify_inclusion_against(&self, trusted_root: &[u8; 32]) -> bool {
+        match crate::batching::merkle::MerkleArity::from_u8(self.arity) {
+            Some(arity) => self.proof.verify_with_arity(trusted_root, arity),
+            None => false,
+        }
+    }
+
+    pub fn estimated_cu(&self) -> u64 {
+        self.proof.estimated_cu()
+    }
+}
+
+/// Several leaves of one batch proven together with a deduplicated
+/// [`MerkleMultiProof`](crate::batching::merkle::MerkleMultiProof), instead
+/// of one [`BatchLightweightProof`] (and one full path) per leaf.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub struct BatchLightweightMultiProof {
+    pub merkle_root: [u8; 32],
+    pub epoch: u64,
+    /// Leaf commitments, ordered as `multi_proof.leaf_indices`.
+    pub leaf_commitments: Vec<[u8; 32]>,
+    pub multi_proof: crate::batching::merkle::MerkleMultiProof,
+}
+
+#[cfg(feature = "alloc")]
+impl BatchLightweightMultiProof {
+    /// Verify inclusion of every leaf against a TRUSTED external root (C-A;
+    /// see [`BatchLightweightProof::verify_inclusion_against`]).
+    pub fn verify_inclusion_against(&self, trusted_root: &[u8; 32]) -> bool {
+        self.multi_proof
+            .verify_against(&self.leaf_commitments, trusted_root)
+    }
+
+    /// Upper bound: one hash per carried sibling or proven leaf.
+    pub fn estimated_cu(&self) -> u64 {
+        let hashes = (self.multi_proof.siblings.len() + self.leaf_commitments.len()) as u64;
+        500 + (hashes * 300)
+    }
+}
+
 #[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
@@ -316,8 +386,11 @@ mod tests {
         let leaf1 = [2u8; 32];
 
         let combined = [leaf0.as_slice(), leaf1.as_slice()].concat();
-        let root =
-            crate::batching::merkle::bind_root(&poseidon_hash(&combined, DOMAIN_MERKLE_NODE), 2);
+        let root = crate::batching::merkle::bind_root(
+            &poseidon_hash(&combined, DOMAIN_MERKLE_NODE),
+            2,
+            crate::batching::merkle::MerkleArity::Binary,
+        );
 
         // Create batch proof for leaf0
         let batch_proof = BatchLightweightProof {
@@ -341,8 +414,11 @@ mod tests {
         let leaf1 = [2u8; 32];
 
         let combined = [leaf0.as_slice(), leaf1.as_slice()].concat();
-        let root =
-            crate::batching::merkle::bind_root(&poseidon_hash(&combined, DOMAIN_MERKLE_NODE), 2);
+        let root = crate::batching::merkle::bind_root(
+            &poseidon_hash(&combined, DOMAIN_MERKLE_NODE),
+            2,
+            crate::batching::merkle::MerkleArity::Binary,
+        );
 
         // Create batch proof for leaf1 (index 1, odd)
         let batch_proof = BatchLightweightProof {
@@ -357,6 +433,55 @@ mod tests {
         assert!(batch_proof.verify_inclusion());
     }
 
+    #[test]
+    fn test_batch_lightweight_proof_higher_arity() {
+        use crate::batching::merkle::{MerkleArity, MerkleTree};
+
+        let leaves: Vec<[u8; 32]> = (0..11u8).map(|i| [i; 32]).collect();
+        for arity in [MerkleArity::Quaternary, MerkleArity::Octal] {
+            let tree = MerkleTree::with_arity(leaves.clone(), arity).unwrap();
+            let trusted = *tree.root();
+            let path = tree.get_proof(9).unwrap();
+            let proof = BatchLightweightProofV2 {
+                arity: arity as u8,
+                proof: BatchLightweightProof {
+                    merkle_root: trusted,
+                    proof_count: 11,
+                    epoch: 100,
+                    merkle_path: path.siblings.clone(),
+                    leaf_index: 9,
+                    leaf_commitment: leaves[9],
+                },
+            };
+            assert!(proof.verify_inclusion_against(&trusted));
+
+            // Same path under another or an unsupported arity, as a binary
+            // proof, or with the index and leaf count relabeled
+            for bad_arity in [2, 3, 16] {
+                let mut bad = proof.clone();
+                bad.arity = bad_arity;
+                assert!(!bad.verify_inclusion_against(&trusted));
+            }
+            assert!(!proof.proof.verify_inclusion_against(&trusted));
+            let mut bad = proof.clone();
+            bad.proof.leaf_index += 16;
+            bad.proof.proof_count = 32;
+            assert!(!bad.verify_inclusion_against(&trusted));
+        }
+
+        // Binary proofs carry over unchanged
+        let tree = MerkleTree::new(leaves.clone()).unwrap();
+        let binary = BatchLightweightProof {
+            merkle_root: *tree.root(),
+            proof_count: 11,
+            epoch: 100,
+            merkle_path: tree.get_proof(3).unwrap().siblings,
+            leaf_index: 3,
+            leaf_commitment: leaves[3],
+        };
+        assert!(BatchLightweightProofV2::from(binary).verify_inclusion_against(tree.root()));
+    }
+
     #[test]
     fn test_batch_lightweight_proof_verify_inclusion_invalid() {
         let batch_proof = BatchLightweightProof {
@@ -403,6 +528,38 @@ mod tests {
         assert_eq!(cu, 1700);
     }
 
+    #[test]
+    fn test_batch_lightweight_multi_proof() {
+        use crate::batching::merkle::{hash_leaf, MerkleTree};
+
+        let leaves: Vec<[u8; 32]> = (0..16u8).map(|i| hash_leaf(&[i])).collect();
+        let tree = MerkleTree::new(leaves.clone()).unwrap();
+        let multi_proof = tree.get_multi_proof(&[2, 3, 9]).unwrap();
+        let batch = BatchLightweightMultiProof {
+            merkle_root: *tree.root(),
+            epoch: 100,
+            leaf_commitments: vec![leaves[2], leaves[3], leaves[9]],
+            multi_proof,
+        };
+        assert!(batch.verify_inclusion_against(tree.root()));
+        assert!(!batch.verify_inclusion_against(&[0u8; 32]));
+
+        // Cheaper than three single-leaf proofs (3 * (500 + 4 * 300))
+        assert!(batch.estimated_cu() < 3 * 1700);
+
+        let mut forged = batch.clone();
+        forged.leaf_commitments[1] = [0x41u8; 32];
+        assert!(!forged.verify_inclusion_against(tree.root()));
+
+        #[cfg(feature = "borsh")]
+        {
+            let bytes = borsh::to_vec(&batch).unwrap();
+            let decoded: BatchLightweightMultiProof = borsh::from_slice(&bytes).unwrap();
+            assert_eq!(decoded.multi_proof, batch.multi_proof);
+            assert!(decoded.verify_inclusion_against(tree.root()));
+        }
+    }
+
     #[test]
     fn test_lightweight_proof_clone_debug() {
         let proof = LightweightProof::new(
@@ -3,5 +3,9 @@
 pub mod lightweight;
 pub mod onchain_verifier;
 
-pub use lightweight::{BatchLightweightProof, LightweightProof, ProofCommitment};
+#[cfg(feature = "alloc")]
+pub use lightweight::BatchLightweightMultiProof;
+pub use lightweight::{
+    BatchLightweightProof, BatchLightweightProofV2, LightweightProof, ProofCommitment,
+};
 pub use onchain_verifier::OnchainVerifier;
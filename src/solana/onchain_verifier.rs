@@ -10,7 +10,15 @@ extern crate alloc;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
-use super::lightweight::{BatchLightweightProof, LightweightProof, ProofCommitment};
+#[cfg(feature = "alloc")]
+use super::lightweight::BatchLightweightMultiProof;
+use super::lightweight::{
+    BatchLightweightProof, BatchLightweightProofV2, LightweightProof, ProofCommitment,
+};
+#[cfg(feature = "alloc")]
+use crate::batching::nullifier::SmtProof;
+#[cfg(feature = "alloc")]
+use crate::mtd::{Epoch, RevocationList, RevocationReason};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum VerificationStatus {
@@ -21,6 +29,21 @@ pub enum VerificationStatus {
     InvalidPublicValues,
     InvalidCommittedValues,
     MalformedProof,
+    /// Epoch is on the configured revocation list.
+    RevokedEpoch {
+        epoch: u64,
+    },
+    /// Seed fingerprint is on the configured revocation list.
+    RevokedSeed {
+        fingerprint: u64,
+    },
+    /// The revocation list revokes seeds and the proof carries no
+    /// fingerprint to clear it.
+    UnknownSeed,
+    /// Proof nullifier is already in the nullifier set (replay).
+    NullifierSpent,
+    /// Nullifier path does not verify against the trusted set root.
+    InvalidNullifierProof,
 }
 
 impl VerificationStatus {
@@ -39,6 +62,11 @@ pub struct OnchainVerifier {
     /// Trusted batch Merkle root (C-A). MUST come from a trusted source
     /// (on-chain state / signed header), never from the submitted proof.
     expected_merkle_root: Option<[u8; 32]>,
+    #[cfg(feature = "alloc")]
+    revocations: Option<RevocationList>,
+    /// Trusted nullifier-set root (program state), for replay rejection.
+    #[cfg(feature = "alloc")]
+    nullifier_root: Option<[u8; 32]>,
 }
 
 impl OnchainVerifier {
@@ -50,12 +78,18 @@ impl OnchainVerifier {
             expected_public_values: None,
             expected_committed_values,
             expected_merkle_root: None,
+            #[cfg(feature = "alloc")]
+            revocations: None,
+            #[cfg(feature = "alloc")]
+            nullifier_root: None,
         }
     }
 
     /// Configure the TRUSTED batch Merkle root used by [`verify_batch`].
     /// Required: without it, `verify_batch` rejects every batch proof, because
     /// trusting the proof's own root would let an attacker forge inclusion (C-A).
+    /// Off-chain relayers can authenticate the root with
+    /// `XmssPublicKey::verify_batch_header` before installing it here.
     pub fn with_expected_merkle_root(mut self, root: [u8; 32]) -> Self {
         self.expected_merkle_root = Some(root);
         self
@@ -77,6 +111,27 @@ impl OnchainVerifier {
         self
     }
 
+    /// Reject proofs from revoked epochs or seeds. Like the trusted Merkle
+    /// root, the list must be authenticated (e.g. with
+    /// `XmssPublicKey::verify_revocation_list`) before it is installed.
+    /// Only [`ProofCommitment`]s carry a seed fingerprint, so once the list
+    /// revokes any seed, lightweight and batch proofs fail with
+    /// [`VerificationStatus::UnknownSeed`].
+    #[cfg(feature = "alloc")]
+    pub fn with_revocation_list(mut self, list: RevocationList) -> Self {
+        self.revocations = Some(list);
+        self
+    }
+
+    /// Trusted root of the nullifier set used by
+    /// [`verify_unspent`](Self::verify_unspent). After accepting a proof the
+    /// program stores `path.root_after_insert(&proof.nullifier(), &root)`.
+    #[cfg(feature = "alloc")]
+    pub fn with_nullifier_root(mut self, root: [u8; 32]) -> Self {
+        self.nullifier_root = Some(root);
+        self
+    }
+
     #[cfg(feature = "alloc")]
     pub fn verify(&self, proof: &LightweightProof) -> VerificationStatus {
         // 0. RT-5: reject absurd public-value counts (defense-in-depth; borsh
@@ -85,6 +140,10 @@ impl OnchainVerifier {
             return VerificationStatus::InvalidPublicValues;
         }
 
+        if let Some(status) = self.check_revocation(proof.epoch, None) {
+            return status;
+        }
+
         // 1. Verify epoch
         if !self.is_valid_epoch(proof.epoch) {
             return VerificationStatus::InvalidEpoch {
@@ -118,6 +177,10 @@ impl OnchainVerifier {
 
     #[cfg(feature = "alloc")]
     pub fn verify_batch(&self, batch_proof: &BatchLightweightProof) -> VerificationStatus {
+        if let Some(status) = self.check_revocation(batch_proof.epoch, None) {
+            return status;
+        }
+
         // 1. Verify epoch
         if !self.is_valid_epoch(batch_proof.epoch) {
             return VerificationStatus::InvalidEpoch {
@@ -141,6 +204,88 @@ impl OnchainVerifier {
         VerificationStatus::Valid
     }
 
+    /// [`verify`](Self::verify) plus replay rejection: `path` must prove the
+    /// proof's nullifier absent from the trusted nullifier set. Without a
+    /// configured root every proof is rejected.
+    #[cfg(feature = "alloc")]
+    pub fn verify_unspent(&self, proof: &LightweightProof, path: &SmtProof) -> VerificationStatus {
+        let status = self.verify(proof);
+        if !status.is_valid() {
+            return status;
+        }
+
+        let Some(ref root) = self.nullifier_root else {
+            return VerificationStatus::InvalidNullifierProof;
+        };
+        let nullifier = proof.nullifier();
+        if path.verify_membership(&nullifier, root) {
+            return VerificationStatus::NullifierSpent;
+        }
+        if !path.verify_non_membership(&nullifier, root) {
+            return VerificationStatus::InvalidNullifierProof;
+        }
+
+        VerificationStatus::Valid
+    }
+
+    /// [`verify_batch`](Self::verify_batch) for a tree of any arity.
+    #[cfg(feature = "alloc")]
+    pub fn verify_batch_v2(&self, batch_proof: &BatchLightweightProofV2) -> VerificationStatus {
+        let epoch = batch_proof.proof.epoch;
+        if let Some(status) = self.check_revocation(epoch, None) {
+            return status;
+        }
+
+        if !self.is_valid_epoch(epoch) {
+            return VerificationStatus::InvalidEpoch {
+                expected: self.current_epoch,
+                got: epoch,
+            };
+        }
+
+        // C-A: trusted root only
+        match self.expected_merkle_root {
+            Some(ref trusted_root) => {
+                if !batch_proof.verify_inclusion_against(trusted_root) {
+                    return VerificationStatus::InvalidMerkleProof;
+                }
+            }
+            None => return VerificationStatus::InvalidMerkleProof,
+        }
+
+        VerificationStatus::Valid
+    }
+
+    /// [`verify_batch`](Self::verify_batch) for several leaves at once.
+    #[cfg(feature = "alloc")]
+    pub fn verify_batch_multi(
+        &self,
+        batch_proof: &BatchLightweightMultiProof,
+    ) -> VerificationStatus {
+        if let Some(status) = self.check_revocation(batch_proof.epoch, None) {
+            return status;
+        }
+
+        if !self.is_valid_epoch(batch_proof.epoch) {
+            return VerificationStatus::InvalidEpoch {
+                expected: self.current_epoch,
+                got: batch_proof.epoch,
+            };
+        }
+
+        // C-A: trusted root only
+        match self.expected_merkle_root {
+            Some(ref trusted_root) => {
+                if !batch_proof.verify_inclusion_against(trusted_root) {
+                    return VerificationStatus::InvalidMerkleProof;
+                }
+            }
+            None => return VerificationStatus::InvalidMerkleProof,
+        }
+
+        VerificationStatus::Valid
+    }
+
     fn is_valid_epoch(&self, proof_epoch: u64) -> bool {
         if proof_epoch > self.current_epoch {
             return false; // Future epochs not allowed
@@ -162,6 +307,47 @@ impl OnchainVerifier {
         commitment.verify(proof_data)
     }
 
+    /// Check a [`ProofCommitment`] against the revocation list (epoch and
+    /// seed fingerprint), the epoch window and the proof data.
+    #[cfg(feature = "alloc")]
+    pub fn verify_proof_commitment(
+        &self,
+        commitment: &ProofCommitment,
+        proof_data: &[u8],
+    ) -> VerificationStatus {
+        if let Some(status) =
+            self.check_revocation(commitment.epoch, Some(commitment.seed_fingerprint))
+        {
+            return status;
+        }
+        if !self.is_valid_epoch(commitment.epoch) {
+            return VerificationStatus::InvalidEpoch {
+                expected: self.current_epoch,
+                got: commitment.epoch,
+            };
+        }
+        if !commitment.verify(proof_data) {
+            return VerificationStatus::InvalidCommitment;
+        }
+        VerificationStatus::Valid
+    }
+
+    #[cfg(feature = "alloc")]
+    fn check_revocation(&self, epoch: u64, fingerprint: Option<u64>) -> Option<VerificationStatus> {
+        let list = self.revocations.as_ref()?;
+        let epoch = match Epoch::try_new(epoch) {
+            Ok(epoch) => epoch,
+            Err(_) => return Some(VerificationStatus::MalformedProof),
+        };
+        list.check(epoch, fingerprint).map(|reason| match reason {
+            RevocationReason::Epoch(epoch) => VerificationStatus::RevokedEpoch {
+                epoch: epoch.value(),
+            },
+            RevocationReason::Seed(fingerprint) => VerificationStatus::RevokedSeed { fingerprint },
+            RevocationReason::UnknownSeed => VerificationStatus::UnknownSeed,
+        })
+    }
+
     /// Estimate on-chain CU cost for lightweight verification.
     ///
     /// This covers commitment + epoch checks only (~5K CU total).
@@ -263,6 +449,152 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_revocation_list() {
+        let committed = [99u8; 32];
+        let mut list = RevocationList::new(1);
+        list.revoke_epochs(Epoch::new(99), Epoch::new(99)).unwrap();
+        let epochs_only = OnchainVerifier::new(100, committed).with_revocation_list(list.clone());
+        list.revoke_seed(crate::mtd::seed_fingerprint(b"leaked"))
+            .unwrap();
+        let verifier = OnchainVerifier::new(100, committed).with_revocation_list(list);
+
+        let proof = LightweightProof::from_commitment([1u8; 32], 100, vec![1, 1, 2], committed);
+        assert!(epochs_only.verify(&proof).is_valid());
+        let revoked = LightweightProof::from_commitment([1u8; 32], 99, vec![1, 1, 2], committed);
+        assert_eq!(
+            epochs_only.verify(&revoked),
+            VerificationStatus::RevokedEpoch { epoch: 99 }
+        );
+
+        // Lightweight proofs carry no seed fingerprint, so a list revoking
+        // seeds rejects them rather than letting a revoked seed through
+        assert_eq!(verifier.verify(&proof), VerificationStatus::UnknownSeed);
+
+        let data = b"proof bytes";
+        let good = ProofCommitment::from_data(data, 100, b"fine");
+        assert!(verifier.verify_proof_commitment(&good, data).is_valid());
+        assert_eq!(
+            verifier.verify_proof_commitment(&good, b"other bytes"),
+            VerificationStatus::InvalidCommitment
+        );
+        let leaked = ProofCommitment::from_data(data, 100, b"leaked");
+        assert_eq!(
+            verifier.verify_proof_commitment(&leaked, data),
+            VerificationStatus::RevokedSeed {
+                fingerprint: leaked.seed_fingerprint
+            }
+        );
+    }
+
+    #[test]
+    fn test_verify_batch_multi() {
+        use crate::batching::merkle::{hash_leaf, MerkleTree};
+
+        let leaves: Vec<[u8; 32]> = (0..8u8).map(|i| hash_leaf(&[i])).collect();
+        let tree = MerkleTree::new(leaves.clone()).unwrap();
+        let proof = BatchLightweightMultiProof {
+            merkle_root: *tree.root(),
+            epoch: 100,
+            leaf_commitments: vec![leaves[0], leaves[6]],
+            multi_proof: tree.get_multi_proof(&[0, 6]).unwrap(),
+        };
+
+        let verifier = OnchainVerifier::new(100, [0u8; 32]).with_expected_merkle_root(*tree.root());
+        assert!(verifier.verify_batch_multi(&proof).is_valid());
+
+        // No trusted root, stale epoch, wrong trusted root
+        let untrusted = OnchainVerifier::new(100, [0u8; 32]);
+        assert_eq!(
+            untrusted.verify_batch_multi(&proof),
+            VerificationStatus::InvalidMerkleProof
+        );
+        let later = OnchainVerifier::new(105, [0u8; 32]).with_expected_merkle_root(*tree.root());
+        assert!(!later.verify_batch_multi(&proof).is_valid());
+        let other = OnchainVerifier::new(100, [0u8; 32]).with_expected_merkle_root([7u8; 32]);
+        assert_eq!(
+            other.verify_batch_multi(&proof),
+            VerificationStatus::InvalidMerkleProof
+        );
+    }
+
+    #[test]
+    fn test_verify_batch_v2() {
+        use crate::batching::merkle::{hash_leaf, MerkleArity, MerkleTree};
+
+        let leaves: Vec<[u8; 32]> = (0..10u8).map(|i| hash_leaf(&[i])).collect();
+        let tree = MerkleTree::with_arity(leaves.clone(), MerkleArity::Quaternary).unwrap();
+        let proof = BatchLightweightProofV2 {
+            arity: 4,
+            proof: BatchLightweightProof {
+                merkle_root: *tree.root(),
+                proof_count: 10,
+                epoch: 100,
+                merkle_path: tree.get_proof(7).unwrap().siblings,
+                leaf_index: 7,
+                leaf_commitment: leaves[7],
+            },
+        };
+
+        let verifier = OnchainVerifier::new(100, [0u8; 32]).with_expected_merkle_root(*tree.root());
+        assert!(verifier.verify_batch_v2(&proof).is_valid());
+        assert_eq!(
+            verifier.verify_batch(&proof.proof),
+            VerificationStatus::InvalidMerkleProof
+        );
+
+        let untrusted = OnchainVerifier::new(100, [0u8; 32]);
+        assert_eq!(
+            untrusted.verify_batch_v2(&proof),
+            VerificationStatus::InvalidMerkleProof
+        );
+        let later = OnchainVerifier::new(105, [0u8; 32]).with_expected_merkle_root(*tree.root());
+        assert!(!later.verify_batch_v2(&proof).is_valid());
+    }
+
+    #[test]
+    fn test_verify_unspent() {
+        use crate::batching::nullifier::NullifierSet;
+
+        let committed = [99u8; 32];
+        let proof = LightweightProof::from_commitment([1u8; 32], 100, vec![1, 1, 2], committed);
+        let mut set = NullifierSet::new();
+
+        let root = set.root();
+        let path = set.prove(&proof.nullifier());
+        let verifier = OnchainVerifier::new(100, committed).with_nullifier_root(root);
+        assert!(verifier.verify_unspent(&proof, &path).is_valid());
+        assert_eq!(
+            OnchainVerifier::new(100, committed).verify_unspent(&proof, &path),
+            VerificationStatus::InvalidNullifierProof
+        );
+
+        // Program state moves to the next root; resubmission is a replay
+        let next = path.root_after_insert(&proof.nullifier(), &root).unwrap();
+        set.insert(proof.nullifier()).unwrap();
+        assert_eq!(next, set.root());
+        let verifier = OnchainVerifier::new(100, committed).with_nullifier_root(next);
+        assert_eq!(
+            verifier.verify_unspent(&proof, &set.prove(&proof.nullifier())),
+            VerificationStatus::NullifierSpent
+        );
+        let other = OnchainVerifier::new(100, committed).with_nullifier_root([7u8; 32]);
+        assert_eq!(
+            other.verify_unspent(&proof, &path),
+            VerificationStatus::InvalidNullifierProof
+        );
+
+        // Fields the verifier does not check do not change the nullifier
+        let mut altered = proof.clone();
+        altered.commitment[0] ^= 1;
+        altered.merkle_root = [5u8; 32];
+        assert!(verifier.verify(&altered).is_valid());
+        assert_eq!(
+            verifier.verify_unspent(&altered, &set.prove(&altered.nullifier())),
+            VerificationStatus::NullifierSpent
+        );
+    }
+
     #[test]
     fn test_fibonacci_verification() {
         use syscall_helpers::verify_fibonacci_sequence;
@@ -1,6 +1,8 @@
 //! AIR (Algebraic Intermediate Representation) for STARK polynomial constraints
 
+use crate::core::errors::Result;
 use crate::core::types::FieldElement;
+use crate::stark::layout::validate_column_order;
 
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
@@ -11,11 +13,17 @@ use p3_air::{AirBuilder, BaseAir, WindowAccess};
 use p3_goldilocks::Goldilocks;
 use p3_matrix::dense::RowMajorMatrix;
 
+/// Widest [`SimpleAir`] (Sum / Multiplication).
+const MAX_SIMPLE_COLUMNS: usize = 3;
+
 /// Simple AIR for Fibonacci, Sum, Multiplication
 #[derive(Debug, Clone)]
 pub struct SimpleAir {
     num_columns: usize,
     air_type: AirType,
+    /// Physical trace column of each logical column (identity unless the
+    /// layout is warped, see `stark::layout`).
+    columns: [usize; MAX_SIMPLE_COLUMNS],
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,15 +35,39 @@ pub enum AirType {
 
 impl SimpleAir {
     pub fn fibonacci() -> Self {
-        Self { num_columns: 2, air_type: AirType::Fibonacci }
+        Self {
+            num_columns: 2,
+            air_type: AirType::Fibonacci,
+            columns: [0, 1, 2],
+        }
     }
 
     pub fn sum() -> Self {
-        Self { num_columns: 3, air_type: AirType::Sum }
+        Self {
+            num_columns: 3,
+            air_type: AirType::Sum,
+            columns: [0, 1, 2],
+        }
     }
 
     pub fn multiplication() -> Self {
-        Self { num_columns: 3, air_type: AirType::Multiplication }
+        Self {
+            num_columns: 3,
+            air_type: AirType::Multiplication,
+            columns: [0, 1, 2],
+        }
+    }
+
+    /// Place logical column `i` at physical column `order[i]`. The trace must
+    /// be permuted the same way.
+    pub fn with_column_order(mut self, order: &[usize]) -> Result<Self> {
+        validate_column_order(order, self.num_columns)?;
+        self.columns[..self.num_columns].copy_from_slice(order);
+        Ok(self)
+    }
+
+    pub fn column_order(&self) -> &[usize] {
+        &self.columns[..self.num_columns]
     }
 
     pub fn num_columns(&self) -> usize {
@@ -100,6 +132,7 @@ impl BaseAir<Goldilocks> for SimpleAir {
 impl<AB: AirBuilder<F = Goldilocks>> P3Air<AB> for SimpleAir {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
+        let [c0, c1, c2] = self.columns;
         let local = main.current_slice();
         let next = main.next_slice();
 
@@ -112,20 +145,20 @@ impl<AB: AirBuilder<F = Goldilocks>> P3Air<AB> for SimpleAir {
                 let (init_a, init_b, final_a, final_b) = (pis[0], pis[1], pis[2], pis[3]);
 
                 let mut when_first = builder.when_first_row();
-                when_first.assert_eq(local[0], init_a);
-                when_first.assert_eq(local[1], init_b);
+                when_first.assert_eq(local[c0], init_a);
+                when_first.assert_eq(local[c1], init_b);
 
                 let mut when_trans = builder.when_transition();
-                when_trans.assert_eq(next[0], local[1]);
-                when_trans.assert_eq(next[1], local[0] + local[1]);
+                when_trans.assert_eq(next[c0], local[c1]);
+                when_trans.assert_eq(next[c1], local[c0] + local[c1]);
 
                 let mut when_last = builder.when_last_row();
-                when_last.assert_eq(local[0], final_a);
-                when_last.assert_eq(local[1], final_b);
+                when_last.assert_eq(local[c0], final_a);
+                when_last.assert_eq(local[c1], final_b);
             }
             AirType::Sum => {
                 // Per-row relation: c = a + b
-                builder.assert_eq(local[2], local[0] + local[1]);
+                builder.assert_eq(local[c2], local[c0] + local[c1]);
 
                 // SOUNDNESS (C-1): bind first/last trace rows to the public values
                 // [a_first, b_first, c_first, a_last, b_last, c_last].
@@ -133,18 +166,18 @@ impl<AB: AirBuilder<F = Goldilocks>> P3Air<AB> for SimpleAir {
                 let (af, bf, cf, al, bl, cl) = (pis[0], pis[1], pis[2], pis[3], pis[4], pis[5]);
 
                 let mut when_first = builder.when_first_row();
-                when_first.assert_eq(local[0], af);
-                when_first.assert_eq(local[1], bf);
-                when_first.assert_eq(local[2], cf);
+                when_first.assert_eq(local[c0], af);
+                when_first.assert_eq(local[c1], bf);
+                when_first.assert_eq(local[c2], cf);
 
                 let mut when_last = builder.when_last_row();
-                when_last.assert_eq(local[0], al);
-                when_last.assert_eq(local[1], bl);
-                when_last.assert_eq(local[2], cl);
+                when_last.assert_eq(local[c0], al);
+                when_last.assert_eq(local[c1], bl);
+                when_last.assert_eq(local[c2], cl);
             }
             AirType::Multiplication => {
                 // Per-row relation: c = a * b
-                builder.assert_eq(local[2], local[0] * local[1]);
+                builder.assert_eq(local[c2], local[c0] * local[c1]);
 
                 // SOUNDNESS (C-1): bind first/last trace rows to the public values
                 // [a_first, b_first, c_first, a_last, b_last, c_last].
@@ -152,14 +185,14 @@ impl<AB: AirBuilder<F = Goldilocks>> P3Air<AB> for SimpleAir {
                 let (af, bf, cf, al, bl, cl) = (pis[0], pis[1], pis[2], pis[3], pis[4], pis[5]);
 
                 let mut when_first = builder.when_first_row();
-                when_first.assert_eq(local[0], af);
-                when_first.assert_eq(local[1], bf);
-                when_first.assert_eq(local[2], cf);
+                when_first.assert_eq(local[c0], af);
+                when_first.assert_eq(local[c1], bf);
+                when_first.assert_eq(local[c2], cf);
 
                 let mut when_last = builder.when_last_row();
-                when_last.assert_eq(local[0], al);
-                when_last.assert_eq(local[1], bl);
-                when_last.assert_eq(local[2], cl);
+                when_last.assert_eq(local[c0], al);
+                when_last.assert_eq(local[c1], bl);
+                when_last.assert_eq(local[c2], cl);
             }
         }
     }
@@ -0,0 +1,411 @@
+//! Expiring credentials - integrated proofs valid over an explicit epoch range
+//!
+//! A credential is an [`IntegratedProof`] made under params derived from a
+//! long-lived credential key (a node of the `mtd::keytree` hierarchy) and its
+//! validity range, rather than from the hourly MTD schedule. It verifies at
+//! any epoch inside `[valid_from, valid_until]` and nowhere else.
+//!
+//! Each credential is issued to a holder's XMSS public key, which is bound
+//! into its params like the range. Credentials are never accepted bare: the
+//! holder signs a [`CredentialPresentation`] over the current epoch's MTD
+//! params. A presentation captured in one epoch cannot be replayed in the
+//! next, and a captured credential cannot be presented at all without the
+//! holder's signing key.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::mtd::{DerivationPath, Epoch, ExtendedSeed, WarpingParams};
+use crate::signature::xmss::public_keys_match;
+use crate::signature::{SignerStateStore, XmssPublicKey, XmssSignature, XmssSigner};
+use crate::stark::integrated::{IntegratedProof, IntegratedProver, IntegratedVerifier};
+use crate::utils::constants::{
+    DOMAIN_CREDENTIAL_PRESENTATION, DOMAIN_CREDENTIAL_RANGE, MAX_CREDENTIAL_EPOCHS,
+};
+use crate::utils::hash::poseidon_hash;
+
+use zeroize::Zeroizing;
+
+/// Inclusive epoch range a credential is valid for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialValidity {
+    valid_from: Epoch,
+    valid_until: Epoch,
+}
+
+impl CredentialValidity {
+    pub fn new(valid_from: Epoch, valid_until: Epoch) -> Result<Self> {
+        if valid_until < valid_from
+            || valid_until.value() - valid_from.value() >= MAX_CREDENTIAL_EPOCHS
+        {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: alloc::format!(
+                    "Invalid credential validity {}..={} (at most {} epochs)",
+                    valid_from.value(),
+                    valid_until.value(),
+                    MAX_CREDENTIAL_EPOCHS
+                ),
+            });
+        }
+        Ok(Self {
+            valid_from,
+            valid_until,
+        })
+    }
+
+    /// `epochs` epochs starting at `valid_from` (e.g. 720 for 30 days).
+    pub fn for_epochs(valid_from: Epoch, epochs: u64) -> Result<Self> {
+        let until = epochs
+            .checked_sub(1)
+            .and_then(|n| valid_from.value().checked_add(n))
+            .ok_or_else(|| ZKMTDError::ConfigurationError {
+                reason: "Credential validity must span at least one epoch".into(),
+            })?;
+        Self::new(valid_from, Epoch::try_new(until)?)
+    }
+
+    pub fn valid_from(&self) -> Epoch {
+        self.valid_from
+    }
+
+    pub fn valid_until(&self) -> Epoch {
+        self.valid_until
+    }
+
+    pub fn contains(&self, epoch: Epoch) -> bool {
+        self.valid_from <= epoch && epoch <= self.valid_until
+    }
+
+    fn to_bytes(self) -> [u8; 16] {
+        let mut out = [0u8; 16];
+        out[..8].copy_from_slice(&self.valid_from.to_bytes());
+        out[8..].copy_from_slice(&self.valid_until.to_bytes());
+        out
+    }
+}
+
+/// Long-lived key credentials are issued and checked under.
+pub struct CredentialKey {
+    seed: Zeroizing<[u8; 32]>,
+}
+
+impl core::fmt::Debug for CredentialKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CredentialKey")
+            .field("seed", &"<redacted>")
+            .finish()
+    }
+}
+
+impl CredentialKey {
+    /// The credential key at `path` below `master_seed`.
+    pub fn from_path(master_seed: &[u8], path: &DerivationPath) -> Result<Self> {
+        let node = ExtendedSeed::from_path(master_seed, path)?;
+        Ok(Self {
+            seed: Zeroizing::new(*node.seed()),
+        })
+    }
+
+    /// Seed for one validity range and holder: proofs under it are bound to
+    /// both.
+    fn range_seed(
+        &self,
+        validity: &CredentialValidity,
+        holder: &XmssPublicKey,
+    ) -> Zeroizing<[u8; 32]> {
+        let mut data = Zeroizing::new([0u8; 32 + 16 + 65]);
+        data[..32].copy_from_slice(&*self.seed);
+        data[32..48].copy_from_slice(&validity.to_bytes());
+        data[48..].copy_from_slice(&holder.to_bytes());
+        Zeroizing::new(poseidon_hash(&*data, DOMAIN_CREDENTIAL_RANGE))
+    }
+
+    /// Prover for credentials issued to `holder`, valid over `validity`. Its
+    /// proofs carry `valid_from` as their epoch.
+    pub fn prover(
+        &self,
+        validity: &CredentialValidity,
+        holder: &XmssPublicKey,
+    ) -> Result<IntegratedProver> {
+        IntegratedProver::new(&*self.range_seed(validity, holder), validity.valid_from)
+    }
+
+    fn verifier(
+        &self,
+        validity: &CredentialValidity,
+        holder: &XmssPublicKey,
+    ) -> Result<IntegratedVerifier> {
+        IntegratedVerifier::new(&*self.range_seed(validity, holder), validity.valid_from)
+    }
+}
+
+/// An issued credential: the proof plus the range and holder it is bound to.
+#[derive(Debug)]
+pub struct Credential {
+    pub proof: IntegratedProof,
+    pub validity: CredentialValidity,
+    pub holder: XmssPublicKey,
+}
+
+impl Credential {
+    pub fn new(
+        proof: IntegratedProof,
+        validity: CredentialValidity,
+        holder: XmssPublicKey,
+    ) -> Self {
+        Self {
+            proof,
+            validity,
+            holder,
+        }
+    }
+
+    /// Present the credential in the epoch `session` params belong to,
+    /// spending one of the holder's one-time signing keys. Fails if `signer`
+    /// is not the holder's.
+    pub fn present<'a, S: SignerStateStore>(
+        &'a self,
+        session: &WarpingParams,
+        signer: &mut XmssSigner,
+        store: &mut S,
+    ) -> Result<CredentialPresentation<'a>> {
+        if !public_keys_match(signer.public_key(), &self.holder) {
+            return Err(ZKMTDError::ConfigurationError {
+                reason: "Signer is not the credential holder".into(),
+            });
+        }
+        let signature = signer.sign_presentation(&presentation_binding(self, session), store)?;
+        Ok(CredentialPresentation {
+            credential: self,
+            epoch: session.epoch,
+            signature,
+        })
+    }
+}
+
+/// A credential signed by its holder for one presentation epoch's MTD params.
+#[derive(Debug, Clone)]
+pub struct CredentialPresentation<'a> {
+    pub credential: &'a Credential,
+    pub epoch: Epoch,
+    pub signature: XmssSignature,
+}
+
+fn presentation_binding(credential: &Credential, session: &WarpingParams) -> [u8; 32] {
+    let mut data = [0u8; 32 + 16 + 65 + 104];
+    data[..32].copy_from_slice(&credential.proof.binding_hash);
+    data[32..48].copy_from_slice(&credential.validity.to_bytes());
+    data[48..113].copy_from_slice(&credential.holder.to_bytes());
+    data[113..].copy_from_slice(&session.to_bytes());
+    poseidon_hash(&data, DOMAIN_CREDENTIAL_PRESENTATION)
+}
+
+/// Verifies presentations in the current epoch against a credential key.
+#[derive(Debug)]
+pub struct CredentialVerifier {
+    key: CredentialKey,
+    session: WarpingParams,
+}
+
+impl CredentialVerifier {
+    /// `session` are the current epoch's MTD params (e.g. from an
+    /// `MTDManager` sharing the holder's seed).
+    pub fn new(key: CredentialKey, session: WarpingParams) -> Self {
+        Self { key, session }
+    }
+
+    pub fn current_epoch(&self) -> Epoch {
+        self.session.epoch
+    }
+
+    /// Move to a new epoch's session params.
+    pub fn set_session(&mut self, session: WarpingParams) {
+        self.session = session;
+    }
+
+    /// Rejects with [`ZKMTDError::InvalidEpoch`] if the current epoch lies
+    /// outside the credential's range; `Ok(false)` for a presentation from
+    /// another epoch, one not signed by the holder, or a credential that does
+    /// not verify.
+    pub fn verify(&self, presentation: &CredentialPresentation<'_>) -> Result<bool> {
+        let credential = presentation.credential;
+        let current = self.session.epoch;
+        if !credential.validity.contains(current) {
+            return Err(ZKMTDError::InvalidEpoch {
+                current: current.value(),
+                reason: alloc::format!(
+                    "Credential valid for epochs {}..={}",
+                    credential.validity.valid_from.value(),
+                    credential.validity.valid_until.value()
+                ),
+            });
+        }
+        if presentation.epoch != current {
+            return Ok(false);
+        }
+        let binding = presentation_binding(credential, &self.session);
+        if !credential
+            .holder
+            .verify_presentation(&binding, &presentation.signature)
+        {
+            return Ok(false);
+        }
+
+        self.key
+            .verifier(&credential.validity, &credential.holder)?
+            .verify(&credential.proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtd::MTDManager;
+    use crate::signature::MemoryStateStore;
+    use crate::utils::constants::MIN_XMSS_HEIGHT;
+
+    const MASTER: &[u8] = b"credential-master-seed";
+    const SESSION_SEED: &[u8] = b"session-seed";
+
+    fn key() -> CredentialKey {
+        CredentialKey::from_path(MASTER, &DerivationPath::parse("m/credentials/kyc").unwrap())
+            .unwrap()
+    }
+
+    fn holder(seed: u8) -> XmssSigner {
+        XmssSigner::from_seeds(&[seed; 32], [seed ^ 0xFF; 32], MIN_XMSS_HEIGHT + 2, 0).unwrap()
+    }
+
+    fn issue(validity: CredentialValidity, holder: &XmssSigner) -> Credential {
+        let proof = key()
+            .prover(&validity, holder.public_key())
+            .unwrap()
+            .prove_range(720, 18, [7u8; 32])
+            .unwrap();
+        Credential::new(proof, validity, *holder.public_key())
+    }
+
+    fn session(epoch: u64) -> WarpingParams {
+        MTDManager::with_epoch(SESSION_SEED, Epoch::new(epoch))
+            .unwrap()
+            .current_params()
+            .clone()
+    }
+
+    #[test]
+    fn test_credential_valid_across_range() {
+        let validity = CredentialValidity::for_epochs(Epoch::new(1000), 720).unwrap();
+        assert_eq!(validity.valid_until(), Epoch::new(1719));
+        let mut signer = holder(1);
+        let mut store = MemoryStateStore::new();
+        let credential = issue(validity, &signer);
+
+        for epoch in [1000, 1001, 1500, 1719] {
+            let verifier = CredentialVerifier::new(key(), session(epoch));
+            let presentation = credential
+                .present(&session(epoch), &mut signer, &mut store)
+                .unwrap();
+            assert!(verifier.verify(&presentation).unwrap());
+        }
+        for epoch in [999, 1720] {
+            let verifier = CredentialVerifier::new(key(), session(epoch));
+            let presentation = credential
+                .present(&session(epoch), &mut signer, &mut store)
+                .unwrap();
+            assert!(verifier.verify(&presentation).is_err());
+        }
+    }
+
+    #[test]
+    fn test_presentation_replay_and_tampering() {
+        let validity = CredentialValidity::for_epochs(Epoch::new(1000), 720).unwrap();
+        let mut signer = holder(1);
+        let mut store = MemoryStateStore::new();
+        let credential = issue(validity, &signer);
+        let mut verifier = CredentialVerifier::new(key(), session(1100));
+
+        // A presentation from the previous epoch is not accepted now
+        let old = credential
+            .present(&session(1099), &mut signer, &mut store)
+            .unwrap();
+        assert!(!verifier.verify(&old).unwrap());
+        let mut relabelled = old;
+        relabelled.epoch = Epoch::new(1100);
+        assert!(!verifier.verify(&relabelled).unwrap());
+
+        // Stretching the validity range breaks the credential proof
+        let stretched = Credential::new(
+            key()
+                .prover(&validity, signer.public_key())
+                .unwrap()
+                .prove_range(720, 18, [7u8; 32])
+                .unwrap(),
+            CredentialValidity::for_epochs(Epoch::new(1000), 2000).unwrap(),
+            *signer.public_key(),
+        );
+        verifier.set_session(session(1800));
+        let presentation = stretched
+            .present(&session(1800), &mut signer, &mut store)
+            .unwrap();
+        assert!(!verifier.verify(&presentation).unwrap());
+
+        // Another credential key does not verify it
+        let other =
+            CredentialKey::from_path(MASTER, &DerivationPath::parse("m/credentials/aml").unwrap())
+                .unwrap();
+        let verifier = CredentialVerifier::new(other, session(1100));
+        let presentation = credential
+            .present(&session(1100), &mut signer, &mut store)
+            .unwrap();
+        assert!(!verifier.verify(&presentation).unwrap());
+    }
+
+    #[test]
+    fn test_captured_credential_cannot_be_presented_by_third_party() {
+        let validity = CredentialValidity::for_epochs(Epoch::new(1000), 720).unwrap();
+        let mut signer = holder(1);
+        let mut store = MemoryStateStore::new();
+        let credential = issue(validity, &signer);
+        let verifier = CredentialVerifier::new(key(), session(1101));
+
+        // The attacker captured the credential and a presentation from 1100,
+        // and knows the public session params of 1101
+        let captured = credential
+            .present(&session(1100), &mut signer, &mut store)
+            .unwrap();
+        let mut replayed = captured.clone();
+        replayed.epoch = Epoch::new(1101);
+        assert!(!verifier.verify(&replayed).unwrap());
+
+        // Their own key cannot present the credential...
+        let mut attacker = holder(2);
+        let mut attacker_store = MemoryStateStore::new();
+        assert!(credential
+            .present(&session(1101), &mut attacker, &mut attacker_store)
+            .is_err());
+
+        // ...nor can it be swapped in as the holder: the proof is bound to the
+        // original holder's key
+        let stolen = Credential::new(
+            IntegratedProof::from_bytes(&credential.proof.to_bytes().unwrap()).unwrap(),
+            validity,
+            *attacker.public_key(),
+        );
+        let presentation = stolen
+            .present(&session(1101), &mut attacker, &mut attacker_store)
+            .unwrap();
+        assert!(!verifier.verify(&presentation).unwrap());
+
+        // The holder can still present it
+        let presentation = credential
+            .present(&session(1101), &mut signer, &mut store)
+            .unwrap();
+        assert!(verifier.verify(&presentation).unwrap());
+    }
+
+    #[test]
+    fn test_credential_validity_bounds() {
+        assert!(CredentialValidity::new(Epoch::new(10), Epoch::new(9)).is_err());
+        assert!(CredentialValidity::for_epochs(Epoch::new(10), 0).is_err());
+        assert!(CredentialValidity::for_epochs(Epoch::new(10), MAX_CREDENTIAL_EPOCHS).is_ok());
+        assert!(CredentialValidity::for_epochs(Epoch::new(10), MAX_CREDENTIAL_EPOCHS + 1).is_err());
+    }
+}
@@ -3,27 +3,42 @@
 //! All proofs commit public values with a salt (privacy-by-default).
 //! No standard/privacy mode distinction — every proof is privacy-preserving.
 
-use crate::core::errors::Result;
+use crate::batching::nullifier::{nullifier_from_commitments, SmtProof};
+use crate::batching::report::ProofStatus;
+use crate::core::errors::{Result, ZKMTDError};
 use crate::core::types::CommittedPublicInputs;
-use crate::mtd::{Epoch, MTDManager, WarpingParams};
+use crate::mtd::{
+    seed_fingerprint, BeaconEntropy, Clock, DerivationPath, Epoch, EpochAnnouncement, ExtendedSeed,
+    MTDManager, RevocationList, ScheduleCommitment, TenantContext, WarpingParams,
+};
 use crate::stark::air::SimpleAir;
+use crate::stark::layout::AirLayout;
 use crate::stark::real_stark::{RealProof, RealStarkProver, RealStarkVerifier};
-use crate::utils::constants::DOMAIN_BINDING;
+use crate::utils::constants::{
+    DEFAULT_PROOF_MAX_AGE_SECS, DOMAIN_BINDING, TIMESTAMP_TOLERANCE_SECS,
+};
 use crate::utils::hash::{constant_time_eq_fixed, poseidon_hash};
 use zeroize::Zeroize;
 
 #[cfg(feature = "alloc")]
-use alloc::vec::Vec;
+use alloc::{boxed::Box, vec::Vec};
 
 /// Compute binding hash for a proof with committed public values.
 /// Single implementation shared by prover and verifier — no duplication.
 ///
 /// SECURITY: Includes air_type to prevent AIR type confusion attacks where
 /// a proof generated for one AIR type is presented as another type.
+/// Tenant-scoped provers/verifiers also bind their tenant context, so a proof
+/// never verifies under another tenant's verifier. A prover-claimed timestamp
+/// is bound behind a marker byte. The binding hash is unkeyed, so anyone can
+/// recompute it; the timestamp is also observed into the STARK transcript,
+/// which is what stops it from being altered or stripped.
 fn compute_binding_hash(
     proof: &RealProof,
     params: &WarpingParams,
     committed: &CommittedPublicInputs,
+    tenant: Option<&TenantContext>,
+    timestamp: Option<u64>,
 ) -> [u8; 32] {
     let mut data = Vec::new();
     // Include AIR type as first element to prevent type confusion attacks
@@ -37,13 +52,52 @@ fn compute_binding_hash(
     data.extend_from_slice(&params.domain_separator);
     data.extend_from_slice(&params.fri_seed);
     data.extend_from_slice(&params.salt);
+    if let Some(tenant) = tenant {
+        data.extend_from_slice(&tenant.to_bytes());
+    }
+    if let Some(timestamp) = timestamp {
+        data.push(b'T');
+        data.extend_from_slice(&timestamp.to_le_bytes());
+    }
     poseidon_hash(&data, DOMAIN_BINDING)
 }
 
-#[derive(Debug)]
+/// Failed check: the status, and the error `verify` returns for it if any.
+type CheckResult = core::result::Result<(), (ProofStatus, Option<ZKMTDError>)>;
+
+/// Accepted age of a proof's claimed timestamp, relative to the verifier's clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreshnessWindow {
+    /// Oldest accepted timestamp, in seconds before now.
+    pub max_age_secs: u64,
+    /// Furthest accepted timestamp ahead of now (prover clock drift).
+    pub max_future_skew_secs: u64,
+}
+
+impl Default for FreshnessWindow {
+    fn default() -> Self {
+        Self {
+            max_age_secs: DEFAULT_PROOF_MAX_AGE_SECS,
+            max_future_skew_secs: TIMESTAMP_TOLERANCE_SECS,
+        }
+    }
+}
+
 pub struct IntegratedProver {
     mtd_manager: MTDManager,
     stark_prover: RealStarkProver,
+    /// Stamps every proof with the current time when set.
+    clock: Option<Box<dyn Clock + Send + Sync>>,
+}
+
+impl core::fmt::Debug for IntegratedProver {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IntegratedProver")
+            .field("mtd_manager", &self.mtd_manager)
+            .field("stark_prover", &self.stark_prover)
+            .field("timestamped", &self.clock.is_some())
+            .finish()
+    }
 }
 
 impl IntegratedProver {
@@ -55,6 +109,7 @@ impl IntegratedProver {
         Ok(Self {
             mtd_manager,
             stark_prover,
+            clock: None,
         })
     }
 
@@ -69,9 +124,45 @@ impl IntegratedProver {
         Ok(Self {
             mtd_manager,
             stark_prover,
+            clock: None,
+        })
+    }
+
+    /// Prover whose params and binding hash are scoped to `tenant`.
+    pub fn for_tenant(seed: &[u8], epoch: Epoch, tenant: TenantContext) -> Result<Self> {
+        let mtd_manager = MTDManager::with_epoch_for_tenant(seed, epoch, tenant)?;
+        let mut stark_prover = RealStarkProver::new(SimpleAir::fibonacci())?;
+        stark_prover.set_mtd_seed(mtd_manager.current_params().fri_seed);
+        Ok(Self {
+            mtd_manager,
+            stark_prover,
+            clock: None,
+        })
+    }
+
+    /// Prover for the seed at `path` below `master_seed` (see `mtd::keytree`).
+    pub fn from_key_path(master_seed: &[u8], path: &DerivationPath, epoch: Epoch) -> Result<Self> {
+        let node = ExtendedSeed::from_path(master_seed, path)?;
+        Self::new(node.seed(), epoch)
+    }
+
+    /// Prover for `beacon`'s epoch, with params bound to the verified beacon
+    /// round (see `MTDManager::from_beacon`).
+    pub fn from_beacon(seed: &[u8], beacon: &BeaconEntropy) -> Result<Self> {
+        let mtd_manager = MTDManager::from_beacon(seed, beacon)?;
+        let mut stark_prover = RealStarkProver::new(SimpleAir::fibonacci())?;
+        stark_prover.set_mtd_seed(mtd_manager.current_params().fri_seed);
+        Ok(Self {
+            mtd_manager,
+            stark_prover,
+            clock: None,
         })
     }
 
+    pub fn tenant(&self) -> Option<&TenantContext> {
+        self.mtd_manager.tenant()
+    }
+
     pub fn current_epoch(&self) -> Epoch {
         self.mtd_manager.current_epoch()
     }
@@ -83,24 +174,72 @@ impl IntegratedProver {
         // H-3: re-bind the new epoch's MTD seed into the STARK transcript.
         self.stark_prover
             .set_mtd_seed(self.mtd_manager.current_params().fri_seed);
+        if self.stark_prover.layout().is_some() {
+            self.stark_prover.set_layout(Some(AirLayout::from_params(
+                self.mtd_manager.current_params(),
+            )));
+        }
         Ok(())
     }
 
-    /// Generate a proof with committed public values.
-    /// All proofs are privacy-preserving — public_values are committed with the given salt.
-    pub fn prove_fibonacci(
+    /// Opt in to epoch-warped circuit layouts (see `stark::layout`): the
+    /// column order, FRI query count and padding of every proof then follow
+    /// the current epoch's params. Verifiers must opt in as well.
+    pub fn with_warped_layout(mut self) -> Self {
+        self.stark_prover.set_layout(Some(AirLayout::from_params(
+            self.mtd_manager.current_params(),
+        )));
+        self
+    }
+
+    pub fn layout(&self) -> Option<&AirLayout> {
+        self.stark_prover.layout()
+    }
+
+    /// Stamp every proof with `clock`'s current time, bound into the STARK
+    /// transcript and the binding hash. The timestamp is the prover's claim;
+    /// verifiers bound it with [`IntegratedVerifier::with_freshness`].
+    pub fn with_timestamp_clock<C: Clock + Send + Sync + 'static>(mut self, clock: C) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Read the clock before proving, so the claimed timestamp is observed
+    /// into the STARK transcript as well as bound into the binding hash.
+    fn prove_with<F>(&self, prove: F, pv_salt: [u8; 32]) -> Result<IntegratedProof>
+    where
+        F: FnOnce(&RealStarkProver) -> Result<RealProof>,
+    {
+        let timestamp = self.clock.as_ref().map(|c| c.now_secs()).transpose()?;
+        let stark_proof = match timestamp {
+            Some(_) => {
+                let mut stark_prover = self.stark_prover.clone();
+                stark_prover.set_timestamp(timestamp);
+                prove(&stark_prover)?
+            }
+            None => prove(&self.stark_prover)?,
+        };
+        self.finish(stark_proof, pv_salt, timestamp)
+    }
+
+    fn finish(
         &self,
-        num_rows: usize,
+        stark_proof: RealProof,
         pv_salt: [u8; 32],
+        timestamp: Option<u64>,
     ) -> Result<IntegratedProof> {
-        let stark_proof = self.stark_prover.prove_fibonacci(num_rows)?;
         let epoch = self.mtd_manager.current_epoch();
         let params = self.mtd_manager.current_params().clone();
 
         let committed_public_values =
             CommittedPublicInputs::commit(&stark_proof.public_values, &pv_salt);
-        let binding_hash =
-            compute_binding_hash(&stark_proof, &params, &committed_public_values);
+        let binding_hash = compute_binding_hash(
+            &stark_proof,
+            &params,
+            &committed_public_values,
+            self.tenant(),
+            timestamp,
+        );
 
         Ok(IntegratedProof {
             stark_proof,
@@ -109,9 +248,19 @@ impl IntegratedProver {
             binding_hash,
             committed_public_values,
             pv_salt: Some(pv_salt),
+            timestamp,
         })
     }
 
+    /// Generate a proof with committed public values.
+    /// All proofs are privacy-preserving — public_values are committed with the given salt.
+    pub fn prove_fibonacci(&self, num_rows: usize, pv_salt: [u8; 32]) -> Result<IntegratedProof> {
+        self.prove_with(
+            |stark_prover| stark_prover.prove_fibonacci(num_rows),
+            pv_salt,
+        )
+    }
+
     /// Prove `a[i] + b[i] = c[i]` for all rows, with committed public values.
     pub fn prove_sum(
         &self,
@@ -119,22 +268,10 @@ impl IntegratedProver {
         b_values: &[u64],
         pv_salt: [u8; 32],
     ) -> Result<IntegratedProof> {
-        let stark_proof = self.stark_prover.prove_sum(a_values, b_values)?;
-        let epoch = self.mtd_manager.current_epoch();
-        let params = self.mtd_manager.current_params().clone();
-        let committed_public_values =
-            CommittedPublicInputs::commit(&stark_proof.public_values, &pv_salt);
-        let binding_hash =
-            compute_binding_hash(&stark_proof, &params, &committed_public_values);
-
-        Ok(IntegratedProof {
-            stark_proof,
-            epoch,
-            params,
-            binding_hash,
-            committed_public_values,
-            pv_salt: Some(pv_salt),
-        })
+        self.prove_with(
+            |stark_prover| stark_prover.prove_sum(a_values, b_values),
+            pv_salt,
+        )
     }
 
     /// Prove `a[i] * b[i] = c[i]` for all rows, with committed public values.
@@ -144,22 +281,10 @@ impl IntegratedProver {
         b_values: &[u64],
         pv_salt: [u8; 32],
     ) -> Result<IntegratedProof> {
-        let stark_proof = self.stark_prover.prove_multiplication(a_values, b_values)?;
-        let epoch = self.mtd_manager.current_epoch();
-        let params = self.mtd_manager.current_params().clone();
-        let committed_public_values =
-            CommittedPublicInputs::commit(&stark_proof.public_values, &pv_salt);
-        let binding_hash =
-            compute_binding_hash(&stark_proof, &params, &committed_public_values);
-
-        Ok(IntegratedProof {
-            stark_proof,
-            epoch,
-            params,
-            binding_hash,
-            committed_public_values,
-            pv_salt: Some(pv_salt),
-        })
+        self.prove_with(
+            |stark_prover| stark_prover.prove_multiplication(a_values, b_values),
+            pv_salt,
+        )
     }
 
     /// Prove value >= threshold via bit decomposition, with committed public values.
@@ -169,22 +294,10 @@ impl IntegratedProver {
         threshold: u64,
         pv_salt: [u8; 32],
     ) -> Result<IntegratedProof> {
-        let stark_proof = self.stark_prover.prove_range(value, threshold)?;
-        let epoch = self.mtd_manager.current_epoch();
-        let params = self.mtd_manager.current_params().clone();
-        let committed_public_values =
-            CommittedPublicInputs::commit(&stark_proof.public_values, &pv_salt);
-        let binding_hash =
-            compute_binding_hash(&stark_proof, &params, &committed_public_values);
-
-        Ok(IntegratedProof {
-            stark_proof,
-            epoch,
-            params,
-            binding_hash,
-            committed_public_values,
-            pv_salt: Some(pv_salt),
-        })
+        self.prove_with(
+            |stark_prover| stark_prover.prove_range(value, threshold),
+            pv_salt,
+        )
     }
 
     pub fn get_verifier(&self) -> IntegratedVerifier {
@@ -192,15 +305,37 @@ impl IntegratedProver {
             stark_verifier: self.stark_prover.get_verifier(),
             current_epoch: self.mtd_manager.current_epoch(),
             current_params: self.mtd_manager.current_params().clone(),
+            tenant: self.tenant().copied(),
+            seed_fingerprint: Some(self.mtd_manager.seed_fingerprint()),
+            revocations: None,
+            freshness: None,
         }
     }
 }
 
-#[derive(Debug)]
 pub struct IntegratedVerifier {
     stark_verifier: RealStarkVerifier,
     current_epoch: Epoch,
     current_params: WarpingParams,
+    tenant: Option<TenantContext>,
+    /// Unknown for seedless verifiers, which can only check revoked epochs.
+    seed_fingerprint: Option<u64>,
+    revocations: Option<RevocationList>,
+    freshness: Option<(FreshnessWindow, Box<dyn Clock + Send + Sync>)>,
+}
+
+impl core::fmt::Debug for IntegratedVerifier {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IntegratedVerifier")
+            .field("stark_verifier", &self.stark_verifier)
+            .field("current_epoch", &self.current_epoch)
+            .field("current_params", &self.current_params)
+            .field("tenant", &self.tenant)
+            .field("seed_fingerprint", &self.seed_fingerprint)
+            .field("revocations", &self.revocations)
+            .field("freshness", &self.freshness.as_ref().map(|(w, _)| w))
+            .finish()
+    }
 }
 
 impl IntegratedVerifier {
@@ -213,9 +348,79 @@ impl IntegratedVerifier {
             stark_verifier,
             current_epoch: mtd_manager.current_epoch(),
             current_params: mtd_manager.current_params().clone(),
+            tenant: None,
+            seed_fingerprint: Some(seed_fingerprint(seed)),
+            revocations: None,
+            freshness: None,
         })
     }
 
+    /// Verifier for proofs from [`IntegratedProver::from_key_path`].
+    pub fn from_key_path(master_seed: &[u8], path: &DerivationPath, epoch: Epoch) -> Result<Self> {
+        let node = ExtendedSeed::from_path(master_seed, path)?;
+        Self::new(node.seed(), epoch)
+    }
+
+    /// Verifier for proofs from [`IntegratedProver::from_beacon`]; `beacon`
+    /// is fetched and chain-verified by the verifier itself.
+    pub fn from_beacon(seed: &[u8], beacon: &BeaconEntropy) -> Result<Self> {
+        let mtd_manager = MTDManager::from_beacon(seed, beacon)?;
+        let mut verifier = Self::from_params(mtd_manager.current_params().clone())?;
+        // Revoking the seed itself also covers its beacon-bound epochs
+        verifier.seed_fingerprint = Some(seed_fingerprint(seed));
+        Ok(verifier)
+    }
+
+    /// Verifier for proofs from [`IntegratedProver::for_tenant`].
+    pub fn for_tenant(seed: &[u8], epoch: Epoch, tenant: TenantContext) -> Result<Self> {
+        let mtd_manager = MTDManager::with_epoch_for_tenant(seed, epoch, tenant)?;
+        let mut stark_verifier = RealStarkVerifier::new(SimpleAir::fibonacci())?;
+        stark_verifier.set_mtd_seed(mtd_manager.current_params().fri_seed);
+        Ok(Self {
+            stark_verifier,
+            current_epoch: mtd_manager.current_epoch(),
+            current_params: mtd_manager.current_params().clone(),
+            tenant: Some(tenant),
+            seed_fingerprint: Some(seed_fingerprint(seed)),
+            revocations: None,
+            freshness: None,
+        })
+    }
+
+    /// Seedless verifier for one epoch, built from params obtained out of band.
+    /// Prefer [`from_announcement`](Self::from_announcement), which also
+    /// authenticates the params.
+    pub fn from_params(params: WarpingParams) -> Result<Self> {
+        let mut stark_verifier = RealStarkVerifier::new(SimpleAir::fibonacci())?;
+        stark_verifier.set_mtd_seed(params.fri_seed);
+        Ok(Self {
+            stark_verifier,
+            current_epoch: params.epoch,
+            current_params: params,
+            tenant: None,
+            seed_fingerprint: None,
+            revocations: None,
+            freshness: None,
+        })
+    }
+
+    /// Seedless verifier for an announced epoch. The announcement must open
+    /// against the TRUSTED schedule commitment published by the seed holder.
+    pub fn from_announcement(
+        announcement: &EpochAnnouncement,
+        commitment: &ScheduleCommitment,
+    ) -> Result<Self> {
+        if !announcement.verify(commitment) {
+            return Err(ZKMTDError::VerificationFailed {
+                reason: alloc::format!(
+                    "Announcement for {} does not match the schedule commitment",
+                    announcement.epoch()
+                ),
+            });
+        }
+        Self::from_params(announcement.params.clone())
+    }
+
     pub fn current_epoch(&self) -> Epoch {
         self.current_epoch
     }
@@ -223,23 +428,193 @@ impl IntegratedVerifier {
         &self.current_params
     }
 
-    pub fn verify(&self, proof: &IntegratedProof) -> Result<bool> {
+    /// Reject proofs from revoked epochs, or every proof if this verifier's
+    /// seed is revoked, with [`ZKMTDError::Revoked`]. A seedless verifier
+    /// ([`from_params`](Self::from_params)) cannot tell its seed, so it
+    /// rejects every proof once the list revokes any seed. The list's
+    /// signature must be checked by the caller before installing it.
+    pub fn with_revocation_list(mut self, list: RevocationList) -> Self {
+        self.revocations = Some(list);
+        self
+    }
+
+    pub fn revocation_list(&self) -> Option<&RevocationList> {
+        self.revocations.as_ref()
+    }
+
+    /// Verify proofs from an [`IntegratedProver::with_warped_layout`] prover.
+    pub fn with_warped_layout(mut self) -> Self {
+        self.stark_verifier
+            .set_layout(Some(AirLayout::from_params(&self.current_params)));
+        self
+    }
+
+    pub fn layout(&self) -> Option<&AirLayout> {
+        self.stark_verifier.layout()
+    }
+
+    /// Require a timestamp on every proof, within `window` of `clock`'s time.
+    /// Replays are then bounded in wall-clock time, not only by epoch.
+    pub fn with_freshness<C: Clock + Send + Sync + 'static>(
+        mut self,
+        window: FreshnessWindow,
+        clock: C,
+    ) -> Self {
+        self.freshness = Some((window, Box::new(clock)));
+        self
+    }
+
+    pub fn freshness_window(&self) -> Option<&FreshnessWindow> {
+        self.freshness.as_ref().map(|(window, _)| window)
+    }
+
+    /// A claimed timestamp must fall inside its proof's epoch (with the usual
+    /// tolerance); with a freshness policy it must also be present and recent.
+    fn check_timestamp(&self, proof: &IntegratedProof) -> Result<bool> {
+        if let Some(ts) = proof.timestamp {
+            let lower = proof
+                .epoch
+                .start_timestamp()
+                .saturating_sub(TIMESTAMP_TOLERANCE_SECS);
+            let upper = proof
+                .epoch
+                .end_timestamp()
+                .saturating_add(TIMESTAMP_TOLERANCE_SECS);
+            if ts < lower || ts > upper {
+                return Ok(false);
+            }
+        }
+
+        let Some((window, clock)) = &self.freshness else {
+            return Ok(true);
+        };
+        let ts = proof
+            .timestamp
+            .ok_or_else(|| ZKMTDError::VerificationFailed {
+                reason: "Proof carries no timestamp but freshness is required".into(),
+            })?;
+        let now = clock.now_secs()?;
+        if ts < now.saturating_sub(window.max_age_secs) {
+            return Err(ZKMTDError::VerificationFailed {
+                reason: alloc::format!(
+                    "Proof timestamp {} is older than {}s (now {})",
+                    ts,
+                    window.max_age_secs,
+                    now
+                ),
+            });
+        }
+        if ts > now.saturating_add(window.max_future_skew_secs) {
+            return Err(ZKMTDError::VerificationFailed {
+                reason: alloc::format!(
+                    "Proof timestamp {} is more than {}s in the future (now {})",
+                    ts,
+                    window.max_future_skew_secs,
+                    now
+                ),
+            });
+        }
+        Ok(true)
+    }
+
+    fn check_revocation(&self, epoch: Epoch) -> Result<()> {
+        if let Some(ref list) = self.revocations {
+            if let Some(reason) = list.check(epoch, self.seed_fingerprint) {
+                return Err(reason.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// First failing check, with the error [`verify`](Self::verify)
+    /// surfaces for it, if any.
+    fn check(&self, proof: &IntegratedProof) -> CheckResult {
+        if let Err(e) = self.check_revocation(proof.epoch) {
+            return Err((ProofStatus::Revoked, Some(e)));
+        }
         if proof.epoch != self.current_epoch {
-            return Ok(false);
+            return Err((
+                ProofStatus::EpochMismatch {
+                    expected: self.current_epoch.value(),
+                    got: proof.epoch.value(),
+                },
+                None,
+            ));
+        }
+        match self.check_timestamp(proof) {
+            Ok(true) => {}
+            Ok(false) => return Err((ProofStatus::TimestampRejected, None)),
+            Err(e) => return Err((ProofStatus::TimestampRejected, Some(e))),
         }
         if !self.verify_params_match(&proof.params) {
-            return Ok(false);
+            return Err((ProofStatus::ParamsMismatch, None));
         }
 
-        let expected_binding =
-            compute_binding_hash(&proof.stark_proof, &proof.params, &proof.committed_public_values);
+        let expected_binding = compute_binding_hash(
+            &proof.stark_proof,
+            &proof.params,
+            &proof.committed_public_values,
+            self.tenant.as_ref(),
+            proof.timestamp,
+        );
 
         // SECURITY: Use constant-time comparison to prevent timing side-channel attacks
         if !constant_time_eq_fixed(&proof.binding_hash, &expected_binding) {
-            return Ok(false);
+            return Err((ProofStatus::BindingMismatch, None));
+        }
+
+        let verified = match proof.timestamp {
+            Some(_) => {
+                let mut stark_verifier = self.stark_verifier.clone();
+                stark_verifier.set_timestamp(proof.timestamp);
+                stark_verifier.verify_by_type(&proof.stark_proof)
+            }
+            None => self.stark_verifier.verify_by_type(&proof.stark_proof),
+        };
+        match verified {
+            Ok(true) => Ok(()),
+            Ok(false) => Err((ProofStatus::StarkFailure, None)),
+            Err(e) => Err((ProofStatus::StarkFailure, Some(e))),
+        }
+    }
+
+    pub fn verify(&self, proof: &IntegratedProof) -> Result<bool> {
+        match self.check(proof) {
+            Ok(()) => Ok(true),
+            Err((_, Some(e))) => Err(e),
+            Err((_, None)) => Ok(false),
         }
+    }
 
-        self.stark_verifier.verify_by_type(&proof.stark_proof)
+    /// Like [`verify`](Self::verify), but names the first check that fails
+    /// instead of returning `false` or an error.
+    pub fn diagnose(&self, proof: &IntegratedProof) -> ProofStatus {
+        match self.check(proof) {
+            Ok(()) => ProofStatus::Valid,
+            Err((status, _)) => status,
+        }
+    }
+
+    /// [`verify`](Self::verify), first rejecting a spent nullifier: the
+    /// caller supplies the nullifier set's trusted root and a path for
+    /// `proof.nullifier()`. A membership path fails with
+    /// [`ZKMTDError::Replayed`]; a path that proves neither is `Ok(false)`.
+    pub fn verify_unspent(
+        &self,
+        proof: &IntegratedProof,
+        nullifier_root: &[u8; 32],
+        path: &SmtProof,
+    ) -> Result<bool> {
+        let nullifier = proof.nullifier()?;
+        if path.verify_membership(&nullifier, nullifier_root) {
+            return Err(ZKMTDError::Replayed {
+                reason: "Proof nullifier already spent".into(),
+            });
+        }
+        if !path.verify_non_membership(&nullifier, nullifier_root) {
+            return Ok(false);
+        }
+        self.verify(proof)
     }
 
     /// Verify a proof with the original public values and salt.
@@ -263,9 +638,13 @@ impl IntegratedVerifier {
         expected_epoch: Epoch,
         expected_params: &WarpingParams,
     ) -> Result<bool> {
+        self.check_revocation(expected_epoch)?;
         if proof.epoch != expected_epoch {
             return Ok(false);
         }
+        if !self.check_timestamp(proof)? {
+            return Ok(false);
+        }
         if proof.params.domain_separator != expected_params.domain_separator
             || proof.params.fri_seed != expected_params.fri_seed
             || proof.params.salt != expected_params.salt
@@ -273,15 +652,28 @@ impl IntegratedVerifier {
             return Ok(false);
         }
 
-        let expected_binding =
-            compute_binding_hash(&proof.stark_proof, &proof.params, &proof.committed_public_values);
+        let expected_binding = compute_binding_hash(
+            &proof.stark_proof,
+            &proof.params,
+            &proof.committed_public_values,
+            self.tenant.as_ref(),
+            proof.timestamp,
+        );
 
         // SECURITY: Use constant-time comparison to prevent timing side-channel attacks
         if !constant_time_eq_fixed(&proof.binding_hash, &expected_binding) {
             return Ok(false);
         }
 
-        self.stark_verifier.verify_by_type(&proof.stark_proof)
+        // H-3: the transcript is bound to the expected epoch's fri_seed, which
+        // need not be this verifier's own epoch.
+        let mut stark_verifier = self.stark_verifier.clone();
+        stark_verifier.set_mtd_seed(expected_params.fri_seed);
+        stark_verifier.set_timestamp(proof.timestamp);
+        if stark_verifier.layout().is_some() {
+            stark_verifier.set_layout(Some(AirLayout::from_params(expected_params)));
+        }
+        stark_verifier.verify_by_type(&proof.stark_proof)
     }
 
     fn verify_params_match(&self, proof_params: &WarpingParams) -> bool {
@@ -292,6 +684,12 @@ impl IntegratedVerifier {
     }
 }
 
+const PROOF_MAGIC: &[u8; 8] = b"ZKMTDIPF";
+const PROOF_FORMAT_VERSION: u8 = 1;
+/// magic (8) || version (1) || epoch (8) || params (104) || binding (32)
+/// || commitment (32) || value count (4)
+const PROOF_HEADER_LEN: usize = 189;
+
 pub struct IntegratedProof {
     pub stark_proof: RealProof,
     pub epoch: Epoch,
@@ -302,6 +700,8 @@ pub struct IntegratedProof {
     /// Salt used for commitment (erasable for GDPR compliance).
     /// Access via `erase_salt()` for secure deletion — do not set directly.
     pub(crate) pv_salt: Option<[u8; 32]>,
+    /// Prover-claimed creation time (Unix seconds), bound into `binding_hash`.
+    pub timestamp: Option<u64>,
 }
 
 impl core::fmt::Debug for IntegratedProof {
@@ -313,6 +713,7 @@ impl core::fmt::Debug for IntegratedProof {
             .field("binding_hash", &self.binding_hash)
             .field("committed_public_values", &self.committed_public_values)
             .field("pv_salt", &self.pv_salt.as_ref().map(|_| "<redacted>"))
+            .field("timestamp", &self.timestamp)
             .finish()
     }
 }
@@ -345,11 +746,94 @@ impl IntegratedProof {
     pub fn has_salt(&self) -> bool {
         self.pv_salt.is_some()
     }
+
+    /// Replay nullifier, derived from the STARK commitments and the epoch.
+    /// Both are fixed by the transcript, so re-committing the public values
+    /// or recomputing the binding hash leaves it unchanged. Proving the same
+    /// statement again in the same epoch (without a timestamp) yields the
+    /// same STARK, and therefore the same nullifier.
+    pub fn nullifier(&self) -> Result<[u8; 32]> {
+        let commitments = self.stark_proof.commitments_bytes()?;
+        Ok(nullifier_from_commitments(self.epoch.value(), &commitments))
+    }
+
+    /// Canonical encoding, e.g. for Merkle leaves. The salt is never
+    /// encoded; decoded proofs have none.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let stark = self.stark_proof.to_bytes()?;
+        let mut out = Vec::with_capacity(PROOF_HEADER_LEN + 9 + stark.len());
+        out.extend_from_slice(PROOF_MAGIC);
+        out.push(PROOF_FORMAT_VERSION);
+        out.extend_from_slice(&self.epoch.to_bytes());
+        out.extend_from_slice(&self.params.to_bytes());
+        out.extend_from_slice(&self.binding_hash);
+        out.extend_from_slice(&self.committed_public_values.commitment);
+        out.extend_from_slice(&self.committed_public_values.value_count.to_le_bytes());
+        match self.timestamp {
+            Some(ts) => {
+                out.push(1);
+                out.extend_from_slice(&ts.to_le_bytes());
+            }
+            None => out.push(0),
+        }
+        out.extend_from_slice(&stark);
+        Ok(out)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < PROOF_HEADER_LEN + 1 || &bytes[..8] != PROOF_MAGIC {
+            return Err(ZKMTDError::SerializationError {
+                reason: "Not an integrated proof".into(),
+            });
+        }
+        if bytes[8] != PROOF_FORMAT_VERSION {
+            return Err(ZKMTDError::SerializationError {
+                reason: alloc::format!("Unsupported integrated proof version {}", bytes[8]),
+            });
+        }
+        let read_32 = |at: usize| {
+            let mut buf = [0u8; 32];
+            buf.copy_from_slice(&bytes[at..at + 32]);
+            buf
+        };
+        let mut epoch = [0u8; 8];
+        epoch.copy_from_slice(&bytes[9..17]);
+        let mut value_count = [0u8; 4];
+        value_count.copy_from_slice(&bytes[185..189]);
+
+        let (timestamp, stark_at) = match bytes[PROOF_HEADER_LEN] {
+            0 => (None, PROOF_HEADER_LEN + 1),
+            1 if bytes.len() >= PROOF_HEADER_LEN + 9 => {
+                let mut ts = [0u8; 8];
+                ts.copy_from_slice(&bytes[PROOF_HEADER_LEN + 1..PROOF_HEADER_LEN + 9]);
+                (Some(u64::from_le_bytes(ts)), PROOF_HEADER_LEN + 9)
+            }
+            _ => {
+                return Err(ZKMTDError::SerializationError {
+                    reason: "Invalid integrated proof timestamp flag".into(),
+                })
+            }
+        };
+
+        Ok(Self {
+            stark_proof: RealProof::from_bytes(&bytes[stark_at..])?,
+            epoch: Epoch::from_bytes(epoch)?,
+            params: WarpingParams::from_bytes(&bytes[17..121])?,
+            binding_hash: read_32(121),
+            committed_public_values: CommittedPublicInputs {
+                commitment: read_32(153),
+                value_count: u32::from_le_bytes(value_count),
+            },
+            pv_salt: None,
+            timestamp,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::constants::EPOCH_DURATION_SECS;
     use alloc::vec;
 
     /// Helper: generate a deterministic test salt
@@ -717,6 +1201,49 @@ mod tests {
         assert!(is_valid, "Valid integrated range proof was rejected");
     }
 
+    #[test]
+    fn test_seedless_verifier_from_announcement() {
+        use crate::mtd::AnnouncementPublisher;
+
+        let seed = b"test-seed-announcement";
+        let publisher = AnnouncementPublisher::new(seed, Epoch::new(100), 4).unwrap();
+        let commitment = publisher.commitment();
+
+        let prover = IntegratedProver::new(seed, Epoch::new(102)).unwrap();
+        let proof = prover.prove_fibonacci(8, test_salt()).unwrap();
+
+        let announcement = publisher.announce(Epoch::new(102)).unwrap();
+        let verifier = IntegratedVerifier::from_announcement(&announcement, &commitment).unwrap();
+        assert!(
+            verifier.verify(&proof).unwrap(),
+            "Seedless verifier rejected valid proof"
+        );
+
+        // A different epoch's announcement must not verify this proof
+        let other = publisher.announce(Epoch::new(103)).unwrap();
+        let wrong = IntegratedVerifier::from_announcement(&other, &commitment).unwrap();
+        assert!(!wrong.verify(&proof).unwrap());
+
+        // Forged params are refused before any proof is checked
+        let mut forged = announcement.clone();
+        forged.params.salt[0] ^= 1;
+        assert!(IntegratedVerifier::from_announcement(&forged, &commitment).is_err());
+    }
+
+    #[test]
+    fn test_verify_with_params_other_epoch() {
+        let seed = b"test-seed-with-params";
+        let prover = IntegratedProver::new(seed, Epoch::new(100)).unwrap();
+        let proof = prover.prove_fibonacci(8, test_salt()).unwrap();
+
+        // Verifier sits at a later epoch but checks against the proof's announced params
+        let verifier = IntegratedVerifier::new(seed, Epoch::new(105)).unwrap();
+        let params = WarpingParams::generate(seed, Epoch::new(100)).unwrap();
+        assert!(verifier
+            .verify_with_params(&proof, Epoch::new(100), &params)
+            .unwrap());
+    }
+
     #[test]
     fn test_integrated_sum_wrong_epoch() {
         let seed = b"test-seed-sum-epoch";
@@ -730,4 +1257,356 @@ mod tests {
         let is_valid = wrong_verifier.verify(&proof).unwrap();
         assert!(!is_valid, "Sum proof from wrong epoch was accepted");
     }
+
+    #[test]
+    fn test_tenant_proofs_do_not_cross_verify() {
+        use crate::mtd::{ApplicationDomain, TenantId};
+
+        let seed = b"shared-master-seed";
+        let epoch = Epoch::new(100);
+        let a = TenantContext::new(
+            TenantId::from_name(b"tenant-a"),
+            ApplicationDomain::Identity,
+        );
+        let b = TenantContext::new(
+            TenantId::from_name(b"tenant-b"),
+            ApplicationDomain::Identity,
+        );
+
+        let prover_a = IntegratedProver::for_tenant(seed, epoch, a).unwrap();
+        let proof = prover_a.prove_fibonacci(8, test_salt()).unwrap();
+
+        assert!(prover_a.get_verifier().verify(&proof).unwrap());
+        assert!(IntegratedVerifier::for_tenant(seed, epoch, a)
+            .unwrap()
+            .verify(&proof)
+            .unwrap());
+        assert!(!IntegratedVerifier::for_tenant(seed, epoch, b)
+            .unwrap()
+            .verify(&proof)
+            .unwrap());
+        assert!(!IntegratedVerifier::new(seed, epoch)
+            .unwrap()
+            .verify(&proof)
+            .unwrap());
+
+        // Even with tenant A's params, tenant B's binding differs
+        let verifier_b = IntegratedVerifier::for_tenant(seed, epoch, b).unwrap();
+        assert!(!verifier_b
+            .verify_with_params(&proof, epoch, &proof.params)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_prover_verifier_from_key_path() {
+        let master = b"keytree-master";
+        let payments = DerivationPath::parse("m/prod'/eu/payments").unwrap();
+        let ledger = DerivationPath::parse("m/prod'/eu/ledger").unwrap();
+
+        let prover = IntegratedProver::from_key_path(master, &payments, Epoch::new(100)).unwrap();
+        let proof = prover.prove_fibonacci(8, test_salt()).unwrap();
+
+        let verifier =
+            IntegratedVerifier::from_key_path(master, &payments, Epoch::new(100)).unwrap();
+        assert!(verifier.verify(&proof).unwrap());
+
+        let other = IntegratedVerifier::from_key_path(master, &ledger, Epoch::new(100)).unwrap();
+        assert!(!other.verify(&proof).unwrap());
+    }
+
+    #[test]
+    fn test_revocation_list_rejects_with_distinct_error() {
+        let seed = b"test-seed-revocation";
+        let prover = IntegratedProver::new(seed, Epoch::new(5005)).unwrap();
+        let proof = prover.prove_fibonacci(8, test_salt()).unwrap();
+
+        let mut epochs = RevocationList::new(1);
+        epochs
+            .revoke_epochs(Epoch::new(5000), Epoch::new(5010))
+            .unwrap();
+        let verifier = prover.get_verifier().with_revocation_list(epochs.clone());
+        assert!(matches!(
+            verifier.verify(&proof),
+            Err(ZKMTDError::Revoked { .. })
+        ));
+        assert!(matches!(
+            verifier.verify_with_params(&proof, proof.epoch, &proof.params),
+            Err(ZKMTDError::Revoked { .. })
+        ));
+
+        // Seedless verifiers still honour revoked epochs
+        let seedless = IntegratedVerifier::from_params(proof.params.clone())
+            .unwrap()
+            .with_revocation_list(epochs);
+        assert!(matches!(
+            seedless.verify(&proof),
+            Err(ZKMTDError::Revoked { .. })
+        ));
+
+        // Revoked seed: every epoch is rejected; other seeds are unaffected
+        let mut seeds = RevocationList::new(2);
+        seeds.revoke_seed(seed_fingerprint(seed)).unwrap();
+        let verifier = IntegratedVerifier::new(seed, Epoch::new(5005))
+            .unwrap()
+            .with_revocation_list(seeds.clone());
+        assert!(matches!(
+            verifier.verify(&proof),
+            Err(ZKMTDError::Revoked { .. })
+        ));
+
+        let other = IntegratedProver::new(b"other-seed", Epoch::new(5005)).unwrap();
+        let other_proof = other.prove_fibonacci(8, test_salt()).unwrap();
+        let verifier = other.get_verifier().with_revocation_list(seeds.clone());
+        assert!(verifier.verify(&other_proof).unwrap());
+
+        // A seedless verifier cannot clear its seed against the list
+        let seedless = IntegratedVerifier::from_params(other_proof.params.clone())
+            .unwrap()
+            .with_revocation_list(seeds);
+        assert!(matches!(
+            seedless.verify(&other_proof),
+            Err(ZKMTDError::Revoked { .. })
+        ));
+    }
+
+    #[test]
+    fn test_warped_layout_prove_and_verify() {
+        let seed = b"test-seed-warped";
+        let prover = IntegratedProver::new(seed, Epoch::new(100))
+            .unwrap()
+            .with_warped_layout();
+        let verifier = IntegratedVerifier::new(seed, Epoch::new(100))
+            .unwrap()
+            .with_warped_layout();
+        assert_eq!(prover.layout(), verifier.layout());
+
+        let proofs = [
+            prover.prove_fibonacci(8, test_salt()).unwrap(),
+            prover
+                .prove_sum(&[1, 2, 3], &[4, 5, 6], test_salt())
+                .unwrap(),
+            prover
+                .prove_multiplication(&[2, 3], &[4, 5], test_salt())
+                .unwrap(),
+            prover.prove_range(50, 10, test_salt()).unwrap(),
+        ];
+        let plain = IntegratedVerifier::new(seed, Epoch::new(100)).unwrap();
+        for proof in &proofs {
+            assert!(verifier.verify(proof).unwrap());
+            assert!(prover.get_verifier().verify(proof).unwrap());
+            // Unwarped verifiers reject warped proofs, and vice versa
+            assert!(!plain.verify(proof).unwrap());
+        }
+        let plain_proof = IntegratedProver::new(seed, Epoch::new(100))
+            .unwrap()
+            .prove_fibonacci(8, test_salt())
+            .unwrap();
+        assert!(!verifier.verify(&plain_proof).unwrap());
+    }
+
+    #[test]
+    fn test_warped_layout_is_epoch_specific() {
+        let seed = b"test-seed-warped";
+        let mut prover = IntegratedProver::new(seed, Epoch::new(100))
+            .unwrap()
+            .with_warped_layout();
+        let layout_100 = *prover.layout().unwrap();
+        let proof_100 = prover.prove_sum(&[1, 2], &[3, 4], test_salt()).unwrap();
+
+        // Find an epoch whose layout differs in every dimension we can see
+        prover.advance_epoch().unwrap();
+        assert_ne!(prover.layout(), Some(&layout_100));
+        let proof_101 = prover.prove_sum(&[1, 2], &[3, 4], test_salt()).unwrap();
+        assert!(prover.get_verifier().verify(&proof_101).unwrap());
+
+        // Other epochs' layouts are derived from the expected params
+        let verifier = IntegratedVerifier::new(seed, Epoch::new(101))
+            .unwrap()
+            .with_warped_layout();
+        assert!(verifier
+            .verify_with_params(&proof_100, Epoch::new(100), &proof_100.params)
+            .unwrap());
+
+        // A proof re-labelled with another epoch's layout fails
+        let mut stark_verifier = RealStarkVerifier::new(SimpleAir::fibonacci()).unwrap();
+        stark_verifier.set_mtd_seed(proof_100.params.fri_seed);
+        stark_verifier.set_layout(Some(AirLayout::from_params(&proof_101.params)));
+        assert!(!stark_verifier
+            .verify_by_type(&proof_100.stark_proof)
+            .unwrap());
+        stark_verifier.set_layout(Some(layout_100));
+        assert!(stark_verifier
+            .verify_by_type(&proof_100.stark_proof)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_timestamped_proof_freshness() {
+        use crate::mtd::ManualClock;
+
+        let seed = b"test-seed-fresh";
+        let epoch = Epoch::new(100);
+        let t0 = epoch.start_timestamp() + 60;
+        let clock = ManualClock::new(t0);
+        let prover = IntegratedProver::new(seed, epoch)
+            .unwrap()
+            .with_timestamp_clock(clock.clone());
+        let proof = prover.prove_fibonacci(8, test_salt()).unwrap();
+        assert_eq!(proof.timestamp, Some(t0));
+
+        let window = FreshnessWindow {
+            max_age_secs: 120,
+            max_future_skew_secs: 30,
+        };
+        let verifier = IntegratedVerifier::new(seed, epoch)
+            .unwrap()
+            .with_freshness(window, clock.clone());
+        assert!(verifier.verify(&proof).unwrap());
+        // Verifiers without a freshness policy still accept it
+        assert!(prover.get_verifier().verify(&proof).unwrap());
+
+        // Too old, or too far ahead of the verifier's clock
+        clock.advance(121);
+        assert!(verifier.verify(&proof).is_err());
+        clock.set(t0 - 31);
+        assert!(verifier.verify(&proof).is_err());
+
+        // The timestamp cannot be altered or stripped, even by someone who
+        // recomputes the (unkeyed) binding hash to match
+        let rebind = |proof: &mut IntegratedProof, timestamp: Option<u64>| {
+            proof.timestamp = timestamp;
+            proof.binding_hash = compute_binding_hash(
+                &proof.stark_proof,
+                &proof.params,
+                &proof.committed_public_values,
+                None,
+                timestamp,
+            );
+        };
+        clock.set(t0 + 1000);
+        let mut restamped = prover.prove_fibonacci(8, test_salt()).unwrap();
+        rebind(&mut restamped, Some(t0 + 1000 - 60));
+        assert!(!verifier.verify(&restamped).unwrap());
+        assert!(!prover.get_verifier().verify(&restamped).unwrap());
+        let mut stripped = prover.prove_fibonacci(8, test_salt()).unwrap();
+        rebind(&mut stripped, None);
+        assert!(!prover.get_verifier().verify(&stripped).unwrap());
+
+        // An old proof refreshed with a recent timestamp is still rejected
+        let mut refreshed = proof;
+        rebind(&mut refreshed, Some(t0 + 1000));
+        assert_eq!(
+            verifier.check(&refreshed).unwrap_err().0,
+            ProofStatus::StarkFailure
+        );
+    }
+
+    #[test]
+    fn test_freshness_requires_timestamp_in_epoch() {
+        use crate::mtd::ManualClock;
+
+        let seed = b"test-seed-fresh";
+        let epoch = Epoch::new(100);
+        let clock = ManualClock::new(epoch.start_timestamp());
+        let verifier = IntegratedVerifier::new(seed, epoch)
+            .unwrap()
+            .with_freshness(FreshnessWindow::default(), clock.clone());
+
+        // Untimestamped proofs are rejected under a freshness policy
+        let plain = IntegratedProver::new(seed, epoch)
+            .unwrap()
+            .prove_fibonacci(8, test_salt())
+            .unwrap();
+        assert!(verifier.verify(&plain).is_err());
+
+        // A timestamp outside the proof's epoch window never verifies
+        let far = ManualClock::new(epoch.start_timestamp() + 10 * EPOCH_DURATION_SECS);
+        let skewed = IntegratedProver::new(seed, epoch)
+            .unwrap()
+            .with_timestamp_clock(far)
+            .prove_fibonacci(8, test_salt())
+            .unwrap();
+        assert!(!IntegratedVerifier::new(seed, epoch)
+            .unwrap()
+            .verify(&skewed)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_integrated_proof_bytes_roundtrip() {
+        let clock = crate::mtd::ManualClock::new(Epoch::new(100).start_timestamp() + 5);
+        let prover = IntegratedProver::new(b"test-seed-bytes", Epoch::new(100)).unwrap();
+        let verifier = prover.get_verifier();
+        let timestamped = IntegratedProver::new(b"test-seed-bytes", Epoch::new(100))
+            .unwrap()
+            .with_timestamp_clock(clock);
+
+        for proof in [
+            prover.prove_sum(&[1, 2], &[3, 4], test_salt()).unwrap(),
+            timestamped.prove_range(50, 10, test_salt()).unwrap(),
+        ] {
+            let bytes = proof.to_bytes().unwrap();
+            let decoded = IntegratedProof::from_bytes(&bytes).unwrap();
+            assert!(!decoded.has_salt());
+            assert_eq!(decoded.timestamp, proof.timestamp);
+            assert_eq!(decoded.to_bytes().unwrap(), bytes);
+            assert!(verifier.verify(&decoded).unwrap());
+
+            let mut trailing = bytes.clone();
+            trailing.push(0);
+            assert!(IntegratedProof::from_bytes(&trailing).is_err());
+            assert!(IntegratedProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        }
+        assert!(IntegratedProof::from_bytes(b"ZKMTDIPF").is_err());
+    }
+
+    #[test]
+    fn test_verify_unspent_rejects_replay() {
+        use crate::batching::nullifier::NullifierSet;
+
+        let prover = IntegratedProver::new(b"test-seed-nullifier", Epoch::new(100)).unwrap();
+        let verifier = prover.get_verifier();
+        let proof = prover.prove_fibonacci(8, test_salt()).unwrap();
+        let mut set = NullifierSet::new();
+
+        let root = set.root();
+        let path = set.prove(&proof.nullifier().unwrap());
+        assert!(verifier.verify_unspent(&proof, &root, &path).unwrap());
+        let next = path.root_after_insert(&proof.nullifier().unwrap(), &root);
+        set.insert(proof.nullifier().unwrap()).unwrap();
+        assert_eq!(next, Some(set.root()));
+
+        // Same proof again: its nullifier is now a member
+        let root = set.root();
+        let path = set.prove(&proof.nullifier().unwrap());
+        assert!(matches!(
+            verifier.verify_unspent(&proof, &root, &path),
+            Err(ZKMTDError::Replayed { .. })
+        ));
+        // Re-committing the public values under a new salt and recomputing
+        // the binding hash yields a valid proof, but the STARK, and so the
+        // nullifier, is unchanged
+        let mut recommitted = prover.prove_fibonacci(8, test_salt()).unwrap();
+        recommitted.committed_public_values =
+            CommittedPublicInputs::commit(&recommitted.stark_proof.public_values, &[9u8; 32]);
+        recommitted.binding_hash = compute_binding_hash(
+            &recommitted.stark_proof,
+            &recommitted.params,
+            &recommitted.committed_public_values,
+            None,
+            None,
+        );
+        assert!(verifier.verify(&recommitted).unwrap());
+        assert!(matches!(
+            verifier.verify_unspent(&recommitted, &root, &path),
+            Err(ZKMTDError::Replayed { .. })
+        ));
+
+        // A path against another root proves nothing
+        let other = prover.prove_fibonacci(16, [9u8; 32]).unwrap();
+        assert_ne!(other.nullifier().unwrap(), proof.nullifier().unwrap());
+        let path = set.prove(&other.nullifier().unwrap());
+        assert!(!verifier.verify_unspent(&other, &[1u8; 32], &path).unwrap());
+        assert!(verifier.verify_unspent(&other, &root, &path).unwrap());
+    }
 }
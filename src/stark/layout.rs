@@ -0,0 +1,168 @@
+//! Warped AIR layouts - per-epoch circuit shape derived from `WarpingParams`
+//!
+//! Opt-in: when a prover/verifier pair carries an [`AirLayout`], each epoch's
+//! params also pick
+//! - a permutation of the trace columns of every built-in AIR,
+//! - the FRI query count, within
+//!   `WARPED_MIN_FRI_QUERIES..=WARPED_MAX_FRI_QUERIES` (never below the
+//!   default 128-bit conjectured level),
+//! - a trace padding factor `2^padding_log` for the AIRs whose constraints
+//!   are row-local (Sum, Multiplication, Range). Fibonacci's row count is
+//!   part of its statement, so it is never padded.
+//!
+//! The layout digest is observed into the Fiat-Shamir transcript next to the
+//! MTD seed, so a proof only verifies under the exact layout it was made
+//! with, including under the unwarped (no layout) mode.
+
+use crate::core::errors::{Result, ZKMTDError};
+use crate::core::types::HashDigest;
+use crate::mtd::WarpingParams;
+use crate::utils::constants::{
+    DOMAIN_AIR_LAYOUT, DOMAIN_AIR_LAYOUT_PERM, MAX_LAYOUT_PADDING_LOG, MAX_TRACE_ROWS,
+    WARPED_MAX_FRI_QUERIES, WARPED_MIN_FRI_QUERIES,
+};
+use crate::utils::hash::poseidon_hash;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AirLayout {
+    seed: HashDigest,
+    num_queries: usize,
+    padding_log: u32,
+}
+
+impl AirLayout {
+    /// The layout for the epoch `params` belong to.
+    pub fn from_params(params: &WarpingParams) -> Self {
+        let seed = poseidon_hash(&params.to_bytes(), DOMAIN_AIR_LAYOUT);
+
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&seed[..8]);
+        let span = (WARPED_MAX_FRI_QUERIES - WARPED_MIN_FRI_QUERIES + 1) as u64;
+        let num_queries = WARPED_MIN_FRI_QUERIES + (u64::from_le_bytes(word) % span) as usize;
+        let padding_log = u32::from(seed[8]) % (MAX_LAYOUT_PADDING_LOG + 1);
+
+        Self {
+            seed,
+            num_queries,
+            padding_log,
+        }
+    }
+
+    pub fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    pub fn padding_log(&self) -> u32 {
+        self.padding_log
+    }
+
+    /// Trace height after padding `rows` (a power of two) by `2^padding_log`.
+    pub fn padded_rows(&self, rows: usize) -> Result<usize> {
+        let padded = rows << self.padding_log;
+        if padded > MAX_TRACE_ROWS {
+            return Err(ZKMTDError::InvalidWitness {
+                reason: alloc::format!(
+                    "Padded row count {} exceeds maximum {}",
+                    padded,
+                    MAX_TRACE_ROWS
+                ),
+            });
+        }
+        Ok(padded)
+    }
+
+    /// Physical column of each logical column for an AIR of `width` columns
+    /// (Fisher-Yates over a Poseidon2 counter-mode stream).
+    #[cfg(feature = "alloc")]
+    pub fn column_order(&self, width: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..width).collect();
+        let mut words = [0u64; 4];
+        for (n, i) in (1..width).rev().enumerate() {
+            if n % 4 == 0 {
+                let mut input = [0u8; 48];
+                input[..32].copy_from_slice(&self.seed);
+                input[32..40].copy_from_slice(&(width as u64).to_le_bytes());
+                input[40..].copy_from_slice(&((n / 4) as u64).to_le_bytes());
+                let block = poseidon_hash(&input, DOMAIN_AIR_LAYOUT_PERM);
+                for (w, chunk) in words.iter_mut().zip(block.chunks_exact(8)) {
+                    let mut buf = [0u8; 8];
+                    buf.copy_from_slice(chunk);
+                    *w = u64::from_le_bytes(buf);
+                }
+            }
+            let j = (words[n % 4] % (i as u64 + 1)) as usize;
+            order.swap(i, j);
+        }
+        order
+    }
+
+    /// Value observed into the STARK transcript.
+    pub fn digest(&self) -> HashDigest {
+        let mut data = [0u8; 44];
+        data[..32].copy_from_slice(&self.seed);
+        data[32..40].copy_from_slice(&(self.num_queries as u64).to_le_bytes());
+        data[40..].copy_from_slice(&self.padding_log.to_le_bytes());
+        poseidon_hash(&data, DOMAIN_AIR_LAYOUT)
+    }
+}
+
+/// Check that `order` is a permutation of `0..width`.
+pub(crate) fn validate_column_order(order: &[usize], width: usize) -> Result<()> {
+    let valid = order.len() == width
+        && order
+            .iter()
+            .enumerate()
+            .all(|(i, &c)| c < width && !order[..i].contains(&c));
+    if !valid {
+        return Err(ZKMTDError::ConfigurationError {
+            reason: "Column order is not a permutation of the AIR columns".into(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtd::Epoch;
+
+    fn layout(epoch: u64) -> AirLayout {
+        AirLayout::from_params(&WarpingParams::generate(b"layout-seed", Epoch::new(epoch)).unwrap())
+    }
+
+    #[test]
+    fn test_layout_parameters_in_range() {
+        for epoch in 0..64 {
+            let layout = layout(epoch);
+            assert!(
+                (WARPED_MIN_FRI_QUERIES..=WARPED_MAX_FRI_QUERIES).contains(&layout.num_queries())
+            );
+            assert!(layout.padding_log() <= MAX_LAYOUT_PADDING_LOG);
+            for width in [2, 3, 35] {
+                validate_column_order(&layout.column_order(width), width).unwrap();
+            }
+        }
+        assert!(layout(1).padded_rows(MAX_TRACE_ROWS << 1).is_err());
+    }
+
+    #[test]
+    fn test_layout_varies_by_epoch() {
+        assert_eq!(layout(7), layout(7));
+        let orders: Vec<_> = (0..16).map(|e| layout(e).column_order(35)).collect();
+        assert!(orders.iter().any(|o| o != &orders[0]));
+        assert!((0..16).any(|e| layout(e).num_queries() != layout(0).num_queries()));
+        assert!((0..16).any(|e| layout(e).padding_log() != layout(0).padding_log()));
+        assert_ne!(layout(1).digest(), layout(2).digest());
+    }
+
+    #[test]
+    fn test_validate_column_order() {
+        assert!(validate_column_order(&[1, 0, 2], 3).is_ok());
+        assert!(validate_column_order(&[1, 1, 2], 3).is_err());
+        assert!(validate_column_order(&[0, 1, 3], 3).is_err());
+        assert!(validate_column_order(&[0, 1], 3).is_err());
+    }
+}
@@ -15,6 +15,7 @@
 
 pub mod air;
 pub mod config;
+pub mod layout;
 pub mod prover;
 pub mod verifier;
 
@@ -24,6 +25,8 @@ pub mod real_stark;
 #[cfg(feature = "full-p3")]
 pub mod integrated;
 
+#[cfg(all(feature = "full-p3", feature = "alloc"))]
+pub mod credential;
 #[cfg(feature = "full-p3")]
 pub mod range_air;
 
@@ -32,6 +35,7 @@ pub mod range_commit_air;
 
 pub use air::SimpleAir;
 pub use config::StarkConfig;
+pub use layout::AirLayout;
 #[allow(deprecated)]
 pub use prover::{MTDProver, MTDVerifier};
 
@@ -39,10 +43,15 @@ pub use prover::{MTDProver, MTDVerifier};
 pub use real_stark::{ProofAirType, RealProof, RealStarkProver, RealStarkVerifier};
 
 #[cfg(feature = "full-p3")]
-pub use integrated::{IntegratedProof, IntegratedProver, IntegratedVerifier};
+pub use integrated::{FreshnessWindow, IntegratedProof, IntegratedProver, IntegratedVerifier};
+
+#[cfg(all(feature = "full-p3", feature = "alloc"))]
+pub use credential::{
+    Credential, CredentialKey, CredentialPresentation, CredentialValidity, CredentialVerifier,
+};
 
 #[cfg(feature = "full-p3")]
 pub use range_air::RangeAir;
 
 #[cfg(all(feature = "full-p3", feature = "alloc"))]
-pub use range_commit_air::{build_range_commit_trace, RangeCommitAir};
+pub use range_commit_air::{build_range_commit_trace, value_commitment, RangeCommitAir};
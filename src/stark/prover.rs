@@ -4,6 +4,8 @@
 use crate::core::errors::{Result, ZKMTDError};
 use crate::core::traits::Prover;
 use crate::core::types::{Proof, PublicInputs, Witness};
+#[cfg(feature = "alloc")]
+use crate::mtd::RevocationList;
 use crate::mtd::{Epoch, MTDManager, WarpingParams};
 use crate::stark::StarkConfig;
 use crate::utils::constants::{DOMAIN_PROOF_GENERATION, MIN_WITNESS_SIZE};
@@ -61,6 +63,9 @@ impl MTDProver {
             config: self.config.clone(),
             current_epoch: self.current_epoch(),
             current_params: self.current_params().clone(),
+            seed_fingerprint: self.mtd_manager.seed_fingerprint(),
+            #[cfg(feature = "alloc")]
+            revocations: None,
         }
     }
 
@@ -194,6 +199,9 @@ pub struct MTDVerifier {
     pub(crate) config: StarkConfig,
     pub(crate) current_epoch: Epoch,
     pub(crate) current_params: WarpingParams,
+    pub(crate) seed_fingerprint: u64,
+    #[cfg(feature = "alloc")]
+    pub(crate) revocations: Option<RevocationList>,
 }
 
 impl MTDVerifier {
@@ -203,6 +211,14 @@ impl MTDVerifier {
     pub fn current_params(&self) -> &WarpingParams {
         &self.current_params
     }
+
+    /// Reject proofs from revoked epochs or a revoked seed with
+    /// [`ZKMTDError::Revoked`].
+    #[cfg(feature = "alloc")]
+    pub fn with_revocation_list(mut self, list: RevocationList) -> Self {
+        self.revocations = Some(list);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -21,6 +21,7 @@ use crate::core::errors::{Result, ZKMTDError};
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+use crate::stark::layout::validate_column_order;
 use p3_air::Air as P3Air;
 use p3_air::{AirBuilder, BaseAir, WindowAccess};
 use p3_field::PrimeCharacteristicRing;
@@ -38,17 +39,29 @@ pub const MAX_RANGE_VALUE: u64 = 1u64 << RANGE_BITS; // 2^32
 #[derive(Debug, Clone)]
 pub struct RangeAir {
     num_bits: usize,
+    /// Physical trace column of each logical column (identity unless the
+    /// layout is warped, see `stark::layout`).
+    columns: Vec<usize>,
 }
 
 impl RangeAir {
     pub fn new() -> Self {
+        Self::with_bits(RANGE_BITS)
+    }
+
+    pub fn with_bits(num_bits: usize) -> Self {
         Self {
-            num_bits: RANGE_BITS,
+            num_bits,
+            columns: (0..num_bits + 3).collect(),
         }
     }
 
-    pub fn with_bits(num_bits: usize) -> Self {
-        Self { num_bits }
+    /// Place logical column `i` at physical column `order[i]`. The trace must
+    /// be permuted the same way.
+    pub fn with_column_order(mut self, order: &[usize]) -> Result<Self> {
+        validate_column_order(order, self.width())?;
+        self.columns = order.to_vec();
+        Ok(self)
     }
 
     pub fn width(&self) -> usize {
@@ -79,15 +92,16 @@ impl<AB: AirBuilder<F = Goldilocks>> P3Air<AB> for RangeAir {
         let main = builder.main();
         let local = main.current_slice();
 
-        // Column indices
-        let bits_end = self.num_bits;
-        let value_idx = bits_end;
-        let threshold_idx = bits_end + 1;
-        let diff_idx = bits_end + 2;
+        // Column indices (logical -> physical)
+        let bits = &self.columns[..self.num_bits];
+        let value_idx = self.columns[self.num_bits];
+        let threshold_idx = self.columns[self.num_bits + 1];
+        let diff_idx = self.columns[self.num_bits + 2];
 
         // 1. Verify each bit is binary (0 or 1)
         // Constraint: bit * (1 - bit) = 0
-        for &bit in local.iter().take(self.num_bits) {
+        for &col in bits {
+            let bit = local[col];
             builder.assert_zero(bit * (AB::Expr::ONE - bit));
         }
 
@@ -107,8 +121,8 @@ impl<AB: AirBuilder<F = Goldilocks>> P3Air<AB> for RangeAir {
         let mut reconstructed = AB::Expr::ZERO;
         let mut power_of_two = AB::Expr::ONE;
 
-        for &bit in local.iter().take(self.num_bits) {
-            reconstructed += bit * power_of_two.clone();
+        for &col in bits {
+            reconstructed += local[col] * power_of_two.clone();
             power_of_two *= AB::Expr::from_u64(2);
         }
 
@@ -236,6 +236,14 @@ pub fn build_range_commit_trace(
     Ok((RowMajorMatrix::new(values, TOTAL_WIDTH), commitment))
 }
 
+/// The public commitment `Poseidon2([value, salt, 0..])[0]` that a committed
+/// range proof for `value` is checked against. Fails for values the AIR
+/// cannot prove (`value >= MAX_RANGE_VALUE`).
+#[cfg(feature = "alloc")]
+pub fn value_commitment(value: u64, salt: u64) -> Result<Goldilocks> {
+    build_range_commit_trace(value, 0, salt).map(|(_, commitment)| commitment)
+}
+
 #[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
@@ -1,6 +1,7 @@
 //! Real Plonky3 STARK (full-p3 feature required)
 
 use crate::core::errors::{Result, ZKMTDError};
+use crate::utils::constants::{MAX_PROOF_SIZE, MAX_PUBLIC_INPUTS_SIZE};
 
 #[cfg(feature = "alloc")]
 use alloc::vec;
@@ -23,7 +24,9 @@ use p3_uni_stark::{prove, verify, Proof, StarkConfig};
 
 // AIR
 use crate::stark::air::SimpleAir;
+use crate::stark::layout::AirLayout;
 use crate::stark::range_air::RangeAir;
+use crate::stark::range_commit_air::{build_range_commit_trace, RangeCommitAir};
 
 pub type Val = Goldilocks;
 pub type Challenge = BinomialExtensionField<Val, 2>;
@@ -50,6 +53,7 @@ pub enum ProofAirType {
     Sum = 1,
     Multiplication = 2,
     Range = 3,
+    RangeCommit = 4,
 }
 
 impl ProofAirType {
@@ -58,6 +62,17 @@ impl ProofAirType {
     pub fn as_u8(self) -> u8 {
         self as u8
     }
+
+    pub fn from_u8(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Fibonacci),
+            1 => Some(Self::Sum),
+            2 => Some(Self::Multiplication),
+            3 => Some(Self::Range),
+            4 => Some(Self::RangeCommit),
+            _ => None,
+        }
+    }
 }
 
 pub struct RealStarkProver {
@@ -66,6 +81,10 @@ pub struct RealStarkProver {
     /// Per-epoch MTD seed observed into the Fiat-Shamir transcript (H-3).
     /// Zero for standalone use (epoch-independent).
     mtd_seed: [u8; 32],
+    /// Opt-in per-epoch circuit layout (see `stark::layout`).
+    layout: Option<AirLayout>,
+    /// Claimed creation time observed into the transcript, if any.
+    timestamp: Option<u64>,
 }
 
 impl Clone for RealStarkProver {
@@ -74,6 +93,8 @@ impl Clone for RealStarkProver {
             air: self.air.clone(),
             perm: self.perm.clone(),
             mtd_seed: self.mtd_seed,
+            layout: self.layout,
+            timestamp: self.timestamp,
         }
     }
 }
@@ -90,7 +111,13 @@ impl core::fmt::Debug for RealStarkProver {
 impl RealStarkProver {
     pub fn new(air: SimpleAir) -> Result<Self> {
         let perm = create_poseidon2_perm();
-        Ok(Self { air, perm, mtd_seed: [0u8; 32] })
+        Ok(Self {
+            air,
+            perm,
+            mtd_seed: [0u8; 32],
+            layout: None,
+            timestamp: None,
+        })
     }
 
     /// Bind a per-epoch MTD seed into the STARK Fiat-Shamir transcript (H-3),
@@ -100,18 +127,44 @@ impl RealStarkProver {
         self.mtd_seed = mtd_seed;
     }
 
+    /// Enable (or with `None`, disable) the warped circuit layout. The
+    /// verifier must use the same layout.
+    pub fn set_layout(&mut self, layout: Option<AirLayout>) {
+        self.layout = layout;
+    }
+
+    pub fn layout(&self) -> Option<&AirLayout> {
+        self.layout.as_ref()
+    }
+
+    /// Bind a claimed creation time (Unix seconds) into the transcript, so
+    /// the proof only verifies under that same timestamp.
+    pub fn set_timestamp(&mut self, timestamp: Option<u64>) {
+        self.timestamp = timestamp;
+    }
+
     pub fn prove_fibonacci(&self, num_rows: usize) -> Result<RealProof> {
-        // 1. Generate trace
-        let trace = build_fibonacci_trace(num_rows)?;
+        // 1. Generate trace (column-permuted, never padded, when warped)
+        let air = warp_simple_air(self.air.clone(), self.layout.as_ref())?;
+        let trace = warp_trace(
+            build_fibonacci_trace(num_rows)?,
+            self.layout.as_ref(),
+            false,
+        )?;
 
         // 2. Public values (initial + final values)
         let public_values = compute_public_values(num_rows);
 
         // 3. Create STARK configuration (challenger embedded in config)
-        let config = create_stark_config(&self.perm, &self.mtd_seed);
+        let config = create_stark_config(
+            &self.perm,
+            &self.mtd_seed,
+            self.layout.as_ref(),
+            self.timestamp,
+        );
 
         // 4. Generate actual STARK proof
-        let proof = prove(&config, &self.air, trace, &public_values);
+        let proof = prove(&config, &air, trace, &public_values);
 
         // 7. Wrap proof
         Ok(RealProof {
@@ -125,12 +178,21 @@ impl RealStarkProver {
 
     /// Prove `a[i] + b[i] = c[i]` for all rows
     pub fn prove_sum(&self, a_values: &[u64], b_values: &[u64]) -> Result<RealProof> {
-        let air = SimpleAir::sum();
-        let trace = build_sum_trace_p3(a_values, b_values)?;
+        let air = warp_simple_air(SimpleAir::sum(), self.layout.as_ref())?;
+        let trace = warp_trace(
+            build_sum_trace_p3(a_values, b_values)?,
+            self.layout.as_ref(),
+            true,
+        )?;
         let num_rows = trace.height();
         let public_values = compute_sum_public_values(a_values, b_values);
 
-        let config = create_stark_config(&self.perm, &self.mtd_seed);
+        let config = create_stark_config(
+            &self.perm,
+            &self.mtd_seed,
+            self.layout.as_ref(),
+            self.timestamp,
+        );
         let proof = prove(&config, &air, trace, &public_values);
 
         Ok(RealProof {
@@ -144,12 +206,21 @@ impl RealStarkProver {
 
     /// Prove `a[i] * b[i] = c[i]` for all rows
     pub fn prove_multiplication(&self, a_values: &[u64], b_values: &[u64]) -> Result<RealProof> {
-        let air = SimpleAir::multiplication();
-        let trace = build_mul_trace_p3(a_values, b_values)?;
+        let air = warp_simple_air(SimpleAir::multiplication(), self.layout.as_ref())?;
+        let trace = warp_trace(
+            build_mul_trace_p3(a_values, b_values)?,
+            self.layout.as_ref(),
+            true,
+        )?;
         let num_rows = trace.height();
         let public_values = compute_mul_public_values(a_values, b_values);
 
-        let config = create_stark_config(&self.perm, &self.mtd_seed);
+        let config = create_stark_config(
+            &self.perm,
+            &self.mtd_seed,
+            self.layout.as_ref(),
+            self.timestamp,
+        );
         let proof = prove(&config, &air, trace, &public_values);
 
         Ok(RealProof {
@@ -163,12 +234,18 @@ impl RealStarkProver {
 
     /// Prove value >= threshold via bit decomposition
     pub fn prove_range(&self, value: u64, threshold: u64) -> Result<RealProof> {
-        let air = RangeAir::new();
+        let air = warp_range_air(RangeAir::new(), self.layout.as_ref())?;
         let trace = crate::stark::range_air::trace_builder::build_range_proof_trace(value, threshold)?;
+        let trace = warp_trace(trace, self.layout.as_ref(), true)?;
         let num_rows = trace.height();
         let public_values = vec![Val::from_u64(threshold)];
 
-        let config = create_stark_config(&self.perm, &self.mtd_seed);
+        let config = create_stark_config(
+            &self.perm,
+            &self.mtd_seed,
+            self.layout.as_ref(),
+            self.timestamp,
+        );
         let proof = prove(&config, &air, trace, &public_values);
 
         Ok(RealProof {
@@ -180,11 +257,34 @@ impl RealStarkProver {
         })
     }
 
+    /// Prove that the value committed as `Poseidon2([value, salt])[0]` is
+    /// at least `threshold` (see `stark::range_commit_air`). Public values
+    /// are `[threshold, commitment]`. Not available under a warped layout.
+    pub fn prove_range_commit(&self, value: u64, threshold: u64, salt: u64) -> Result<RealProof> {
+        reject_layout(self.layout.as_ref())?;
+        let (trace, commitment) = build_range_commit_trace(value, threshold, salt)?;
+        let num_rows = trace.height();
+        let public_values = vec![Val::from_u64(threshold), commitment];
+
+        let config = create_stark_config(&self.perm, &self.mtd_seed, None, self.timestamp);
+        let proof = prove(&config, &RangeCommitAir::new(), trace, &public_values);
+
+        Ok(RealProof {
+            num_rows,
+            public_values: public_values.iter().map(|v| v.as_canonical_u64()).collect(),
+            air_type: ProofAirType::RangeCommit,
+            inner: proof,
+            perm: self.perm.clone(),
+        })
+    }
+
     pub fn get_verifier(&self) -> RealStarkVerifier {
         RealStarkVerifier {
             air: self.air.clone(),
             perm: self.perm.clone(),
             mtd_seed: self.mtd_seed,
+            layout: self.layout,
+            timestamp: self.timestamp,
         }
     }
 }
@@ -194,6 +294,8 @@ pub struct RealStarkVerifier {
     perm: Perm,
     /// Per-epoch MTD seed observed into the Fiat-Shamir transcript (H-3).
     mtd_seed: [u8; 32],
+    layout: Option<AirLayout>,
+    timestamp: Option<u64>,
 }
 
 impl Clone for RealStarkVerifier {
@@ -202,6 +304,8 @@ impl Clone for RealStarkVerifier {
             air: self.air.clone(),
             perm: self.perm.clone(),
             mtd_seed: self.mtd_seed,
+            layout: self.layout,
+            timestamp: self.timestamp,
         }
     }
 }
@@ -218,7 +322,13 @@ impl core::fmt::Debug for RealStarkVerifier {
 impl RealStarkVerifier {
     pub fn new(air: SimpleAir) -> Result<Self> {
         let perm = create_poseidon2_perm();
-        Ok(Self { air, perm, mtd_seed: [0u8; 32] })
+        Ok(Self {
+            air,
+            perm,
+            mtd_seed: [0u8; 32],
+            layout: None,
+            timestamp: None,
+        })
     }
 
     /// Bind the per-epoch MTD seed used to verify (H-3). Must match the seed the
@@ -227,6 +337,20 @@ impl RealStarkVerifier {
         self.mtd_seed = mtd_seed;
     }
 
+    /// Verify under the warped layout the prover used (see `stark::layout`).
+    pub fn set_layout(&mut self, layout: Option<AirLayout>) {
+        self.layout = layout;
+    }
+
+    pub fn layout(&self) -> Option<&AirLayout> {
+        self.layout.as_ref()
+    }
+
+    /// Verify under the timestamp the prover bound into the transcript.
+    pub fn set_timestamp(&mut self, timestamp: Option<u64>) {
+        self.timestamp = timestamp;
+    }
+
     /// Dispatch verification based on proof's AIR type
     pub fn verify_by_type(&self, proof: &RealProof) -> Result<bool> {
         match proof.air_type {
@@ -234,6 +358,7 @@ impl RealStarkVerifier {
             ProofAirType::Sum => self.verify_sum(proof),
             ProofAirType::Multiplication => self.verify_multiplication(proof),
             ProofAirType::Range => self.verify_range(proof),
+            ProofAirType::RangeCommit => self.verify_range_commit(proof),
         }
     }
 
@@ -252,10 +377,16 @@ impl RealStarkVerifier {
             .collect();
 
         // 2. Create STARK configuration (using same perm, challenger embedded)
-        let config = create_stark_config(&proof.perm, &self.mtd_seed);
+        let air = warp_simple_air(self.air.clone(), self.layout.as_ref())?;
+        let config = create_stark_config(
+            &proof.perm,
+            &self.mtd_seed,
+            self.layout.as_ref(),
+            self.timestamp,
+        );
 
         // 3. Actual STARK verification
-        match verify(&config, &self.air, &proof.inner, &public_values) {
+        match verify(&config, &air, &proof.inner, &public_values) {
             Ok(()) => Ok(true),
             Err(_) => Ok(false),
         }
@@ -269,14 +400,19 @@ impl RealStarkVerifier {
             return Ok(false);
         }
 
-        let air = SimpleAir::sum();
+        let air = warp_simple_air(SimpleAir::sum(), self.layout.as_ref())?;
         let public_values: Vec<Val> = proof
             .public_values
             .iter()
             .map(|&v| Val::from_u64(v))
             .collect();
 
-        let config = create_stark_config(&proof.perm, &self.mtd_seed);
+        let config = create_stark_config(
+            &proof.perm,
+            &self.mtd_seed,
+            self.layout.as_ref(),
+            self.timestamp,
+        );
 
         match verify(&config, &air, &proof.inner, &public_values) {
             Ok(()) => Ok(true),
@@ -292,14 +428,19 @@ impl RealStarkVerifier {
             return Ok(false);
         }
 
-        let air = SimpleAir::multiplication();
+        let air = warp_simple_air(SimpleAir::multiplication(), self.layout.as_ref())?;
         let public_values: Vec<Val> = proof
             .public_values
             .iter()
             .map(|&v| Val::from_u64(v))
             .collect();
 
-        let config = create_stark_config(&proof.perm, &self.mtd_seed);
+        let config = create_stark_config(
+            &proof.perm,
+            &self.mtd_seed,
+            self.layout.as_ref(),
+            self.timestamp,
+        );
 
         match verify(&config, &air, &proof.inner, &public_values) {
             Ok(()) => Ok(true),
@@ -315,20 +456,53 @@ impl RealStarkVerifier {
             return Ok(false);
         }
 
-        let air = RangeAir::new();
+        let air = warp_range_air(RangeAir::new(), self.layout.as_ref())?;
         let public_values: Vec<Val> = proof
             .public_values
             .iter()
             .map(|&v| Val::from_u64(v))
             .collect();
 
-        let config = create_stark_config(&proof.perm, &self.mtd_seed);
+        let config = create_stark_config(
+            &proof.perm,
+            &self.mtd_seed,
+            self.layout.as_ref(),
+            self.timestamp,
+        );
 
         match verify(&config, &air, &proof.inner, &public_values) {
             Ok(()) => Ok(true),
             Err(_) => Ok(false),
         }
     }
+
+    pub fn verify_range_commit(&self, proof: &RealProof) -> Result<bool> {
+        reject_layout(self.layout.as_ref())?;
+        if !proof.num_rows.is_power_of_two()
+            || !(2..=crate::utils::constants::MAX_TRACE_ROWS).contains(&proof.num_rows)
+            || proof.public_values.len() != 2
+        {
+            return Ok(false);
+        }
+
+        let public_values: Vec<Val> = proof
+            .public_values
+            .iter()
+            .map(|&v| Val::from_u64(v))
+            .collect();
+
+        let config = create_stark_config(&proof.perm, &self.mtd_seed, None, self.timestamp);
+
+        match verify(
+            &config,
+            &RangeCommitAir::new(),
+            &proof.inner,
+            &public_values,
+        ) {
+            Ok(()) => Ok(true),
+            Err(_) => Ok(false),
+        }
+    }
 }
 
 pub struct RealProof {
@@ -350,6 +524,91 @@ impl core::fmt::Debug for RealProof {
     }
 }
 
+/// air type (1) || num_rows (8) || public value count (4)
+const REAL_PROOF_HEADER_LEN: usize = 13;
+
+impl RealProof {
+    /// Canonical encoding: header, public values, then the length-prefixed
+    /// postcard encoding of the Plonky3 proof.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let inner =
+            postcard::to_allocvec(&self.inner).map_err(|e| ZKMTDError::SerializationError {
+                reason: alloc::format!("STARK proof encoding failed: {}", e),
+            })?;
+        let mut out = Vec::with_capacity(
+            REAL_PROOF_HEADER_LEN + self.public_values.len() * 8 + 4 + inner.len(),
+        );
+        out.push(self.air_type.as_u8());
+        out.extend_from_slice(&(self.num_rows as u64).to_le_bytes());
+        out.extend_from_slice(&(self.public_values.len() as u32).to_le_bytes());
+        for pv in &self.public_values {
+            out.extend_from_slice(&pv.to_le_bytes());
+        }
+        out.extend_from_slice(&(inner.len() as u32).to_le_bytes());
+        out.extend_from_slice(&inner);
+        Ok(out)
+    }
+
+    /// Canonical encoding of the trace and quotient commitments. They fix
+    /// every Fiat-Shamir challenge, so they cannot change while the proof
+    /// still verifies.
+    pub fn commitments_bytes(&self) -> Result<Vec<u8>> {
+        postcard::to_allocvec(&self.inner.commitments).map_err(|e| ZKMTDError::SerializationError {
+            reason: alloc::format!("STARK commitment encoding failed: {}", e),
+        })
+    }
+
+    /// Inverse of [`to_bytes`](Self::to_bytes). Only the canonical encoding
+    /// is accepted, so equal proofs always have equal bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let malformed = |what: &str| ZKMTDError::SerializationError {
+            reason: alloc::format!("Malformed STARK proof: {}", what),
+        };
+        if bytes.len() < REAL_PROOF_HEADER_LEN || bytes.len() > MAX_PROOF_SIZE {
+            return Err(malformed("length out of range"));
+        }
+        let read_u64 = |at: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&bytes[at..at + 8]);
+            u64::from_le_bytes(buf)
+        };
+        let read_u32 = |at: usize| {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(&bytes[at..at + 4]);
+            u32::from_le_bytes(buf) as usize
+        };
+
+        let air_type =
+            ProofAirType::from_u8(bytes[0]).ok_or_else(|| malformed("unknown AIR type"))?;
+        let num_rows = usize::try_from(read_u64(1)).map_err(|_| malformed("row count"))?;
+        let pv_count = read_u32(9);
+        if pv_count > MAX_PUBLIC_INPUTS_SIZE {
+            return Err(malformed("too many public values"));
+        }
+        let inner_at = REAL_PROOF_HEADER_LEN + pv_count * 8;
+        if bytes.len() < inner_at + 4 || bytes.len() != inner_at + 4 + read_u32(inner_at) {
+            return Err(malformed("length mismatch"));
+        }
+        let public_values = (0..pv_count)
+            .map(|i| read_u64(REAL_PROOF_HEADER_LEN + i * 8))
+            .collect();
+        let inner = postcard::from_bytes(&bytes[inner_at + 4..])
+            .map_err(|_| malformed("undecodable Plonky3 proof"))?;
+
+        let proof = Self {
+            num_rows,
+            public_values,
+            air_type,
+            inner,
+            perm: create_poseidon2_perm(),
+        };
+        if proof.to_bytes()? != bytes {
+            return Err(malformed("non-canonical encoding"));
+        }
+        Ok(proof)
+    }
+}
+
 fn create_poseidon2_perm() -> Perm {
     use crate::utils::constants::ZKMTD_POSEIDON2_SEED;
     use rand::SeedableRng;
@@ -361,7 +620,12 @@ fn create_poseidon2_perm() -> Perm {
     Poseidon2Goldilocks::<16>::new_from_rng_128(&mut rng)
 }
 
-fn create_stark_config(perm: &Perm, mtd_seed: &[u8; 32]) -> MyStarkConfig {
+fn create_stark_config(
+    perm: &Perm,
+    mtd_seed: &[u8; 32],
+    layout: Option<&AirLayout>,
+    timestamp: Option<u64>,
+) -> MyStarkConfig {
     // Hash and compression functions
     let hash = MyHash::new(perm.clone());
     let compress = MyCompress::new(perm.clone());
@@ -386,11 +650,12 @@ fn create_stark_config(perm: &Perm, mtd_seed: &[u8; 32]) -> MyStarkConfig {
     // only ~half (~1 bit/query → ~60+8 ≈ 68 bits). Reaching 128-bit *proven*
     // soundness would require roughly doubling num_queries (~120). Acceptable
     // for most uses; tighten num_queries if proven 128-bit is required.
+    // Warped layouts pick 60..=80 queries per epoch, never fewer.
     let fri_params = FriParameters {
         log_blowup: 2,
         log_final_poly_len: 0,
         max_log_arity: 1,
-        num_queries: 60,
+        num_queries: layout.map_or(60, AirLayout::num_queries),
         commit_proof_of_work_bits: 0,
         query_proof_of_work_bits: 8,
         mmcs: challenge_mmcs,
@@ -417,9 +682,86 @@ fn create_stark_config(perm: &Perm, mtd_seed: &[u8; 32]) -> MyStarkConfig {
         challenger.observe(Val::from_u64(u64::from_le_bytes(buf)));
     }
 
+    // Warped layouts: bind the layout too, so a proof made under one layout
+    // (or none) never verifies under another, even where the AIR and trace
+    // shape alone would not tell them apart (e.g. padding).
+    if let Some(layout) = layout {
+        for chunk in layout.digest().chunks(8) {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(chunk);
+            challenger.observe(Val::from_u64(u64::from_le_bytes(buf)));
+        }
+    }
+
+    // Timestamped proofs: bind the claimed time as two 32-bit limbs (each
+    // below the Goldilocks modulus), so the STARK itself, not only the
+    // outer binding hash, is specific to it.
+    if let Some(timestamp) = timestamp {
+        challenger.observe(Val::from_u64(timestamp & 0xFFFF_FFFF));
+        challenger.observe(Val::from_u64(timestamp >> 32));
+    }
+
     StarkConfig::new(pcs, challenger)
 }
 
+fn warp_simple_air(air: SimpleAir, layout: Option<&AirLayout>) -> Result<SimpleAir> {
+    match layout {
+        Some(layout) => {
+            let order = layout.column_order(air.num_columns());
+            air.with_column_order(&order)
+        }
+        None => Ok(air),
+    }
+}
+
+/// `RangeCommitAir` embeds a fixed Poseidon2 column block, so it has no
+/// warped variant.
+fn reject_layout(layout: Option<&AirLayout>) -> Result<()> {
+    match layout {
+        Some(_) => Err(ZKMTDError::ConfigurationError {
+            reason: "Committed range proofs do not support warped layouts".into(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn warp_range_air(air: RangeAir, layout: Option<&AirLayout>) -> Result<RangeAir> {
+    match layout {
+        Some(layout) => {
+            let order = layout.column_order(air.width());
+            air.with_column_order(&order)
+        }
+        None => Ok(air),
+    }
+}
+
+/// Lay a logical trace out under `layout`: optionally pad to the layout's
+/// height by repeating the last row (only sound for row-local AIRs whose
+/// last row is already the public "last" row), then permute the columns.
+fn warp_trace(
+    trace: RowMajorMatrix<Val>,
+    layout: Option<&AirLayout>,
+    pad: bool,
+) -> Result<RowMajorMatrix<Val>> {
+    let layout = match layout {
+        Some(layout) => layout,
+        None => return Ok(trace),
+    };
+    let width = trace.width();
+    let rows = trace.height();
+    let height = if pad { layout.padded_rows(rows)? } else { rows };
+    let order = layout.column_order(width);
+
+    let mut values = vec![Val::ZERO; height * width];
+    for (r, dst) in values.chunks_exact_mut(width).enumerate() {
+        let src = &trace.values[r.min(rows - 1) * width..][..width];
+        for (i, &col) in order.iter().enumerate() {
+            dst[col] = src[i];
+        }
+    }
+    Ok(RowMajorMatrix::new(values, width))
+}
+
 fn build_fibonacci_trace(num_rows: usize) -> Result<RowMajorMatrix<Val>> {
     if !num_rows.is_power_of_two() {
         return Err(ZKMTDError::InvalidWitness {
@@ -752,6 +1094,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_range_commit_binds_commitment() {
+        let prover = RealStarkProver::new(SimpleAir::fibonacci()).unwrap();
+        let proof = prover.prove_range_commit(100, 50, 42).unwrap();
+        let verifier = prover.get_verifier();
+        assert_eq!(proof.air_type, ProofAirType::RangeCommit);
+        assert_eq!(
+            proof.public_values[1],
+            crate::stark::value_commitment(100, 42)
+                .unwrap()
+                .as_canonical_u64()
+        );
+
+        let restored = RealProof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        assert!(verifier.verify_by_type(&restored).unwrap());
+
+        // A commitment to a different value must not verify.
+        let mut forged = restored;
+        forged.public_values[1] = forged.public_values[1].wrapping_add(1);
+        assert!(!verifier.verify_range_commit(&forged).unwrap());
+
+        // Warped layouts are not supported for committed range proofs.
+        let mut warped = RealStarkProver::new(SimpleAir::fibonacci()).unwrap();
+        warped.set_layout(Some(AirLayout::from_params(
+            &crate::mtd::WarpingParams::generate(b"layout-seed", crate::mtd::Epoch::new(1))
+                .unwrap(),
+        )));
+        assert!(warped.prove_range_commit(100, 50, 42).is_err());
+    }
+
     #[test]
     fn test_c1_fibonacci_rejects_forged_final_value() {
         let prover = RealStarkProver::new(SimpleAir::fibonacci()).unwrap();
@@ -4,7 +4,7 @@
 use crate::core::errors::{Result, ZKMTDError};
 use crate::core::traits::Verifier;
 use crate::core::types::{Proof, PublicInputs};
-use crate::mtd::WarpingParams;
+use crate::mtd::{Epoch, WarpingParams};
 use crate::stark::prover::MTDVerifier as MTDVerifierInner;
 use crate::utils::constants::DOMAIN_PROOF_VERIFICATION;
 use crate::utils::hash::poseidon_hash;
@@ -14,6 +14,13 @@ use alloc::vec::Vec;
 
 impl Verifier for MTDVerifierInner {
     fn verify(&self, proof: &Proof, public_inputs: &PublicInputs) -> Result<bool> {
+        #[cfg(feature = "alloc")]
+        if let Some(ref list) = self.revocations {
+            let epoch = Epoch::try_new(proof.epoch)?;
+            if let Some(reason) = list.check(epoch, Some(self.seed_fingerprint)) {
+                return Err(reason.into());
+            }
+        }
         if proof.epoch != self.current_epoch.value() {
             return Err(ZKMTDError::InvalidProof);
         }
@@ -34,11 +41,9 @@ impl Verifier for MTDVerifierInner {
                 ),
             });
         }
-        proofs
-            .iter()
-            .zip(public_inputs.iter())
-            .map(|(p, i)| self.verify(p, i))
-            .collect()
+        crate::utils::parallel::try_map_indices(proofs.len(), |i| {
+            self.verify(&proofs[i], &public_inputs[i])
+        })
     }
 }
 
@@ -165,6 +170,9 @@ impl MTDVerifierInner {
             config: self.config.clone(),
             current_epoch: params.epoch,
             current_params: params.clone(),
+            seed_fingerprint: self.seed_fingerprint,
+            #[cfg(feature = "alloc")]
+            revocations: self.revocations.clone(),
         };
         temp.verify(proof, public_inputs)
     }
@@ -219,6 +227,27 @@ mod tests {
         assert!(result.is_err(), "Proof with different epoch was accepted");
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_verifier_revoked_epoch() {
+        use crate::mtd::RevocationList;
+
+        let prover =
+            MTDProver::with_epoch(b"test-seed", StarkConfig::for_testing(), Epoch::new(100))
+                .unwrap();
+        let witness = Witness::new(vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let public_inputs = PublicInputs::new(vec![42]);
+        let proof = prover.prove(&witness, &public_inputs).unwrap();
+
+        let mut list = RevocationList::new(1);
+        list.revoke_epoch(Epoch::new(100)).unwrap();
+        let verifier = prover.get_verifier().with_revocation_list(list);
+        assert!(matches!(
+            verifier.verify(&proof, &public_inputs),
+            Err(ZKMTDError::Revoked { .. })
+        ));
+    }
+
     #[cfg(feature = "alloc")]
     #[test]
     fn test_verifier_invalid_proof() {
@@ -54,6 +54,18 @@ pub const DOMAIN_BIOMETRIC: &[u8] = b"ZKMTD::Privacy::Biometric";
 pub const DOMAIN_CREDENTIAL: &[u8] = b"ZKMTD::Privacy::Credential";
 pub const DOMAIN_COMMUNICATION: &[u8] = b"ZKMTD::Privacy::Communication";
 
+// Multi-tenant parameter scoping (mtd::tenant)
+pub const DOMAIN_TENANT_ID: &[u8] = b"ZKMTD::Tenant::Id";
+pub const DOMAIN_TENANT_SALT: &[u8] = b"ZKMTD::Tenant::Salt";
+
+// Hierarchical seed derivation (mtd::keytree)
+pub const DOMAIN_KEYTREE_MASTER_KEY: &[u8] = b"ZKMTD::KeyTree::MasterKey";
+pub const DOMAIN_KEYTREE_MASTER_CHAIN: &[u8] = b"ZKMTD::KeyTree::MasterChain";
+pub const DOMAIN_KEYTREE_CHILD_KEY: &[u8] = b"ZKMTD::KeyTree::ChildKey";
+pub const DOMAIN_KEYTREE_CHILD_CHAIN: &[u8] = b"ZKMTD::KeyTree::ChildChain";
+pub const DOMAIN_KEYTREE_PUBLIC: &[u8] = b"ZKMTD::KeyTree::Public";
+pub const DOMAIN_KEYTREE_FINGERPRINT: &[u8] = b"ZKMTD::KeyTree::Fingerprint";
+
 // Internal domain separation tags (used in MTD parameter derivation, proof integrity, etc.)
 pub const DOMAIN_MTD_DOMAIN_SEP: &[u8] = b"MTD_DOMAIN_SEP";
 pub const DOMAIN_MTD_SALT: &[u8] = b"MTD_SALT";
@@ -62,6 +74,65 @@ pub const DOMAIN_PROOF_INTEGRITY: &[u8] = b"PROOF_INTEGRITY";
 pub const DOMAIN_SEED_FINGERPRINT: &[u8] = b"SEED_FINGERPRINT";
 pub const DOMAIN_COMPRESSION_CHECKSUM: &[u8] = b"COMPRESSION_CHECKSUM";
 pub const DOMAIN_SOLANA_ENTROPY: &[u8] = b"SOLANA_ENTROPY_V1";
+pub const DOMAIN_ENTROPY_POOL_MIX: &[u8] = b"ZKMTD::EntropyPool::Mix";
+pub const DOMAIN_ENTROPY_POOL_OUTPUT: &[u8] = b"ZKMTD::EntropyPool::Output";
+pub const DOMAIN_DRBG_INPUT: &[u8] = b"ZKMTD::DRBG::Input";
+pub const DOMAIN_DRBG_KEY: &[u8] = b"ZKMTD::DRBG::Key";
+pub const DOMAIN_DRBG_VALUE: &[u8] = b"ZKMTD::DRBG::Value";
+pub const DOMAIN_SNAPSHOT_ENC: &[u8] = b"ZKMTD::Snapshot::EncKey";
+pub const DOMAIN_SNAPSHOT_STREAM: &[u8] = b"ZKMTD::Snapshot::Stream";
+pub const DOMAIN_SNAPSHOT_MAC_KEY: &[u8] = b"ZKMTD::Snapshot::MacKey";
+pub const DOMAIN_SNAPSHOT_MAC: &[u8] = b"ZKMTD::Snapshot::MAC";
+
+// Threshold seed sharing (mtd::threshold)
+pub const DOMAIN_THRESHOLD_COMMIT: &[u8] = b"ZKMTD::Threshold::Commit";
+pub const DOMAIN_THRESHOLD_PRF: &[u8] = b"ZKMTD::Threshold::PRF";
+pub const DOMAIN_THRESHOLD_CHECK: &[u8] = b"ZKMTD::Threshold::Check";
+pub const DOMAIN_THRESHOLD_PAD: &[u8] = b"ZKMTD::Threshold::Pad";
+
+// Epoch parameter announcements (mtd::announcement)
+pub const DOMAIN_EPOCH_ANNOUNCEMENT: &[u8] = b"ZKMTD::Announcement";
+
+// Epoch-warped AIR layouts (stark::layout)
+pub const DOMAIN_AIR_LAYOUT: &[u8] = b"ZKMTD::AirLayout";
+pub const DOMAIN_AIR_LAYOUT_PERM: &[u8] = b"ZKMTD::AirLayout::Permutation";
+
+// Epoch / seed revocation lists (mtd::revocation)
+pub const DOMAIN_REVOCATION_ENTRY: &[u8] = b"ZKMTD::Revocation::Entry";
+
+// Expiring credentials (stark::credential)
+pub const DOMAIN_CREDENTIAL_RANGE: &[u8] = b"ZKMTD::Credential::Range";
+pub const DOMAIN_CREDENTIAL_PRESENTATION: &[u8] = b"ZKMTD::Credential::Presentation";
+
+// Randomness beacon entropy (mtd::beacon)
+pub const DOMAIN_BEACON_GENESIS: &[u8] = b"ZKMTD::Beacon::Genesis";
+pub const DOMAIN_BEACON_ROUND: &[u8] = b"ZKMTD::Beacon::Round";
+pub const DOMAIN_BEACON_ENTROPY: &[u8] = b"ZKMTD::Beacon::Entropy";
+pub const DOMAIN_BEACON_SEED: &[u8] = b"ZKMTD::Beacon::Seed";
+
+// Merkle Mountain Range proof logs (batching::mmr)
+pub const DOMAIN_MMR_NODE: &[u8] = b"ZKMTD::MMR::Node";
+pub const DOMAIN_MMR_ROOT: &[u8] = b"ZKMTD::MMR::Root";
+
+// Nullifier sparse Merkle tree (batching::nullifier)
+pub const DOMAIN_NULLIFIER: &[u8] = b"ZKMTD::Nullifier";
+pub const DOMAIN_ONCHAIN_NULLIFIER: &[u8] = b"ZKMTD::Nullifier::Onchain";
+pub const DOMAIN_SMT_LEAF: &[u8] = b"ZKMTD::SMT::Leaf";
+pub const DOMAIN_SMT_NODE: &[u8] = b"ZKMTD::SMT::Node";
+
+// Multi-epoch batches (batching::multi_epoch)
+pub const DOMAIN_EPOCH_SUBTREE: &[u8] = b"ZKMTD::Batch::EpochSubtree";
+
+// Merkle sum tree for proof of liabilities (batching::sum_tree)
+pub const DOMAIN_SUM_TREE_LEAF: &[u8] = b"ZKMTD::SumTree::Leaf";
+pub const DOMAIN_SUM_TREE_NODE: &[u8] = b"ZKMTD::SumTree::Node";
+
+// Hash-based signatures (signature::wots / signature::xmss)
+pub const DOMAIN_WOTS_SECRET: &[u8] = b"ZKMTD::WOTS::Secret";
+pub const DOMAIN_WOTS_CHAIN: &[u8] = b"ZKMTD::WOTS::Chain";
+pub const DOMAIN_XMSS_LEAF: &[u8] = b"ZKMTD::XMSS::Leaf";
+pub const DOMAIN_XMSS_MESSAGE: &[u8] = b"ZKMTD::XMSS::Message";
+pub const DOMAIN_XMSS_RANDOMIZER: &[u8] = b"ZKMTD::XMSS::Randomizer";
 
 // Solana adapter CU constants (full adapter-level proof serialization + hashing)
 pub const SOLANA_MAX_TX_SIZE: usize = 1000;
@@ -86,11 +157,54 @@ pub const ZKMTD_POSEIDON2_SEED: u64 = 0x5A4B4D54445F5032;
 pub const MIN_ENTROPY_BITS: usize = 128;
 pub const RECOMMENDED_ENTROPY_BITS: usize = 256;
 pub const MAX_BATCH_SIZE: usize = 1000;
+/// Epoch subtrees in one multi-epoch batch (one week of hourly epochs).
+pub const MAX_BATCH_EPOCHS: usize = 168;
 pub const FRI_FOLDING_FACTOR: usize = 4;
 pub const FRI_NUM_QUERIES: usize = 100;
 pub const POSEIDON_OUTPUT_SIZE: usize = 32;
 pub const MTD_PARAM_CACHE_SIZE: usize = 16;
+/// Largest configurable `ParamsCache` capacity (also bounds snapshots).
+pub const MAX_PARAM_CACHE_CAPACITY: usize = 1 << 12;
 pub const TIMESTAMP_TOLERANCE_SECS: u64 = 300;
+/// Default maximum age of a timestamped proof under a freshness policy.
+pub const DEFAULT_PROOF_MAX_AGE_SECS: u64 = 600;
+
+/// Maximum number of seed share holders (`n` in t-of-n sharing).
+pub const MAX_THRESHOLD_SHARES: usize = 16;
+/// Maximum number of replicated pieces `C(n, t-1)` a dealing may produce.
+/// Bounds dealer memory and the per-share piece count for large `n`.
+pub const MAX_THRESHOLD_PIECES: usize = 1024;
+/// Maximum epochs one threshold dealing covers (one leap year of hourly
+/// epochs).
+pub const MAX_THRESHOLD_EPOCHS: u64 = 366 * 24;
+/// Maximum number of epochs one announcement schedule may commit to
+/// (2^16 hourly epochs ≈ 7.5 years).
+pub const MAX_SCHEDULE_EPOCHS: u64 = 1 << 16;
+/// XMSS tree height bounds (2^height one-time keys per key pair).
+pub const MIN_XMSS_HEIGHT: u8 = 2;
+pub const MAX_XMSS_HEIGHT: u8 = 20;
+/// DRBG limits (SP 800-90A, HMAC_DRBG): minimum entropy input per
+/// (re)seed, maximum bytes per request, requests between reseeds.
+pub const DRBG_MIN_ENTROPY_BYTES: usize = 32;
+pub const DRBG_MAX_REQUEST_BYTES: usize = 1 << 16;
+pub const DRBG_DEFAULT_RESEED_INTERVAL: u64 = 1 << 20;
+pub const DRBG_MAX_RESEED_INTERVAL: u64 = 1 << 48;
+/// Key tree limits: path depth and bytes per path label.
+pub const MAX_KEYTREE_DEPTH: usize = 32;
+pub const MAX_KEYTREE_LABEL_LEN: usize = 64;
+/// Warped AIR layouts: per-epoch FRI query count range (60 queries at
+/// log_blowup 2 plus 8 PoW bits is 128-bit conjectured soundness) and maximum
+/// log2 trace padding factor.
+pub const WARPED_MIN_FRI_QUERIES: usize = 60;
+pub const WARPED_MAX_FRI_QUERIES: usize = 80;
+pub const MAX_LAYOUT_PADDING_LOG: u32 = 2;
+/// Maximum revoked epoch ranges plus seed fingerprints in one revocation list.
+pub const MAX_REVOCATION_ENTRIES: usize = 1 << 16;
+/// Longest credential validity range in epochs (one leap year of hourly epochs).
+pub const MAX_CREDENTIAL_EPOCHS: u64 = 366 * 24;
+/// Maximum beacon rounds walked in one chain verification (about a year of
+/// 30-second rounds); longer walks need a trusted checkpoint.
+pub const MAX_BEACON_CHAIN_ROUNDS: u64 = 1 << 20;
 
 #[cfg(test)]
 mod tests {
@@ -137,6 +251,51 @@ mod tests {
             DOMAIN_SEED_FINGERPRINT,
             DOMAIN_COMPRESSION_CHECKSUM,
             DOMAIN_SOLANA_ENTROPY,
+            DOMAIN_ENTROPY_POOL_MIX,
+            DOMAIN_ENTROPY_POOL_OUTPUT,
+            DOMAIN_DRBG_INPUT,
+            DOMAIN_DRBG_KEY,
+            DOMAIN_DRBG_VALUE,
+            DOMAIN_SNAPSHOT_ENC,
+            DOMAIN_SNAPSHOT_STREAM,
+            DOMAIN_SNAPSHOT_MAC_KEY,
+            DOMAIN_SNAPSHOT_MAC,
+            DOMAIN_TENANT_ID,
+            DOMAIN_TENANT_SALT,
+            DOMAIN_KEYTREE_MASTER_KEY,
+            DOMAIN_KEYTREE_MASTER_CHAIN,
+            DOMAIN_KEYTREE_CHILD_KEY,
+            DOMAIN_KEYTREE_CHILD_CHAIN,
+            DOMAIN_KEYTREE_PUBLIC,
+            DOMAIN_KEYTREE_FINGERPRINT,
+            DOMAIN_THRESHOLD_COMMIT,
+            DOMAIN_THRESHOLD_PRF,
+            DOMAIN_THRESHOLD_CHECK,
+            DOMAIN_THRESHOLD_PAD,
+            DOMAIN_EPOCH_ANNOUNCEMENT,
+            DOMAIN_REVOCATION_ENTRY,
+            DOMAIN_AIR_LAYOUT,
+            DOMAIN_AIR_LAYOUT_PERM,
+            DOMAIN_CREDENTIAL_RANGE,
+            DOMAIN_CREDENTIAL_PRESENTATION,
+            DOMAIN_BEACON_GENESIS,
+            DOMAIN_BEACON_ROUND,
+            DOMAIN_BEACON_ENTROPY,
+            DOMAIN_BEACON_SEED,
+            DOMAIN_MMR_NODE,
+            DOMAIN_MMR_ROOT,
+            DOMAIN_NULLIFIER,
+            DOMAIN_ONCHAIN_NULLIFIER,
+            DOMAIN_SMT_LEAF,
+            DOMAIN_SMT_NODE,
+            DOMAIN_EPOCH_SUBTREE,
+            DOMAIN_SUM_TREE_LEAF,
+            DOMAIN_SUM_TREE_NODE,
+            DOMAIN_WOTS_SECRET,
+            DOMAIN_WOTS_CHAIN,
+            DOMAIN_XMSS_LEAF,
+            DOMAIN_XMSS_MESSAGE,
+            DOMAIN_XMSS_RANDOMIZER,
         ];
 
         for i in 0..tags.len() {
@@ -3,6 +3,8 @@
 pub mod compression;
 pub mod constants;
 pub mod hash;
+#[cfg(feature = "alloc")]
+pub(crate) mod parallel;
 
 pub use compression::{select_compression_algorithm, CompressedProof, CompressionAlgorithm};
 pub use constants::*;
@@ -0,0 +1,212 @@
+//! Order-preserving data parallelism behind the `parallel` feature
+//!
+//! Each helper maps items independently and collects results in input
+//! order, so output never depends on the thread count. Without the feature
+//! they run sequentially. The `try_*` and `_until` helpers stop at the first
+//! failure in input order in both modes, so one bad item does not cost a
+//! full batch of work.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// `items.iter().map(f).collect()`, in parallel with `parallel`.
+pub(crate) fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// `items.chunks(size).map(f).collect()`, in parallel with `parallel`.
+pub(crate) fn map_chunks<T, R, F>(items: &[T], size: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&[T]) -> R + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        items.par_chunks(size).map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.chunks(size).map(f).collect()
+    }
+}
+
+/// `(0..count).map(f).collect()`, in parallel with `parallel`.
+pub(crate) fn map_indices<R, F>(count: usize, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(usize) -> R + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        (0..count).into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        (0..count).map(f).collect()
+    }
+}
+
+/// `(0..count).map(f)` up to and including the first result for which
+/// `stop` holds; later indices are `None` and `f` is not called for them.
+/// With `parallel`, indices past the earliest stop found so far are skipped
+/// and any finished late are discarded, so the output is the same as the
+/// sequential one.
+pub(crate) fn map_indices_until<R, F, S>(count: usize, f: F, stop: S) -> Vec<Option<R>>
+where
+    R: Send,
+    F: Fn(usize) -> R + Sync + Send,
+    S: Fn(&R) -> bool + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        let first = AtomicUsize::new(usize::MAX);
+        let mut results: Vec<Option<R>> = (0..count)
+            .into_par_iter()
+            .map(|i| {
+                if i > first.load(Ordering::Relaxed) {
+                    return None;
+                }
+                let result = f(i);
+                if stop(&result) {
+                    first.fetch_min(i, Ordering::Relaxed);
+                }
+                Some(result)
+            })
+            .collect();
+        let first = first.into_inner();
+        if first < count {
+            results[first + 1..].iter_mut().for_each(|r| *r = None);
+        }
+        results
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        let mut stopped = false;
+        (0..count)
+            .map(|i| {
+                if stopped {
+                    return None;
+                }
+                let result = f(i);
+                stopped = stop(&result);
+                Some(result)
+            })
+            .collect()
+    }
+}
+
+/// Whether `f` returns `Ok(true)` for every index. Stops at the first
+/// `Ok(false)` or error in index order, and returns that outcome.
+pub(crate) fn try_all_indices<E, F>(count: usize, f: F) -> Result<bool, E>
+where
+    E: Send,
+    F: Fn(usize) -> Result<bool, E> + Sync + Send,
+{
+    let results = map_indices_until(count, f, |r| !matches!(r, Ok(true)));
+    match results.into_iter().flatten().last() {
+        Some(Err(e)) => Err(e),
+        Some(Ok(false)) => Ok(false),
+        _ => Ok(true),
+    }
+}
+
+/// `(0..count).map(f).collect::<Result<Vec<_>, _>>()`, stopping at the
+/// first error in index order.
+pub(crate) fn try_map_indices<R, E, F>(count: usize, f: F) -> Result<Vec<R>, E>
+where
+    R: Send,
+    E: Send,
+    F: Fn(usize) -> Result<R, E> + Sync + Send,
+{
+    map_indices_until(count, f, Result::is_err)
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_short_circuits_at_first_failure() {
+        let calls = AtomicUsize::new(0);
+        let check = |i: usize| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            match i {
+                3 => Ok(false),
+                5 => Err(i),
+                _ => Ok(true),
+            }
+        };
+        assert_eq!(try_all_indices(100, check), Ok(false));
+        assert_eq!(try_all_indices(3, check), Ok(true));
+        assert_eq!(try_all_indices(0, check), Ok::<_, usize>(true));
+        assert_eq!(
+            try_map_indices(100, |i| if i == 5 || i == 9 { Err(i) } else { Ok(i) }),
+            Err(5)
+        );
+        assert_eq!(try_map_indices(4, Ok::<_, ()>), Ok(vec![0, 1, 2, 3]));
+
+        let until = map_indices_until(10, |i| i, |&i| i >= 4);
+        assert_eq!(until[..5], [Some(0), Some(1), Some(2), Some(3), Some(4)]);
+        assert!(until[5..].iter().all(Option::is_none));
+
+        // Sequentially nothing after the first failure is evaluated
+        #[cfg(not(feature = "parallel"))]
+        {
+            calls.store(0, Ordering::Relaxed);
+            assert_eq!(try_all_indices(100, check), Ok(false));
+            assert_eq!(calls.load(Ordering::Relaxed), 4);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod parallel_tests {
+    use super::*;
+    use crate::batching::merkle::{hash_leaf, MerkleTree};
+
+    fn with_threads<R: Send>(threads: usize, f: impl FnOnce() -> R + Send) -> R {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap()
+            .install(f)
+    }
+
+    #[test]
+    fn test_results_independent_of_thread_count() {
+        let leaves: Vec<_> = (0..1000u32).map(|i| hash_leaf(&i.to_le_bytes())).collect();
+        let root =
+            |threads| with_threads(threads, || *MerkleTree::new(leaves.clone()).unwrap().root());
+        assert_eq!(root(1), root(4));
+        assert_eq!(root(1), root(7));
+
+        let squares = with_threads(3, || map_indices(100, |i| i * i));
+        assert_eq!(squares, (0..100).map(|i| i * i).collect::<Vec<_>>());
+        assert_eq!(
+            with_threads(2, || map_chunks(&[1, 2, 3, 4, 5], 2, |c| c.len())),
+            [2, 2, 1]
+        );
+    }
+}